@@ -1,6 +1,23 @@
-use crate::config::reader;
-use crate::types::app::AppError;
+use crate::config::{
+    agents, edit, history, hooks, mcp, mcp_env, mcp_inventory, mcp_probe, memory, permissions, reader, resolver,
+    slash_commands, validation, writer,
+};
+use crate::config::resolver::{KeyProvenance, ManagedPolicyReport};
+use crate::config::spanned_json::{self, SpannedDocument, TolerantDocument};
+use crate::config::validation::FileValidation;
+use crate::config::writer::WriteOutcome;
+use crate::types::agent::SubAgent;
+use crate::types::app::{AppError, ConfigEntry};
+use crate::types::history::ConfigVersion;
+use crate::types::hook::HookInventory;
+use crate::types::mcp::{McpInventory, McpProbeResult, McpServerEntry, McpServerExpansion};
+use crate::types::memory::MemoryGraph;
+use crate::types::patch::{ConfigTarget, PatchOperation, PatchPreview};
+use crate::types::permission::PermissionEvaluation;
+use crate::types::slash_command::SlashCommand;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 #[tauri::command]
 pub async fn read_config(path: String) -> Result<String, AppError> {
@@ -18,6 +35,136 @@ pub async fn parse_config(content: String) -> Result<HashMap<String, serde_json:
     }
 }
 
+/// Parse JSON and return the source span of every node by JSON pointer
+#[tauri::command]
+pub async fn parse_config_with_spans(content: String) -> Result<SpannedDocument, AppError> {
+    reader::parse_json_spanned(&content)
+}
+
+/// Parse hand-edited JSON, returning what could be recovered, every syntax issue
+/// and a strict JSON rewrite
+#[tauri::command]
+pub async fn parse_config_tolerant(content: String) -> Result<TolerantDocument, AppError> {
+    Ok(spanned_json::parse_tolerant(&content))
+}
+
+/// Resolve the effective settings of a project by merging every scope by precedence
+#[tauri::command]
+pub async fn get_effective_config(project_path: String) -> Result<Vec<ConfigEntry>, AppError> {
+    tokio::task::spawn_blocking(move || {
+        resolver::resolve_effective_config(&PathBuf::from(project_path))
+    })
+    .await
+    .map_err(|e| AppError::Filesystem(format!("Task error: {}", e)))?
+}
+
+/// Explain every settings layer that defines a dotted key or JSON pointer
+#[tauri::command]
+pub async fn trace_config_key(project_path: String, key: String) -> Result<KeyProvenance, AppError> {
+    tokio::task::spawn_blocking(move || {
+        resolver::trace_project_key(&PathBuf::from(project_path), &key)
+    })
+    .await
+    .map_err(|e| AppError::Filesystem(format!("Task error: {}", e)))?
+}
+
+/// Show the enterprise managed policy and every key it overrides in a project
+#[tauri::command]
+pub async fn get_managed_policy(project_path: String) -> Result<ManagedPolicyReport, AppError> {
+    tokio::task::spawn_blocking(move || {
+        resolver::managed_policy_report(&PathBuf::from(project_path))
+    })
+    .await
+    .map_err(|e| AppError::Filesystem(format!("Task error: {}", e)))?
+}
+
+/// Validate a Claude Code config file against its schema
+#[tauri::command]
+pub async fn validate_config_file(path: String) -> Result<FileValidation, AppError> {
+    tokio::task::spawn_blocking(move || {
+        let content = reader::read_file(path.clone())?;
+        validation::validate_file(&PathBuf::from(&path), &content).ok_or_else(|| {
+            AppError::Parse(format!("Not a recognized Claude Code config file: {}", path))
+        })
+    })
+    .await
+    .map_err(|e| AppError::Filesystem(format!("Task error: {}", e)))?
+}
+
+/// Validate every config file of a project
+#[tauri::command]
+pub async fn validate_project_configs(project_path: String) -> Result<Vec<FileValidation>, AppError> {
+    tokio::task::spawn_blocking(move || validation::validate_project(&PathBuf::from(project_path)))
+        .await
+        .map_err(|e| AppError::Filesystem(format!("Task error: {}", e)))
+}
+
+/// Apply a JSON Patch to a config file in memory and return the diff and validation
+#[tauri::command]
+pub async fn preview_config_patch(
+    target: ConfigTarget,
+    project_path: Option<String>,
+    patch: Vec<PatchOperation>,
+) -> Result<PatchPreview, AppError> {
+    tokio::task::spawn_blocking(move || {
+        let path = edit::target_path(target, project_path.as_deref().map(Path::new))?;
+        edit::preview_patch(&path, &patch)
+    })
+    .await
+    .map_err(|e| AppError::Filesystem(format!("Task error: {}", e)))?
+}
+
+/// Write a previewed JSON Patch, if the file still matches the preview's `base_hash`
+#[tauri::command]
+pub async fn commit_config_patch(
+    target: ConfigTarget,
+    project_path: Option<String>,
+    patch: Vec<PatchOperation>,
+    base_hash: String,
+) -> Result<WriteOutcome, AppError> {
+    tokio::task::spawn_blocking(move || {
+        let path = edit::target_path(target, project_path.as_deref().map(Path::new))?;
+        edit::commit_patch(&path, &patch, &base_hash, writer::backup_dir().as_deref())
+    })
+    .await
+    .map_err(|e| AppError::Filesystem(format!("Task error: {}", e)))?
+}
+
+/// Recorded versions of a config file, newest first
+#[tauri::command]
+pub async fn list_config_versions(path: String) -> Result<Vec<ConfigVersion>, AppError> {
+    tokio::task::spawn_blocking(move || match history::history_dir() {
+        Some(history_dir) => history::list_versions(&history_dir, Path::new(&path)),
+        None => Vec::new(),
+    })
+    .await
+    .map_err(|e| AppError::Filesystem(format!("Task error: {}", e)))
+}
+
+/// Unified diff between two versions of a config file, or a version and the file on disk
+#[tauri::command]
+pub async fn diff_config_versions(path: String, from: String, to: Option<String>) -> Result<String, AppError> {
+    tokio::task::spawn_blocking(move || {
+        let history_dir = history::history_dir()
+            .ok_or_else(|| AppError::Filesystem("No data directory for version history".to_string()))?;
+        history::diff_versions(&history_dir, Path::new(&path), &from, to.as_deref())
+    })
+    .await
+    .map_err(|e| AppError::Filesystem(format!("Task error: {}", e)))?
+}
+
+/// Write a recorded version back to its config file
+#[tauri::command]
+pub async fn restore_config_version(path: String, hash: String) -> Result<WriteOutcome, AppError> {
+    tokio::task::spawn_blocking(move || {
+        let history_dir = history::history_dir()
+            .ok_or_else(|| AppError::Filesystem("No data directory for version history".to_string()))?;
+        history::restore_version(&history_dir, Path::new(&path), &hash, writer::backup_dir().as_deref())
+    })
+    .await
+    .map_err(|e| AppError::Filesystem(format!("Task error: {}", e)))?
+}
+
 #[tauri::command]
 pub fn watch_config(path: String) -> Result<(), String> {
     // TODO: Implement file watching
@@ -39,3 +186,173 @@ pub fn get_home_dir() -> Result<String, AppError> {
         .map(|p| p.to_string_lossy().to_string())
         .ok_or_else(|| AppError::Filesystem("Failed to get home directory".to_string()))
 }
+
+/// List user sub-agents and, when a project is given, the project's sub-agents
+#[tauri::command]
+pub async fn list_sub_agents(project_path: Option<String>) -> Result<Vec<SubAgent>, AppError> {
+    tokio::task::spawn_blocking(move || {
+        agents::list_agents(project_path.as_ref().map(PathBuf::from).as_deref())
+    })
+    .await
+    .map_err(|e| AppError::Filesystem(format!("Task error: {}", e)))
+}
+
+/// List user slash commands and, when a project is given, the project's commands
+#[tauri::command]
+pub async fn list_slash_commands(project_path: Option<String>) -> Result<Vec<SlashCommand>, AppError> {
+    tokio::task::spawn_blocking(move || {
+        slash_commands::list_commands(project_path.as_ref().map(PathBuf::from).as_deref())
+    })
+    .await
+    .map_err(|e| AppError::Filesystem(format!("Task error: {}", e)))
+}
+
+/// List every hook that applies to a project across all settings scopes
+#[tauri::command]
+pub async fn list_hooks(project_path: String) -> Result<HookInventory, AppError> {
+    tokio::task::spawn_blocking(move || hooks::project_hooks(&PathBuf::from(project_path)))
+        .await
+        .map_err(|e| AppError::Filesystem(format!("Task error: {}", e)))?
+}
+
+/// Decide whether a tool call such as `Bash(npm run test)` would be allowed, asked or denied
+#[tauri::command]
+pub async fn evaluate_permission(project_path: String, tool_call: String) -> Result<PermissionEvaluation, AppError> {
+    tokio::task::spawn_blocking(move || permissions::evaluate_project(&PathBuf::from(project_path), &tool_call))
+        .await
+        .map_err(|e| AppError::Filesystem(format!("Task error: {}", e)))?
+}
+
+/// Find every CLAUDE.md memory file that applies to a project and its import graph
+#[tauri::command]
+pub async fn get_memory_graph(project_path: String) -> Result<MemoryGraph, AppError> {
+    tokio::task::spawn_blocking(move || memory::project_memory(&PathBuf::from(project_path)))
+        .await
+        .map_err(|e| AppError::Filesystem(format!("Task error: {}", e)))?
+}
+
+/// List user MCP servers and, when a project is given, its project and local servers
+#[tauri::command]
+pub async fn list_mcp_servers(project_path: Option<String>) -> Result<Vec<McpServerEntry>, AppError> {
+    tokio::task::spawn_blocking(move || {
+        mcp::list_servers(project_path.as_ref().map(PathBuf::from).as_deref())
+    })
+    .await
+    .map_err(|e| AppError::Filesystem(format!("Task error: {}", e)))
+}
+
+/// Expand `${VAR}` references in a project's `.mcp.json`
+///
+/// Uses `env` when given, otherwise the app's own environment.
+#[tauri::command]
+pub async fn expand_mcp_config(
+    project_path: String,
+    env: Option<HashMap<String, String>>,
+) -> Result<Vec<McpServerExpansion>, AppError> {
+    tokio::task::spawn_blocking(move || {
+        let mcp_json = PathBuf::from(project_path).join(".mcp.json");
+        if !mcp_json.is_file() {
+            return Ok(Vec::new());
+        }
+        mcp_env::expand_mcp_json(&mcp_json, env.as_ref())
+    })
+    .await
+    .map_err(|e| AppError::Filesystem(format!("Task error: {}", e)))?
+}
+
+/// Start or connect to a configured MCP server and run the initialize handshake
+///
+/// When several scopes define the same name, the highest-precedence one is probed.
+/// A successful probe of a project's server updates its cached inventory.
+#[tauri::command]
+pub async fn probe_mcp_server(
+    project_path: Option<String>,
+    name: String,
+    timeout_ms: Option<u64>,
+) -> Result<McpProbeResult, AppError> {
+    let project_dir = project_path.map(PathBuf::from);
+    let lookup_dir = project_dir.clone();
+    let servers = tokio::task::spawn_blocking(move || mcp::list_servers(lookup_dir.as_deref()))
+        .await
+        .map_err(|e| AppError::Filesystem(format!("Task error: {}", e)))?;
+
+    let entry = servers
+        .into_iter()
+        .rev()
+        .find(|server| server.name == name)
+        .ok_or_else(|| AppError::Parse(format!("MCP server '{}' is not configured", name)))?;
+
+    let timeout = timeout_ms.map(Duration::from_millis).unwrap_or(mcp_probe::DEFAULT_PROBE_TIMEOUT);
+    let result = mcp_probe::probe_server(&entry, project_dir.as_deref(), timeout).await;
+
+    if let Some(project_dir) = project_dir {
+        store_inventory(project_dir, vec![(entry, result.clone())]).await;
+    }
+
+    Ok(result)
+}
+
+/// Probe every server a project can use and cache what each one offers
+#[tauri::command]
+pub async fn refresh_mcp_inventory(
+    project_path: String,
+    timeout_ms: Option<u64>,
+) -> Result<Vec<McpProbeResult>, AppError> {
+    let project_dir = PathBuf::from(project_path);
+    let lookup_dir = project_dir.clone();
+    let servers = tokio::task::spawn_blocking(move || mcp::list_servers(Some(&lookup_dir)))
+        .await
+        .map_err(|e| AppError::Filesystem(format!("Task error: {}", e)))?;
+
+    let timeout = timeout_ms.map(Duration::from_millis).unwrap_or(mcp_probe::DEFAULT_PROBE_TIMEOUT);
+    let mut probes = tokio::task::JoinSet::new();
+    for (index, entry) in servers.into_iter().enumerate() {
+        let cwd = project_dir.clone();
+        probes.spawn(async move {
+            let result = mcp_probe::probe_server(&entry, Some(&cwd), timeout).await;
+            (index, entry, result)
+        });
+    }
+
+    let mut results = Vec::new();
+    while let Some(joined) = probes.join_next().await {
+        results.push(joined.map_err(|e| AppError::Filesystem(format!("Task error: {}", e)))?);
+    }
+    results.sort_by_key(|(index, _, _)| *index);
+    let results: Vec<(McpServerEntry, McpProbeResult)> =
+        results.into_iter().map(|(_, entry, result)| (entry, result)).collect();
+
+    let probe_results = results.iter().map(|(_, result)| result.clone()).collect();
+    store_inventory(project_dir, results).await;
+    Ok(probe_results)
+}
+
+/// Cached inventories of the servers a project configures
+#[tauri::command]
+pub async fn get_mcp_inventory(project_path: String) -> Result<Vec<McpInventory>, AppError> {
+    tokio::task::spawn_blocking(move || {
+        let project_dir = PathBuf::from(project_path);
+        let servers = mcp::list_servers(Some(&project_dir));
+        match mcp_inventory::cache_path() {
+            Some(cache_path) => mcp_inventory::project_inventory(&cache_path, &project_dir, &servers),
+            None => Vec::new(),
+        }
+    })
+    .await
+    .map_err(|e| AppError::Filesystem(format!("Task error: {}", e)))
+}
+
+/// Cache probe results, logging rather than failing when the cache cannot be written
+async fn store_inventory(project_dir: PathBuf, probes: Vec<(McpServerEntry, McpProbeResult)>) {
+    let stored = tokio::task::spawn_blocking(move || match mcp_inventory::cache_path() {
+        Some(cache_path) => mcp_inventory::record_probes(&cache_path, &project_dir, &probes),
+        None => Ok(()),
+    })
+    .await
+    .map_err(|e| AppError::Filesystem(format!("Task error: {}", e)))
+    .and_then(|stored| stored);
+
+    if let Err(e) = stored {
+        eprintln!("Warning: Failed to cache MCP inventory: {}", e);
+    }
+}
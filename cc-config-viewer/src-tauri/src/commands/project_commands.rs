@@ -1,8 +1,18 @@
 use crate::types::app::{
-    AppError, Capability, DiffResult, DiffStatus, DiffSeverity, HighlightFilters, SummaryStats,
-    HealthStatus, HealthIssue, HealthMetrics, ProjectHealth,
+    AppError, Capability, ConfigScope, DiffResult, DiffStatus, DiffSeverity, HighlightFilters,
+    SummaryStats, HealthStatus, HealthIssue, HealthMetrics, ProjectHealth,
 };
+use crate::config::{
+    agents, claude_json, hooks, mcp, mcp_env, mcp_inventory, permission_lint, reader, slash_commands, sync, transfer, writer,
+};
+use crate::config::validation::{self, DiagnosticSeverity};
+use crate::types::claude_json::ClaudeJsonProject;
+use crate::types::error::error_codes;
+use crate::types::mcp::{EnvReferenceStatus, McpServerEntry};
+use crate::types::patch::{CapabilityTransfer, SyncDirection, SyncResult, TransferRequest};
+use crate::types::settings::ClaudeSettings;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 /// Project scanning configuration
@@ -21,8 +31,11 @@ pub struct DiscoveredProject {
     pub config_file_count: u32,
     pub last_modified: u64,
     pub config_sources: ConfigSources,
-    pub mcp_servers: Option<Vec<String>>,
+    pub mcp_servers: Option<Vec<McpServerEntry>>,
     pub sub_agents: Option<Vec<String>>,
+    pub slash_commands: Option<Vec<String>>,
+    /// The project's entry in `~/.claude.json`, if it has one
+    pub claude_json: Option<ClaudeJsonProject>,
 }
 
 /// Configuration source indicators
@@ -31,6 +44,9 @@ pub struct ConfigSources {
     pub user: bool,
     pub project: bool,
     pub local: bool,
+    /// Listed in the `projects` map of `~/.claude.json`
+    #[serde(default)]
+    pub claude_json: bool,
 }
 
 /// List all discovered projects from filesystem scan (default depth: 3)
@@ -74,7 +90,60 @@ async fn scan_projects_with_config(config: ScanConfig) -> Result<Vec<DiscoveredP
     let home_dir: PathBuf = home_dir;
 
     // Scan for projects in home directory
-    scan_directory(&home_dir, &config, 0).await
+    let mut projects = scan_directory(&home_dir, &config, 0).await?;
+
+    // Add projects Claude Code has been used in, wherever they are
+    let entries = read_claude_json_projects().await;
+    merge_claude_json_projects(&mut projects, entries).await;
+
+    Ok(projects)
+}
+
+/// Entries of the `projects` map in `~/.claude.json`
+async fn read_claude_json_projects() -> Vec<ClaudeJsonProject> {
+    tokio::task::spawn_blocking(|| {
+        let path = match claude_json::claude_json_path().filter(|path| path.is_file()) {
+            Some(path) => path,
+            None => return Vec::new(),
+        };
+
+        match claude_json::read_claude_json(&path) {
+            Ok(value) => claude_json::projects(&value, &path),
+            Err(e) => {
+                eprintln!("Warning: Failed to read {}: {}", path.display(), e);
+                Vec::new()
+            }
+        }
+    })
+    .await
+    .unwrap_or_default()
+}
+
+/// Attach `~/.claude.json` entries to scanned projects, adding projects the scan missed
+///
+/// Entries for directories that no longer exist are skipped.
+async fn merge_claude_json_projects(projects: &mut Vec<DiscoveredProject>, entries: Vec<ClaudeJsonProject>) {
+    for entry in entries {
+        let dir = PathBuf::from(&entry.path);
+        if !dir.is_dir() {
+            continue;
+        }
+
+        let existing = projects
+            .iter()
+            .position(|project| claude_json::is_same_project(&entry.path, Path::new(&project.path)));
+        let index = match existing {
+            Some(index) => index,
+            None => {
+                projects.push(describe_project(&dir).await);
+                projects.len() - 1
+            }
+        };
+
+        let project = &mut projects[index];
+        project.config_sources.claude_json = true;
+        project.claude_json = Some(entry);
+    }
 }
 
 /// Recursively scan directory for projects using a stack with depth control
@@ -141,19 +210,30 @@ async fn scan_directory(
     Ok(projects)
 }
 
-/// Check if a directory is a project (has .mcp.json or .claude/ directory)
+/// Check if a directory is a project (has .mcp.json or .claude/ settings files)
 async fn check_if_project(dir: &PathBuf) -> Result<Option<DiscoveredProject>, AppError> {
     let mcp_json = dir.join(".mcp.json");
-    let claude_dir = dir.join(".claude");
     let settings_json = dir.join(".claude").join("settings.json");
+    let settings_local_json = dir.join(".claude").join("settings.local.json");
 
     let has_mcp = mcp_json.exists() && mcp_json.is_file();
     let has_claude_settings = settings_json.exists() && settings_json.is_file();
+    let has_local_settings = settings_local_json.exists() && settings_local_json.is_file();
 
-    if !has_mcp && !has_claude_settings {
+    if !has_mcp && !has_claude_settings && !has_local_settings {
         return Ok(None);
     }
 
+    Ok(Some(describe_project(dir).await))
+}
+
+/// Collect metadata for a project directory
+async fn describe_project(dir: &Path) -> DiscoveredProject {
+    let mcp_json = dir.join(".mcp.json");
+    let has_mcp = mcp_json.is_file();
+    let has_claude_settings = dir.join(".claude").join("settings.json").is_file();
+    let has_local_settings = dir.join(".claude").join("settings.local.json").is_file();
+
     // Generate project ID from path
     let id = generate_project_id(dir);
 
@@ -177,30 +257,34 @@ async fn check_if_project(dir: &PathBuf) -> Result<Option<DiscoveredProject>, Ap
     let config_sources = ConfigSources {
         user: has_claude_settings,
         project: has_mcp,
-        local: false, // TODO: Implement local config detection
+        local: has_local_settings,
+        claude_json: false,
     };
 
-    // Count MCP servers if .mcp.json exists
+    // List MCP servers if .mcp.json exists
     let mcp_servers = if has_mcp {
-        match count_mcp_servers(mcp_json).await {
-            Ok(count) => Some(vec![format!("{} servers", count)]),
-            Err(_) => None,
-        }
+        list_mcp_servers(mcp_json).await.ok()
     } else {
         None
     };
 
-    // Count sub-agents if .claude/agents exists
-    let sub_agents = if claude_dir.exists() && claude_dir.is_dir() {
-        match count_sub_agents(claude_dir).await {
-            Ok(count) => Some(vec![format!("{} agents", count)]),
-            Err(_) => None,
-        }
+    // List sub-agents if .claude/agents exists
+    let agents_dir = agents::project_agents_dir(dir);
+    let sub_agents = if agents_dir.is_dir() {
+        list_sub_agent_names(agents_dir).await.ok()
     } else {
         None
     };
 
-    let project = DiscoveredProject {
+    // List slash commands if .claude/commands exists
+    let commands_dir = slash_commands::project_commands_dir(dir);
+    let slash_commands = if commands_dir.is_dir() {
+        list_slash_command_names(commands_dir).await.ok()
+    } else {
+        None
+    };
+
+    DiscoveredProject {
         id,
         name,
         path: dir.to_string_lossy().to_string(),
@@ -209,15 +293,15 @@ async fn check_if_project(dir: &PathBuf) -> Result<Option<DiscoveredProject>, Ap
         config_sources,
         mcp_servers,
         sub_agents,
-    };
-
-    Ok(Some(project))
+        slash_commands,
+        claude_json: None,
+    }
 }
 
 /// Count configuration files in a project
 fn count_config_files(dir: &Path) -> u32 {
     let mut count = 0;
-    let config_files = [".mcp.json", ".claude/settings.json"];
+    let config_files = [".mcp.json", ".claude/settings.json", ".claude/settings.local.json"];
 
     for config_file in &config_files {
         if dir.join(config_file).exists() {
@@ -225,49 +309,39 @@ fn count_config_files(dir: &Path) -> u32 {
         }
     }
 
+    // Agent files are validated like the other config files
+    count += agents::agent_files(&agents::project_agents_dir(dir)).len() as u32;
+
     count
 }
 
-/// Count MCP servers in .mcp.json
-async fn count_mcp_servers(mcp_path: PathBuf) -> Result<usize, AppError> {
-    let content = tokio::task::spawn_blocking(move || {
-        std::fs::read_to_string(&mcp_path).map_err(AppError::from)
+/// MCP servers defined in .mcp.json
+async fn list_mcp_servers(mcp_path: PathBuf) -> Result<Vec<McpServerEntry>, AppError> {
+    tokio::task::spawn_blocking(move || mcp::read_mcp_json(&mcp_path))
+        .await
+        .map_err(|e| AppError::Filesystem(format!("Task error: {}", e)))?
+}
+
+/// Names of the sub-agents in a .claude/agents directory
+async fn list_sub_agent_names(agents_dir: PathBuf) -> Result<Vec<String>, AppError> {
+    let agents = tokio::task::spawn_blocking(move || {
+        agents::discover_agents(&agents_dir, ConfigScope::Project)
     })
     .await
-    .map_err(|e| AppError::Filesystem(format!("Task error: {}", e)))??;
-
-    let config: serde_json::Value = serde_json::from_str(&content)
-        .map_err(AppError::from)?;
+    .map_err(|e| AppError::Filesystem(format!("Task error: {}", e)))?;
 
-    if let Some(mcp_servers) = config.get("mcpServers") {
-        if let Some(servers_obj) = mcp_servers.as_object() {
-            return Ok(servers_obj.len());
-        }
-    }
-
-    Ok(0)
+    Ok(agents.into_iter().map(|agent| agent.name).collect())
 }
 
-/// Count sub-agents in .claude/agents directory
-async fn count_sub_agents(agents_dir: PathBuf) -> Result<usize, AppError> {
-    let entries = tokio::task::spawn_blocking(move || {
-        std::fs::read_dir(&agents_dir).map_err(AppError::from)
+/// Qualified names of the slash commands in a .claude/commands directory
+async fn list_slash_command_names(commands_dir: PathBuf) -> Result<Vec<String>, AppError> {
+    let commands = tokio::task::spawn_blocking(move || {
+        slash_commands::discover_commands(&commands_dir, ConfigScope::Project)
     })
     .await
-    .map_err(|e| AppError::Filesystem(format!("Task error: {}", e)))??;
-
-    let mut count = 0;
-    for entry in entries {
-        let entry = entry.map_err(AppError::from)?;
-        let path = entry.path();
-
-        // Count .md files as agent files
-        if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("md") {
-            count += 1;
-        }
-    }
+    .map_err(|e| AppError::Filesystem(format!("Task error: {}", e)))?;
 
-    Ok(count)
+    Ok(commands.iter().map(|command| command.qualified_name()).collect())
 }
 
 /// Generate a unique ID for a project
@@ -370,6 +444,72 @@ pub async fn compare_projects(
     calculate_diff(left_capabilities, right_capabilities).await
 }
 
+/// Write selected differences of a comparison into one of the projects
+///
+/// `target_path` is the project that is changed: the right project when
+/// `direction` is left to right, the left one otherwise. Settings, permission
+/// rules, hooks and MCP servers are synced; agents and slash commands are
+/// files of their own and are skipped, as are user and managed scope. Without
+/// `base_hashes`, returns the changes without writing them; pass the
+/// `base_hashes` of that preview to write them.
+#[tauri::command]
+pub async fn sync_differences(
+    target_path: String,
+    diffs: Vec<DiffResult>,
+    direction: SyncDirection,
+    base_hashes: Option<BTreeMap<String, String>>,
+) -> Result<SyncResult, AppError> {
+    tokio::task::spawn_blocking(move || {
+        let path = PathBuf::from(&target_path);
+        if !path.is_dir() {
+            return Err(AppError::Filesystem(format!(
+                "Project path does not exist or is not a directory: {}",
+                target_path
+            )));
+        }
+        sync::sync(
+            &path,
+            dirs::home_dir().as_deref(),
+            &diffs,
+            direction,
+            base_hashes.as_ref(),
+            writer::backup_dir().as_deref(),
+        )
+    })
+    .await
+    .map_err(|e| AppError::Filesystem(format!("Task error: {}", e)))?
+}
+
+/// Move or copy a capability of a project to another scope
+///
+/// Without `base_hashes`, returns the changes without writing them; pass the
+/// `base_hashes` of that preview to write them.
+#[tauri::command]
+pub async fn transfer_capability(
+    project_path: String,
+    request: TransferRequest,
+    base_hashes: Option<BTreeMap<String, String>>,
+) -> Result<CapabilityTransfer, AppError> {
+    tokio::task::spawn_blocking(move || {
+        let path = PathBuf::from(&project_path);
+        if !path.is_dir() {
+            return Err(AppError::Filesystem(format!(
+                "Project path does not exist or is not a directory: {}",
+                project_path
+            )));
+        }
+        transfer::transfer(
+            &path,
+            dirs::home_dir().as_deref(),
+            &request,
+            base_hashes.as_ref(),
+            writer::backup_dir().as_deref(),
+        )
+    })
+    .await
+    .map_err(|e| AppError::Filesystem(format!("Task error: {}", e)))?
+}
+
 /// Extract capabilities from a project path
 async fn extract_project_capabilities(project_path: &str) -> Result<Vec<Capability>, AppError> {
     let path = PathBuf::from(project_path);
@@ -392,15 +532,80 @@ async fn extract_project_capabilities(project_path: &str) -> Result<Vec<Capabili
         }
     }
 
-    // Extract .claude/settings.json capabilities
-    let settings_path = path.join(".claude").join("settings.json");
-    if settings_path.exists() && settings_path.is_file() {
-        match extract_settings_capabilities(&settings_path).await {
+    // Extract the tools cached from probing the project's MCP servers
+    if let Some(cache_path) = mcp_inventory::cache_path() {
+        let mut caps = extract_mcp_tool_capabilities(cache_path, path.clone()).await?;
+        capabilities.append(&mut caps);
+    }
+
+    // Extract user MCP servers and the project's entry from ~/.claude.json
+    if let Some(claude_json_path) = claude_json::claude_json_path().filter(|path| path.is_file()) {
+        let project_dir = path.clone();
+        let caps = tokio::task::spawn_blocking(move || {
+            claude_json::read_claude_json(&claude_json_path)
+                .map(|value| claude_json::capabilities(&value, &project_dir))
+        })
+        .await
+        .map_err(|e| AppError::Filesystem(format!("Task error: {}", e)))?;
+
+        match caps {
             Ok(mut caps) => capabilities.append(&mut caps),
-            Err(e) => eprintln!("Warning: Failed to extract settings capabilities: {}", e),
+            Err(e) => eprintln!("Warning: Failed to extract ~/.claude.json capabilities: {}", e),
         }
     }
 
+    // Extract .claude/settings.json and .claude/settings.local.json capabilities
+    let settings_files = [
+        (ConfigScope::Project, path.join(".claude").join("settings.json")),
+        (ConfigScope::Local, path.join(".claude").join("settings.local.json")),
+    ];
+    for (scope, settings_path) in &settings_files {
+        if settings_path.exists() && settings_path.is_file() {
+            match extract_settings_capabilities(settings_path, *scope).await {
+                Ok(mut caps) => capabilities.append(&mut caps),
+                Err(e) => eprintln!(
+                    "Warning: Failed to extract {} settings capabilities: {}",
+                    scope.as_str(),
+                    e
+                ),
+            }
+        }
+    }
+
+    // Extract .claude/agents capabilities
+    let agents_dir = agents::project_agents_dir(&path);
+    if agents_dir.is_dir() {
+        let agents = tokio::task::spawn_blocking(move || {
+            agents::discover_agents(&agents_dir, ConfigScope::Project)
+        })
+        .await
+        .map_err(|e| AppError::Filesystem(format!("Task error: {}", e)))?;
+
+        capabilities.extend(agents.into_iter().map(|agent| Capability {
+            id: format!("agent.{}", agent.name),
+            key: format!("agents.{}", agent.name),
+            value: agent.definition(),
+            source: ConfigScope::Project.as_str().to_string(),
+        }));
+    }
+
+    // Extract .claude/commands capabilities
+    let commands_dir = slash_commands::project_commands_dir(&path);
+    if commands_dir.is_dir() {
+        let commands = tokio::task::spawn_blocking(move || {
+            slash_commands::discover_commands(&commands_dir, ConfigScope::Project)
+        })
+        .await
+        .map_err(|e| AppError::Filesystem(format!("Task error: {}", e)))?;
+
+        capabilities.extend(commands.into_iter().map(|command| Capability {
+            id: format!("command.{}", command.qualified_name()),
+            key: format!("commands.{}", command.qualified_name()),
+            value: command.definition(),
+            source: ConfigScope::Project.as_str().to_string(),
+        }));
+    }
+
     Ok(capabilities)
 }
 
@@ -413,8 +618,8 @@ async fn extract_mcp_capabilities(mcp_path: &PathBuf) -> Result<Vec<Capability>,
     .await
     .map_err(|e| AppError::Filesystem(format!("Task error: {}", e)))??;
 
-    let config: serde_json::Value = serde_json::from_str(&content)
-        .map_err(AppError::from)?;
+    // Syntax problems are reported by the health check's validation
+    let (config, _) = reader::parse_json_recovering(mcp_path, &content)?;
 
     let mut capabilities = Vec::new();
 
@@ -432,11 +637,66 @@ async fn extract_mcp_capabilities(mcp_path: &PathBuf) -> Result<Vec<Capability>,
         }
     }
 
+    // Variable references and literal secrets; whether a variable is set depends on
+    // the machine, so it is left to the health check and not compared
+    let lookup = |_: &str| None;
+    for expansion in mcp_env::expand_servers(mcp_path, &content, &lookup).unwrap_or_default() {
+        if expansion.references.is_empty() && expansion.literal_secrets.is_empty() {
+            continue;
+        }
+
+        let variables: std::collections::BTreeSet<&str> =
+            expansion.references.iter().map(|r| r.name.as_str()).collect();
+        let literal_secrets: Vec<&str> = expansion.literal_secrets.iter().map(|s| s.pointer.as_str()).collect();
+
+        capabilities.push(Capability {
+            id: format!("mcp-env.{}", expansion.name),
+            key: format!("mcpServers.{}.expansion", expansion.name),
+            value: serde_json::json!({
+                "variables": variables,
+                "literalSecrets": literal_secrets,
+            }),
+            source: "project".to_string(),
+        });
+    }
+
     Ok(capabilities)
 }
 
-/// Extract capabilities from .claude/settings.json file
-async fn extract_settings_capabilities(settings_path: &PathBuf) -> Result<Vec<Capability>, AppError> {
+/// Capabilities for each cached tool of the project and local MCP servers
+///
+/// User servers are shared by every project, so their tools are left out.
+async fn extract_mcp_tool_capabilities(cache_path: PathBuf, project_dir: PathBuf) -> Result<Vec<Capability>, AppError> {
+    tokio::task::spawn_blocking(move || {
+        let servers: Vec<McpServerEntry> = mcp::list_servers(Some(&project_dir))
+            .into_iter()
+            .filter(|server| server.scope != ConfigScope::User)
+            .collect();
+        let inventories = mcp_inventory::project_inventory(&cache_path, &project_dir, &servers);
+        mcp_inventory::tool_capabilities(&inventories)
+    })
+    .await
+    .map_err(|e| AppError::Filesystem(format!("Task error: {}", e)))
+}
+
+/// Build the capability ID for a settings key, prefixed with the scope for local overrides
+///
+/// The prefix keeps a project's shared and local value for the same key apart.
+/// Comparisons match by ID, so a local override is only compared with other
+/// local overrides: `model` in one project's settings.json and in another's
+/// settings.local.json show up as only-left and only-right.
+fn settings_capability_id(scope: ConfigScope, key: &str) -> String {
+    match scope {
+        ConfigScope::Local => format!("local.{}", key),
+        _ => key.to_string(),
+    }
+}
+
+/// Extract capabilities from a .claude/settings.json or .claude/settings.local.json file
+async fn extract_settings_capabilities(
+    settings_path: &PathBuf,
+    scope: ConfigScope,
+) -> Result<Vec<Capability>, AppError> {
     let settings_path_clone = settings_path.clone();
     let content = tokio::task::spawn_blocking(move || {
         std::fs::read_to_string(&settings_path_clone).map_err(AppError::from)
@@ -444,27 +704,45 @@ async fn extract_settings_capabilities(settings_path: &PathBuf) -> Result<Vec<Ca
     .await
     .map_err(|e| AppError::Filesystem(format!("Task error: {}", e)))??;
 
-    let config: serde_json::Value = serde_json::from_str(&content)
-        .map_err(AppError::from)?;
+    // Syntax problems are reported by the health check's validation
+    let (value, _) = reader::parse_json_recovering(settings_path, &content)?;
+    let (settings, invalid) = ClaudeSettings::from_value_partial(value);
 
-    let mut capabilities = Vec::new();
+    // Every setting, including unknown and mistyped keys, becomes a capability;
+    // hooks are listed per command below
+    let mut capabilities: Vec<Capability> = settings
+        .entries()
+        .into_iter()
+        .chain(invalid)
+        .filter(|(key, _)| !key.starts_with("hooks."))
+        .map(|(key, value)| Capability {
+            id: settings_capability_id(scope, &key),
+            key,
+            value,
+            source: scope.as_str().to_string(),
+        })
+        .collect();
 
-    // Extract various settings
-    if let Some(allowed_tools) = config.get("allowedTools") {
-        capabilities.push(Capability {
-            id: "allowedTools".to_string(),
-            key: "allowedTools".to_string(),
-            value: allowed_tools.clone(),
-            source: "user".to_string(),
-        });
-    }
+    // Number hooks per event and matcher so the same hook lines up across projects
+    let mut positions: std::collections::HashMap<(String, Option<String>), usize> =
+        std::collections::HashMap::new();
+    for hook in hooks::hooks_from_settings(&settings, scope, settings_path) {
+        let position = positions
+            .entry((hook.event.clone(), hook.matcher.clone()))
+            .or_insert(0);
+        let key = format!(
+            "hooks.{}.{}.{}",
+            hook.event,
+            hook.matcher.as_deref().unwrap_or("*"),
+            position
+        );
+        *position += 1;
 
-    if let Some(disallowed_tools) = config.get("disallowedTools") {
         capabilities.push(Capability {
-            id: "disallowedTools".to_string(),
-            key: "disallowedTools".to_string(),
-            value: disallowed_tools.clone(),
-            source: "user".to_string(),
+            id: settings_capability_id(scope, &key),
+            key,
+            value: hook.definition(),
+            source: scope.as_str().to_string(),
         });
     }
 
@@ -488,8 +766,8 @@ pub async fn calculate_diff(
     // Process left capabilities
     for left_cap in &left_capabilities {
         if let Some(right_cap) = right_map.get(&left_cap.id) {
-            // Capability exists in both - compare values
-            if left_cap.value == right_cap.value {
+            // Capability exists in both - compare values through the settings model
+            if ClaudeSettings::entry_eq(&left_cap.key, &left_cap.value, &right_cap.value) {
                 // Values match
                 diffs.push(DiffResult {
                     capability_id: left_cap.id.clone(),
@@ -630,17 +908,26 @@ pub async fn filter_capabilities(
 #[tauri::command]
 pub async fn health_check_project(
     project_path: String,
+) -> Result<ProjectHealth, AppError> {
+    let managed = reader::managed_settings_path();
+    project_health(Path::new(&project_path), dirs::home_dir().as_deref(), Some(&managed)).await
+}
+
+/// Check a project's health with user settings and `~/.claude.json` read
+/// from `home` and the managed policy from `managed`
+async fn project_health(
+    path: &Path,
+    home: Option<&Path>,
+    managed: Option<&Path>,
 ) -> Result<ProjectHealth, AppError> {
     use crate::types::app::{
         HealthIssue, HealthMetrics, HealthStatus, ProjectHealth,
     };
 
-    let path = Path::new(&project_path);
-
     // Check if project directory exists
     if !path.exists() {
         return Err(AppError::Filesystem(
-            format!("Project path does not exist: {}", project_path)
+            format!("Project path does not exist: {}", path.display())
         ));
     }
 
@@ -651,11 +938,138 @@ pub async fn health_check_project(
     let mut errors = 0;
     let mut issues = Vec::new();
 
-    // TODO: In full implementation, validate config files and detect issues
-    // For now, create basic health based on config count
+    // Validate config files against their schemas
+    for file in validation::validate_project(path) {
+        if !file.valid {
+            invalid_configs += 1;
+        }
+
+        for diagnostic in &file.diagnostics {
+            let (type_, severity) = match diagnostic.severity {
+                DiagnosticSeverity::Error => {
+                    errors += 1;
+                    ("error", DiffSeverity::High)
+                }
+                DiagnosticSeverity::Warning => {
+                    warnings += 1;
+                    ("warning", DiffSeverity::Medium)
+                }
+                DiagnosticSeverity::Info => continue,
+            };
+
+            issues.push(HealthIssue {
+                id: format!("{}:{}#{}", diagnostic.code, file.path, diagnostic.pointer),
+                type_: type_.to_string(),
+                severity,
+                message: diagnostic.message.clone(),
+                details: Some(format!(
+                    "{}:{}:{}",
+                    file.path,
+                    diagnostic.line.unwrap_or(1),
+                    diagnostic.column.unwrap_or(1)
+                )),
+                project_id: generate_project_id(path),
+            });
+        }
+    }
+
+    // Parts of the project's entry in ~/.claude.json may have been ignored
+    if let Some(claude_json_path) = home.map(|home| home.join(".claude.json")) {
+        let file = claude_json_path.to_string_lossy().to_string();
+        let project_dir = path.to_path_buf();
+        let diagnostics = tokio::task::spawn_blocking(move || {
+            claude_json::project_syntax_diagnostics(&claude_json_path, &project_dir)
+        })
+        .await
+        .map_err(|e| AppError::Filesystem(format!("Task error: {}", e)))?;
+
+        for diagnostic in diagnostics {
+            issues.push(HealthIssue {
+                id: format!("{}:{}#{}", diagnostic.code, file, diagnostic.pointer),
+                type_: "warning".to_string(),
+                severity: DiffSeverity::Medium,
+                message: format!("{}; the rest of the file was read", diagnostic.message),
+                details: Some(format!(
+                    "{}:{}:{}",
+                    file,
+                    diagnostic.line.unwrap_or(1),
+                    diagnostic.column.unwrap_or(1)
+                )),
+                project_id: generate_project_id(path),
+            });
+            warnings += 1;
+        }
+    }
+
+    // Lint permission rules across every scope
+    let project_id = generate_project_id(path);
+    let lint_dir = path.to_path_buf();
+    let lint_home = home.map(Path::to_path_buf);
+    let lint_managed = managed.map(Path::to_path_buf);
+    let lint_id = project_id.clone();
+    let permission_issues = tokio::task::spawn_blocking(move || {
+        permission_lint::lint_project(&lint_dir, lint_home.as_deref(), lint_managed.as_deref(), &lint_id)
+    })
+    .await
+    .map_err(|e| AppError::Filesystem(format!("Task error: {}", e)))?;
+    match permission_issues {
+        Ok(permission_issues) => {
+            warnings += permission_issues.len() as u32;
+            issues.extend(permission_issues);
+        }
+        Err(e) => eprintln!("Warning: Failed to lint permission rules: {}", e),
+    }
+
+    // Check variable references and literal secrets in .mcp.json
+    let mcp_json = path.join(".mcp.json");
+    let mut unresolved_references = 0;
+    if mcp_json.is_file() {
+        let expansions = tokio::task::spawn_blocking(move || mcp_env::expand_mcp_json(&mcp_json, None))
+            .await
+            .map_err(|e| AppError::Filesystem(format!("Task error: {}", e)))?;
+        // Parse failures are already reported by validation
+        for expansion in expansions.unwrap_or_default() {
+            let location = |pointer: &str, line: Option<u32>, column: Option<u32>| {
+                (
+                    format!("{}#{}", expansion.path, pointer),
+                    format!("{}:{}:{}", expansion.path, line.unwrap_or(1), column.unwrap_or(1)),
+                )
+            };
+
+            for reference in expansion.references.iter().filter(|r| r.status == EnvReferenceStatus::Unresolved) {
+                let (target, details) = location(&reference.pointer, reference.line, reference.column);
+                issues.push(HealthIssue {
+                    id: format!("{}:{}", error_codes::MC001, target),
+                    type_: "error".to_string(),
+                    severity: DiffSeverity::High,
+                    message: format!(
+                        "MCP server '{}' references ${{{}}}, which is not set and has no default",
+                        expansion.name, reference.name
+                    ),
+                    details: Some(details),
+                    project_id: project_id.clone(),
+                });
+                unresolved_references += 1;
+            }
+
+            for secret in &expansion.literal_secrets {
+                let (target, details) = location(&secret.pointer, secret.line, secret.column);
+                issues.push(HealthIssue {
+                    id: format!("{}:{}", error_codes::MC002, target),
+                    type_: "warning".to_string(),
+                    severity: DiffSeverity::High,
+                    message: format!("MCP server '{}': {}", expansion.name, secret.reason),
+                    details: Some(details),
+                    project_id: project_id.clone(),
+                });
+                warnings += 1;
+            }
+        }
+    }
+    errors += unresolved_references;
 
     // Determine health status based on config count
-    let (status, score) = if config_count == 0 {
+    let (mut status, mut score) = if config_count == 0 {
         (
             HealthStatus::Error,
             0.0,
@@ -672,8 +1086,20 @@ pub async fn health_check_project(
         )
     };
 
-    // If error status, add an issue
-    if matches!(status, HealthStatus::Error) {
+    // Invalid files and warnings downgrade the status
+    if invalid_configs > 0 {
+        status = HealthStatus::Error;
+        score = (score - invalid_configs as f64 * 25.0).max(0.0);
+    } else if unresolved_references > 0 {
+        status = HealthStatus::Error;
+        score = (score - unresolved_references as f64 * 10.0).max(0.0);
+    } else if warnings > 0 && status == HealthStatus::Good {
+        status = HealthStatus::Warning;
+        score = (score - warnings as f64 * 2.0).max(0.0);
+    }
+
+    // If no config files were found, add an issue
+    if config_count == 0 {
         issues.push(HealthIssue {
             id: "no-configs".to_string(),
             type_: "error".to_string(),
@@ -687,7 +1113,7 @@ pub async fn health_check_project(
 
     let metrics = HealthMetrics {
         total_capabilities: config_count as u32,
-        valid_configs: config_count as u32,
+        valid_configs: config_count.saturating_sub(invalid_configs),
         invalid_configs,
         warnings,
         errors,
@@ -712,6 +1138,19 @@ pub async fn health_check_project(
     })
 }
 
+/// Report shadowed, redundant, duplicate and unknown permission rules across every scope
+#[tauri::command]
+pub async fn lint_permissions(project_path: String) -> Result<Vec<HealthIssue>, AppError> {
+    tokio::task::spawn_blocking(move || {
+        let path = PathBuf::from(project_path);
+        let managed = reader::managed_settings_path();
+        let project_id = generate_project_id(&path);
+        permission_lint::lint_project(&path, dirs::home_dir().as_deref(), Some(&managed), &project_id)
+    })
+    .await
+    .map_err(|e| AppError::Filesystem(format!("Task error: {}", e)))?
+}
+
 /// Calculate health metrics for multiple projects
 #[tauri::command]
 pub async fn calculate_health_metrics(
@@ -742,6 +1181,7 @@ pub async fn refresh_all_project_health(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::mcp::McpServer;
 
     #[tokio::test]
     async fn test_generate_project_id() {
@@ -768,6 +1208,30 @@ mod tests {
         std::fs::create_dir_all(&claude_dir).unwrap();
         std::fs::write(claude_dir.join("settings.json"), "{}").unwrap();
         assert_eq!(count_config_files(dir), 2);
+
+        // Add .claude/settings.local.json
+        std::fs::write(claude_dir.join("settings.local.json"), "{}").unwrap();
+        assert_eq!(count_config_files(dir), 3);
+
+        // Add an agent
+        std::fs::create_dir_all(claude_dir.join("agents")).unwrap();
+        std::fs::write(claude_dir.join("agents").join("reviewer.md"), "---\nname: reviewer\n---\n").unwrap();
+        assert_eq!(count_config_files(dir), 4);
+    }
+
+    #[tokio::test]
+    async fn test_health_check_counts_agent_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let agents_dir = temp_dir.path().join(".claude").join("agents");
+        std::fs::create_dir_all(&agents_dir).unwrap();
+        std::fs::write(temp_dir.path().join(".claude").join("settings.json"), "{}").unwrap();
+        std::fs::write(agents_dir.join("broken.md"), "No frontmatter").unwrap();
+
+        let health = health_check_project(temp_dir.path().to_string_lossy().to_string()).await.unwrap();
+
+        assert_eq!(health.metrics.total_capabilities, 2);
+        assert_eq!(health.metrics.invalid_configs, 1);
+        assert_eq!(health.metrics.valid_configs, 1);
     }
 
     #[tokio::test]
@@ -787,31 +1251,74 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_count_mcp_servers() {
+    async fn test_check_if_project_with_local_settings() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path().to_path_buf();
+        let claude_dir = dir.join(".claude");
+        std::fs::create_dir_all(&claude_dir).unwrap();
+        std::fs::write(claude_dir.join("settings.local.json"), "{}").unwrap();
+
+        let project = check_if_project(&dir).await.unwrap().unwrap();
+        assert_eq!(project.config_file_count, 1);
+        assert!(project.config_sources.local);
+        assert!(!project.config_sources.project);
+    }
+
+    #[tokio::test]
+    async fn test_extract_project_capabilities_includes_local_settings() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let claude_dir = temp_dir.path().join(".claude");
+        std::fs::create_dir_all(&claude_dir).unwrap();
+        std::fs::write(claude_dir.join("settings.json"), r#"{"allowedTools": ["Read"]}"#).unwrap();
+        std::fs::write(
+            claude_dir.join("settings.local.json"),
+            r#"{"allowedTools": ["Bash(npm test)"]}"#,
+        )
+        .unwrap();
+
+        let capabilities = extract_project_capabilities(&temp_dir.path().to_string_lossy())
+            .await
+            .unwrap();
+
+        let project = capabilities.iter().find(|c| c.id == "allowedTools").unwrap();
+        assert_eq!(project.source, "project");
+        let local = capabilities.iter().find(|c| c.id == "local.allowedTools").unwrap();
+        assert_eq!(local.source, "local");
+        assert_eq!(local.value, serde_json::json!(["Bash(npm test)"]));
+    }
+
+    #[tokio::test]
+    async fn test_list_mcp_servers() {
         let temp_dir = tempfile::tempdir().unwrap();
         let mcp_path = temp_dir.path().join(".mcp.json");
 
         // Empty mcpServers
         std::fs::write(&mcp_path, r#"{"mcpServers": {}}"#).unwrap();
-        assert_eq!(count_mcp_servers(mcp_path.clone()).await.unwrap(), 0);
+        assert!(list_mcp_servers(mcp_path.clone()).await.unwrap().is_empty());
 
         // With servers
         std::fs::write(
             &mcp_path,
             r#"{
                 "mcpServers": {
-                    "server1": {},
-                    "server2": {},
-                    "server3": {}
+                    "server1": {"command": "npx", "args": ["server1"]},
+                    "server2": {"type": "sse", "url": "http://localhost:3000/sse"},
+                    "server3": {"type": "http", "url": "https://example.com/mcp"}
                 }
             }"#,
         )
         .unwrap();
-        assert_eq!(count_mcp_servers(mcp_path).await.unwrap(), 3);
+        let servers = list_mcp_servers(mcp_path).await.unwrap();
+        let names: Vec<&str> = servers.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["server1", "server2", "server3"]);
+        assert!(matches!(servers[0].server, McpServer::Stdio { .. }));
+        assert!(matches!(servers[1].server, McpServer::Sse { .. }));
+        assert!(matches!(servers[2].server, McpServer::Http { .. }));
+        assert!(servers.iter().all(|s| s.scope == ConfigScope::Project));
     }
 
     #[tokio::test]
-    async fn test_count_sub_agents() {
+    async fn test_list_sub_agent_names() {
         let temp_dir = tempfile::tempdir().unwrap();
         let agents_dir = temp_dir.path().join("agents");
 
@@ -819,14 +1326,26 @@ mod tests {
         std::fs::create_dir_all(&agents_dir).unwrap();
 
         // No agent files yet
-        assert_eq!(count_sub_agents(agents_dir.clone()).await.unwrap(), 0);
+        assert!(list_sub_agent_names(agents_dir.clone()).await.unwrap().is_empty());
 
         // Add agent files
         std::fs::write(agents_dir.join("agent1.md"), "# Agent 1").unwrap();
-        std::fs::write(agents_dir.join("agent2.md"), "# Agent 2").unwrap();
+        std::fs::write(agents_dir.join("agent2.md"), "---\nname: reviewer\n---\n# Agent 2").unwrap();
         std::fs::write(agents_dir.join("readme.txt"), "Not an agent").unwrap();
 
-        assert_eq!(count_sub_agents(agents_dir).await.unwrap(), 2);
+        assert_eq!(list_sub_agent_names(agents_dir).await.unwrap(), vec!["agent1", "reviewer"]);
+    }
+
+    #[tokio::test]
+    async fn test_check_if_project_lists_sub_agents() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let agents_dir = temp_dir.path().join(".claude").join("agents");
+        std::fs::create_dir_all(&agents_dir).unwrap();
+        std::fs::write(temp_dir.path().join(".claude").join("settings.json"), "{}").unwrap();
+        std::fs::write(agents_dir.join("reviewer.md"), "---\nname: code-reviewer\n---\nReview").unwrap();
+
+        let project = check_if_project(&temp_dir.path().to_path_buf()).await.unwrap().unwrap();
+        assert_eq!(project.sub_agents, Some(vec!["code-reviewer".to_string()]));
     }
 
     #[tokio::test]
@@ -921,6 +1440,53 @@ mod tests {
         assert_eq!(result[0].highlight_class, Some("bg-yellow-100 text-yellow-800".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_calculate_diff_compares_settings_by_model() {
+        let allow = |rules: serde_json::Value| Capability {
+            id: "permissions.allow".to_string(),
+            key: "permissions.allow".to_string(),
+            value: rules,
+            source: "project".to_string(),
+        };
+
+        let result = calculate_diff(
+            vec![allow(serde_json::json!(["Read", "Bash(npm test)"]))],
+            vec![allow(serde_json::json!(["Bash(npm test)", "Read"]))],
+        )
+        .await
+        .unwrap();
+        assert_eq!(result[0].status, DiffStatus::Match);
+
+        let result = calculate_diff(
+            vec![allow(serde_json::json!(["Read"]))],
+            vec![allow(serde_json::json!(["Read", "Edit"]))],
+        )
+        .await
+        .unwrap();
+        assert_eq!(result[0].status, DiffStatus::Different);
+    }
+
+    #[tokio::test]
+    async fn test_calculate_diff_keeps_local_overrides_apart() {
+        let shared = tempfile::tempdir().unwrap();
+        let local = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(shared.path().join(".claude")).unwrap();
+        std::fs::create_dir_all(local.path().join(".claude")).unwrap();
+        std::fs::write(shared.path().join(".claude").join("settings.json"), r#"{"model": "opus"}"#).unwrap();
+        std::fs::write(local.path().join(".claude").join("settings.local.json"), r#"{"model": "opus"}"#).unwrap();
+
+        let left = extract_project_capabilities(&shared.path().to_string_lossy()).await.unwrap();
+        let right = extract_project_capabilities(&local.path().to_string_lossy()).await.unwrap();
+        let result = calculate_diff(left, right).await.unwrap();
+        let statuses: Vec<(&str, &DiffStatus)> = result
+            .iter()
+            .filter(|diff| diff.capability_id.ends_with("model"))
+            .map(|diff| (diff.capability_id.as_str(), &diff.status))
+            .collect();
+
+        assert_eq!(statuses, vec![("model", &DiffStatus::OnlyLeft), ("local.model", &DiffStatus::OnlyRight)]);
+    }
+
     #[tokio::test]
     async fn test_calculate_diff_only_left() {
         let left_capabilities = vec![
@@ -1272,6 +1838,378 @@ mod tests {
         assert_eq!(health.metrics.total_capabilities, 1);
     }
 
+    #[tokio::test]
+    async fn test_health_check_project_invalid_settings() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path();
+        let claude_dir = dir.join(".claude");
+        std::fs::create_dir_all(&claude_dir).unwrap();
+        std::fs::write(claude_dir.join("settings.json"), r#"{"permissions": {"allow": "Bash"}}"#).unwrap();
+        std::fs::write(dir.join(".mcp.json"), "{}").unwrap();
+
+        let health = health_check_project(dir.to_string_lossy().to_string()).await.unwrap();
+
+        assert!(matches!(health.status, HealthStatus::Error));
+        assert_eq!(health.metrics.invalid_configs, 1);
+        assert_eq!(health.metrics.valid_configs, 1);
+        assert_eq!(health.issues[0].type_, "error");
+        assert!(health.issues[0].id.starts_with("PR002:"));
+        assert!(health.issues[0].id.ends_with("#/permissions/allow"));
+    }
+
+    #[tokio::test]
+    async fn test_health_check_project_reports_typos() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path();
+        let claude_dir = dir.join(".claude");
+        std::fs::create_dir_all(&claude_dir).unwrap();
+        std::fs::write(claude_dir.join("settings.json"), "{\n  \"permisions\": {}\n}").unwrap();
+        let home = tempfile::tempdir().unwrap();
+
+        let health = project_health(dir, Some(home.path()), None).await.unwrap();
+
+        assert!(matches!(health.status, HealthStatus::Error));
+        let typo = health.issues.iter().find(|issue| issue.id.starts_with("PR003:")).unwrap();
+        assert_eq!(typo.type_, "error");
+        assert!(typo.message.contains("permissions"));
+        assert!(typo.details.as_ref().unwrap().ends_with("settings.json:2:3"));
+    }
+
+    #[tokio::test]
+    async fn test_extract_project_capabilities_includes_agents() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let agents_dir = temp_dir.path().join(".claude").join("agents");
+        std::fs::create_dir_all(&agents_dir).unwrap();
+        std::fs::write(
+            agents_dir.join("reviewer.md"),
+            "---\nname: code-reviewer\ndescription: Reviews code\ntools: Read, Grep\n---\nBe thorough.",
+        )
+        .unwrap();
+
+        let capabilities = extract_project_capabilities(&temp_dir.path().to_string_lossy())
+            .await
+            .unwrap();
+
+        let agent = capabilities.iter().find(|c| c.id == "agent.code-reviewer").unwrap();
+        assert_eq!(agent.key, "agents.code-reviewer");
+        assert_eq!(agent.source, "project");
+        assert_eq!(agent.value["tools"], serde_json::json!(["Read", "Grep"]));
+        assert_eq!(agent.value["prompt"], "Be thorough.");
+        assert!(agent.value.get("path").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_check_if_project_lists_slash_commands() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let commands_dir = temp_dir.path().join(".claude").join("commands");
+        std::fs::create_dir_all(commands_dir.join("git")).unwrap();
+        std::fs::write(temp_dir.path().join(".claude").join("settings.json"), "{}").unwrap();
+        std::fs::write(commands_dir.join("review.md"), "Review the diff").unwrap();
+        std::fs::write(commands_dir.join("git").join("commit.md"), "Commit").unwrap();
+
+        let project = check_if_project(&temp_dir.path().to_path_buf()).await.unwrap().unwrap();
+        assert_eq!(
+            project.slash_commands,
+            Some(vec!["git:commit".to_string(), "review".to_string()])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_merge_claude_json_projects() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let scanned = temp_dir.path().join("scanned");
+        let unmarked = temp_dir.path().join("unmarked");
+        std::fs::create_dir_all(&scanned).unwrap();
+        std::fs::create_dir_all(&unmarked).unwrap();
+        std::fs::write(scanned.join(".mcp.json"), r#"{"mcpServers": {}}"#).unwrap();
+
+        let claude_json_path = temp_dir.path().join(".claude.json");
+        let value = serde_json::json!({
+            "projects": {
+                format!("{}/", scanned.display()): {"allowedTools": ["Read"]},
+                unmarked.to_string_lossy(): {"hasTrustDialogAccepted": true},
+                temp_dir.path().join("deleted").to_string_lossy(): {}
+            }
+        });
+        let entries = claude_json::projects(&value, &claude_json_path);
+
+        let mut projects = vec![check_if_project(&scanned).await.unwrap().unwrap()];
+        merge_claude_json_projects(&mut projects, entries).await;
+
+        assert_eq!(projects.len(), 2);
+        assert!(projects[0].config_sources.project);
+        assert!(projects[0].config_sources.claude_json);
+        assert_eq!(projects[0].claude_json.as_ref().unwrap().allowed_tools, vec!["Read"]);
+
+        assert_eq!(projects[1].path, unmarked.to_string_lossy());
+        assert_eq!(projects[1].config_file_count, 0);
+        assert!(projects[1].config_sources.claude_json);
+        assert!(projects[1].claude_json.as_ref().unwrap().has_trust_dialog_accepted);
+    }
+
+    #[tokio::test]
+    async fn test_compare_projects_reports_slash_commands() {
+        let left = tempfile::tempdir().unwrap();
+        let right = tempfile::tempdir().unwrap();
+        for dir in [left.path(), right.path()] {
+            let commands_dir = dir.join(".claude").join("commands");
+            std::fs::create_dir_all(&commands_dir).unwrap();
+            std::fs::write(commands_dir.join("test.md"), "---\ndescription: Run tests\n---\nnpm test").unwrap();
+        }
+        std::fs::write(
+            left.path().join(".claude").join("commands").join("deploy.md"),
+            "---\nargument-hint: [env]\n---\nDeploy to $1",
+        )
+        .unwrap();
+
+        let diffs = compare_projects(
+            left.path().to_string_lossy().to_string(),
+            right.path().to_string_lossy().to_string(),
+        )
+        .await
+        .unwrap();
+
+        let deploy = diffs.iter().find(|d| d.capability_id == "command.deploy").unwrap();
+        assert_eq!(deploy.status, DiffStatus::OnlyLeft);
+        assert_eq!(deploy.left_value.as_ref().unwrap().value["argumentHint"], "[env]");
+
+        let test = diffs.iter().find(|d| d.capability_id == "command.test").unwrap();
+        assert_eq!(test.status, DiffStatus::Match);
+    }
+
+    #[tokio::test]
+    async fn test_mcp_tool_capabilities_differ_between_projects() {
+        use crate::types::mcp::{McpProbeResult, McpTool};
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let cache_path = temp_dir.path().join("mcp-inventory.json");
+        let left = temp_dir.path().join("left");
+        let right = temp_dir.path().join("right");
+
+        for (project, tools) in [(&left, vec!["search", "create_issue"]), (&right, vec!["search"])] {
+            std::fs::create_dir_all(project).unwrap();
+            std::fs::write(project.join(".mcp.json"), r#"{"mcpServers": {"github": {"command": "gh-mcp"}}}"#).unwrap();
+
+            let entry = mcp::read_mcp_json(&project.join(".mcp.json")).unwrap().remove(0);
+            let result = McpProbeResult {
+                name: "github".to_string(),
+                transport: "stdio".to_string(),
+                success: true,
+                server_name: None,
+                server_version: None,
+                protocol_version: None,
+                capabilities: None,
+                tools: Some(tools.into_iter().map(|name| McpTool { name: name.to_string(), description: None }).collect()),
+                prompts: None,
+                resources: None,
+                list_errors: Vec::new(),
+                error: None,
+                stderr: None,
+                duration_ms: 1,
+            };
+            mcp_inventory::record_probes(&cache_path, project, &[(entry, result)]).unwrap();
+        }
+
+        let left_caps = extract_mcp_tool_capabilities(cache_path.clone(), left).await.unwrap();
+        let right_caps = extract_mcp_tool_capabilities(cache_path, right).await.unwrap();
+        let diff = calculate_diff(left_caps, right_caps).await.unwrap();
+
+        let only_left: Vec<&str> = diff
+            .iter()
+            .filter(|d| d.status == DiffStatus::OnlyLeft)
+            .map(|d| d.capability_id.as_str())
+            .collect();
+        assert_eq!(only_left, vec!["mcp__github__create_issue"]);
+        assert!(diff.iter().any(|d| d.capability_id == "mcp__github__search" && d.status == DiffStatus::Match));
+    }
+
+    #[tokio::test]
+    async fn test_extract_project_capabilities_recovers_jsonc() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            temp_dir.path().join(".mcp.json"),
+            "{\n  // shared servers\n  \"mcpServers\": {\n    \"github\": {\"command\": \"gh\"},\n  },\n}",
+        )
+        .unwrap();
+
+        let capabilities = extract_project_capabilities(&temp_dir.path().to_string_lossy())
+            .await
+            .unwrap();
+
+        let github = capabilities.iter().find(|c| c.id == "mcp.github").unwrap();
+        assert_eq!(github.value, serde_json::json!({"command": "gh"}));
+    }
+
+    #[tokio::test]
+    async fn test_extract_settings_capabilities_typed() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let settings_path = temp_dir.path().join("settings.json");
+        std::fs::write(
+            &settings_path,
+            r#"{
+                "model": "opus",
+                "env": {"NODE_ENV": "test"},
+                "permissions": {"allow": ["Read"], "deny": ["WebFetch"], "defaultMode": "plan"},
+                "statusLine": {"type": "command", "command": "status.sh"}
+            }"#,
+        )
+        .unwrap();
+
+        let capabilities = extract_settings_capabilities(&settings_path, ConfigScope::Project)
+            .await
+            .unwrap();
+        let ids: Vec<&str> = capabilities.iter().map(|c| c.id.as_str()).collect();
+
+        assert!(ids.contains(&"model"));
+        assert!(ids.contains(&"env.NODE_ENV"));
+        assert!(ids.contains(&"permissions.allow"));
+        assert!(ids.contains(&"permissions.deny"));
+        assert!(ids.contains(&"permissions.defaultMode"));
+        assert!(ids.contains(&"statusLine"));
+        assert!(capabilities.iter().all(|c| c.source == "project"));
+    }
+
+    #[tokio::test]
+    async fn test_extract_settings_capabilities_keeps_mistyped_settings() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let settings_path = temp_dir.path().join("settings.json");
+        std::fs::write(
+            &settings_path,
+            r#"{"model": 5, "permissions": {"allow": "Bash", "deny": ["WebFetch"]}}"#,
+        )
+        .unwrap();
+
+        let capabilities = extract_settings_capabilities(&settings_path, ConfigScope::Project)
+            .await
+            .unwrap();
+        let entries: Vec<(&str, &serde_json::Value)> =
+            capabilities.iter().map(|c| (c.id.as_str(), &c.value)).collect();
+
+        assert_eq!(
+            entries,
+            vec![
+                ("permissions.deny", &serde_json::json!(["WebFetch"])),
+                ("model", &serde_json::json!(5)),
+                ("permissions.allow", &serde_json::json!("Bash")),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_extract_settings_capabilities_lists_hooks() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let settings_path = temp_dir.path().join("settings.local.json");
+        std::fs::write(
+            &settings_path,
+            r#"{"hooks": {
+                "PreToolUse": [
+                    {"matcher": "Bash", "hooks": [{"type": "command", "command": "./audit.sh"}]},
+                    {"matcher": "Bash", "hooks": [{"type": "command", "command": "./lint.sh", "timeout": 5}]}
+                ],
+                "Stop": [{"hooks": [{"type": "command", "command": "notify-send done"}]}]
+            }}"#,
+        )
+        .unwrap();
+
+        let capabilities = extract_settings_capabilities(&settings_path, ConfigScope::Local)
+            .await
+            .unwrap();
+        let ids: Vec<&str> = capabilities.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec!["local.hooks.PreToolUse.Bash.0", "local.hooks.PreToolUse.Bash.1", "local.hooks.Stop.*.0"]
+        );
+        assert_eq!(capabilities[1].value["command"], "./lint.sh");
+        assert_eq!(capabilities[1].value["timeout"], 5);
+        assert_eq!(capabilities[2].value["matcher"], serde_json::Value::Null);
+    }
+
+    #[tokio::test]
+    async fn test_health_check_project_reports_permission_lint() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path();
+        std::fs::write(dir.join(".mcp.json"), "{}").unwrap();
+        std::fs::create_dir_all(dir.join(".claude")).unwrap();
+        std::fs::write(
+            dir.join(".claude").join("settings.json"),
+            r#"{"permissions": {"allow": ["Bash(git push:*)"], "deny": ["Bash"]}}"#,
+        )
+        .unwrap();
+
+        let home = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(home.path().join(".claude")).unwrap();
+        std::fs::write(
+            home.path().join(".claude").join("settings.json"),
+            r#"{"permissions": {"allow": ["Read", "Read"]}}"#,
+        )
+        .unwrap();
+
+        let health = project_health(dir, Some(home.path()), None).await.unwrap();
+        let lint: Vec<&HealthIssue> = health.issues.iter().filter(|issue| issue.id.starts_with("PL")).collect();
+        assert_eq!(lint.len(), 2);
+        let denied = lint.iter().find(|issue| issue.id.starts_with("PL004")).unwrap();
+        assert!(denied.message.contains("always denied by 'Bash'"));
+        let duplicate = lint.iter().find(|issue| issue.id.starts_with("PL005")).unwrap();
+        assert!(duplicate.details.as_ref().unwrap().starts_with(&*home.path().to_string_lossy()));
+        assert_eq!(health.status, HealthStatus::Warning);
+    }
+
+    #[tokio::test]
+    async fn test_health_check_project_reports_mcp_env_issues() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path();
+        std::fs::write(
+            dir.join(".mcp.json"),
+            r#"{
+  "mcpServers": {
+    "github": {
+      "command": "npx",
+      "env": {
+        "GITHUB_TOKEN": "${CC_CONFIG_VIEWER_UNSET_TOKEN}",
+        "API_KEY": "literal-key-value"
+      }
+    }
+  }
+}"#,
+        )
+        .unwrap();
+        std::fs::create_dir_all(dir.join(".claude")).unwrap();
+        std::fs::write(dir.join(".claude").join("settings.json"), "{}").unwrap();
+        let home = tempfile::tempdir().unwrap();
+
+        let health = project_health(dir, Some(home.path()), None).await.unwrap();
+        let unresolved = health.issues.iter().find(|issue| issue.id.starts_with("MC001")).unwrap();
+        assert_eq!(unresolved.type_, "error");
+        assert!(unresolved.message.contains("${CC_CONFIG_VIEWER_UNSET_TOKEN}"));
+        assert!(unresolved.details.as_ref().unwrap().ends_with(".mcp.json:6:9"));
+        let secret = health.issues.iter().find(|issue| issue.id.starts_with("MC002")).unwrap();
+        assert!(secret.id.ends_with("#/mcpServers/github/env/API_KEY"));
+        assert_eq!(health.issues.iter().filter(|issue| issue.id.starts_with("MC")).count(), 2);
+        assert_eq!(health.status, HealthStatus::Error);
+
+        let capabilities = extract_mcp_capabilities(&dir.join(".mcp.json")).await.unwrap();
+        let expansion = capabilities.iter().find(|c| c.id == "mcp-env.github").unwrap();
+        assert_eq!(expansion.key, "mcpServers.github.expansion");
+        assert_eq!(expansion.value["variables"], serde_json::json!(["CC_CONFIG_VIEWER_UNSET_TOKEN"]));
+        assert!(expansion.value.get("unresolved").is_none());
+        assert_eq!(expansion.value["literalSecrets"], serde_json::json!(["/mcpServers/github/env/API_KEY"]));
+    }
+
+    #[tokio::test]
+    async fn test_mcp_env_capability_lists_each_variable_once() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mcp_path = temp_dir.path().join(".mcp.json");
+        std::fs::write(
+            &mcp_path,
+            r#"{"mcpServers": {"db": {"command": "db-mcp", "args": ["${DB_HOST}", "${DB_PORT:-5432}", "${DB_HOST}"]}}}"#,
+        )
+        .unwrap();
+
+        let capabilities = extract_mcp_capabilities(&mcp_path).await.unwrap();
+        let expansion = capabilities.iter().find(|c| c.id == "mcp-env.db").unwrap();
+        assert_eq!(expansion.value["variables"], serde_json::json!(["DB_HOST", "DB_PORT"]));
+    }
+
     #[tokio::test]
     async fn test_health_check_project_nonexistent_path() {
         let result = health_check_project("/nonexistent/path".to_string()).await;
@@ -1304,9 +2242,12 @@ mod tests {
                     user: false,
                     project: true,
                     local: false,
+                    claude_json: false,
                 },
                 mcp_servers: None,
                 sub_agents: None,
+                slash_commands: None,
+                claude_json: None,
             },
             DiscoveredProject {
                 id: "project2".to_string(),
@@ -1318,9 +2259,12 @@ mod tests {
                     user: false,
                     project: true,
                     local: false,
+                    claude_json: false,
                 },
                 mcp_servers: None,
                 sub_agents: None,
+                slash_commands: None,
+                claude_json: None,
             },
         ];
 
@@ -1347,9 +2291,12 @@ mod tests {
                 user: false,
                 project: true,
                 local: false,
+                claude_json: false,
             },
             mcp_servers: None,
             sub_agents: None,
+            slash_commands: None,
+            claude_json: None,
         }];
 
         let health_results = refresh_all_project_health(projects).await.unwrap();
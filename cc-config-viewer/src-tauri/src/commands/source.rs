@@ -3,10 +3,9 @@
 //! This module implements commands for tracing configuration items back to their source files.
 //! Integrates with Story 3.3's inheritance path visualization.
 
+use crate::config::{json_path, spanned_json};
 use crate::types::app::AppError;
 use serde::{Deserialize, Serialize};
-use std::fs::File;
-use std::io::{BufRead, BufReader};
 
 /// Represents the location where a configuration item was defined
 #[derive(Debug, Serialize, Deserialize)]
@@ -14,6 +13,10 @@ pub struct SourceLocation {
     pub file_path: String,
     pub line_number: Option<u32>,
     pub column_number: Option<u32>,
+    pub end_line_number: Option<u32>,
+    pub end_column_number: Option<u32>,
+    /// JSON pointer of the matched node, when the file is JSON
+    pub json_pointer: Option<String>,
     pub context: Option<String>,
 }
 
@@ -37,46 +40,78 @@ pub enum SourceTraceError {
     InvalidFormat(String),
 }
 
+/// Find every location of a configuration key in a file
+///
+/// JSON files (comments and trailing commas allowed) are searched by
+/// structured path, e.g. `permissions.allow[2]` or
+/// `mcpServers.github.env.TOKEN`, and report the exact span of each matching
+/// node; a path that does not resolve has no location. Files that are not a
+/// JSON object or array fall back to a plain text search.
+fn find_all_in_file(file_path: &str, config_key: &str) -> Result<Vec<SourceLocation>, SourceTraceError> {
+    let content = std::fs::read_to_string(file_path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::InvalidData => SourceTraceError::InvalidFormat(format!("{}: {}", file_path, e)),
+        _ => SourceTraceError::FileNotAccessible(format!("{}: {}", file_path, e)),
+    })?;
+    let lines: Vec<&str> = content.lines().collect();
+
+    // Files that are not a JSON object or array, such as Markdown, get a text search
+    let document = match spanned_json::parse_tolerant(&content).document {
+        Some(document) if document.value.is_object() || document.value.is_array() => document,
+        _ => return Ok(find_text_in_lines(file_path, &lines, config_key)),
+    };
+
+    let locations: Vec<SourceLocation> = json_path::resolve(&document.value, config_key)
+        .into_iter()
+        .filter_map(|pointer| {
+            let span = document.span(&pointer)?;
+            let start = span.start();
+            Some(SourceLocation {
+                file_path: file_path.to_string(),
+                line_number: Some(start.line),
+                column_number: Some(start.column),
+                end_line_number: Some(span.value.end_line),
+                end_column_number: Some(span.value.end_column),
+                context: lines.get(start.line as usize - 1).map(|line| line.to_string()),
+                json_pointer: Some(pointer),
+            })
+        })
+        .collect();
+
+    Ok(locations)
+}
+
+/// Find every line containing the key as plain text
+fn find_text_in_lines(file_path: &str, lines: &[&str], config_key: &str) -> Vec<SourceLocation> {
+    lines
+        .iter()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let byte_column = line.find(config_key)?;
+            let column = line[..byte_column].chars().count() as u32 + 1;
+            Some(SourceLocation {
+                file_path: file_path.to_string(),
+                line_number: Some(index as u32 + 1),
+                column_number: Some(column),
+                end_line_number: Some(index as u32 + 1),
+                end_column_number: Some(column + config_key.chars().count() as u32),
+                json_pointer: None,
+                context: Some(line.to_string()),
+            })
+        })
+        .collect()
+}
+
 /// Find the source location of a configuration key in a file
 fn find_config_in_file(
     file_path: &str,
     config_key: &str,
 ) -> Result<Option<SourceLocation>, SourceTraceError> {
-    let file = File::open(file_path)
-        .map_err(|e| SourceTraceError::FileNotAccessible(format!("{}: {}", file_path, e)))?;
-
-    let reader = BufReader::new(file);
-    let lines = reader.lines();
-
-    let mut line_number = 0;
-    let mut found_line: Option<String> = None;
-
-    for (current_line, line_result) in lines.enumerate() {
-        let line = line_result.map_err(|e| {
-            SourceTraceError::InvalidFormat(format!("Error reading line {}: {}", current_line + 1, e))
-        })?;
-        line_number = current_line as u32 + 1;
-
-        // Check if this line contains the config key
-        if line.contains(config_key) {
-            found_line = Some(line);
-            break;
-        }
-    }
-
-    if let Some(line_content) = found_line {
-        Ok(Some(SourceLocation {
-            file_path: file_path.to_string(),
-            line_number: Some(line_number),
-            column_number: None, // Could be enhanced to find exact column
-            context: Some(line_content),
-        }))
-    } else {
-        Ok(None)
-    }
+    Ok(find_all_in_file(file_path, config_key)?.into_iter().next())
 }
 
 /// Trace a configuration item back to its source file
+///
+/// Returns the first match, searching files in order.
 #[tauri::command]
 pub async fn get_source_location(
     request: TraceSourceRequest,
@@ -113,6 +148,28 @@ pub async fn get_source_location(
     Ok(None)
 }
 
+/// Trace a configuration item to every location that matches it
+///
+/// Useful when a path is ambiguous, e.g. keys containing dots, or when the
+/// same key is set in several files.
+#[tauri::command]
+pub async fn get_source_locations(request: TraceSourceRequest) -> Result<Vec<SourceLocation>, AppError> {
+    let mut locations = Vec::new();
+
+    for file_path in request.search_paths.into_iter() {
+        let file_path_for_log = file_path.clone();
+        let config_key = request.config_key.clone();
+
+        match tokio::task::spawn_blocking(move || find_all_in_file(&file_path, &config_key)).await {
+            Ok(Ok(found)) => locations.extend(found),
+            Ok(Err(e)) => println!("Error searching in file {}: {}", file_path_for_log, e),
+            Err(e) => println!("Task error for file {}: {}", file_path_for_log, e),
+        }
+    }
+
+    Ok(locations)
+}
+
 /// Open a file in the system's default editor
 #[tauri::command]
 pub async fn open_in_editor(file_path: String, line_number: Option<u32>) -> Result<(), AppError> {
@@ -203,4 +260,80 @@ mod tests {
         assert_eq!(location.line_number, Some(2));
         assert!(location.context.is_some());
     }
+
+    #[test]
+    fn test_find_config_in_file_structured_path() {
+        use std::io::Write;
+        use tempfile::NamedTempFile;
+
+        let mut temp_file = NamedTempFile::new().unwrap();
+        write!(
+            temp_file,
+            "{{\n  // allow lists\n  \"permissions\": {{\n    \"deny\": [\"Bash\"],\n    \"allow\": [\"Read\", \"Edit\", \"Bash\"]\n  }}\n}}"
+        )
+        .unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let location = find_config_in_file(file_path, "permissions.allow[2]").unwrap().unwrap();
+        assert_eq!(location.line_number, Some(5));
+        assert_eq!(location.column_number, Some(31));
+        assert_eq!(location.end_column_number, Some(37));
+        assert_eq!(location.json_pointer.as_deref(), Some("/permissions/allow/2"));
+
+        let location = find_config_in_file(file_path, "permissions.allow").unwrap().unwrap();
+        assert_eq!((location.line_number, location.column_number), (Some(5), Some(5)));
+
+        // Structured matches win over text inside comments and values
+        let location = find_config_in_file(file_path, "permissions.deny").unwrap().unwrap();
+        assert_eq!(location.json_pointer.as_deref(), Some("/permissions/deny"));
+
+        // Paths that do not resolve in a JSON document are not searched as text
+        assert!(find_config_in_file(file_path, "allow lists").unwrap().is_none());
+        assert!(find_config_in_file(file_path, "missing").unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_get_source_locations_returns_every_match() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let settings = temp_dir.path().join("settings.json");
+        let local = temp_dir.path().join("settings.local.json");
+        std::fs::write(&settings, r#"{"env": {"a.b": "1", "a": {"b": "2"}}}"#).unwrap();
+        std::fs::write(&local, r#"{"env": {"a.b": "3"}}"#).unwrap();
+
+        let request = TraceSourceRequest {
+            config_key: "env.a.b".to_string(),
+            search_paths: vec![
+                settings.to_string_lossy().to_string(),
+                local.to_string_lossy().to_string(),
+            ],
+        };
+
+        let locations = get_source_locations(request).await.unwrap();
+        let pointers: Vec<&str> = locations.iter().filter_map(|l| l.json_pointer.as_deref()).collect();
+        assert_eq!(pointers, vec!["/env/a/b", "/env/a.b", "/env/a.b"]);
+        assert_eq!(locations[2].file_path, local.to_string_lossy());
+    }
+
+    #[test]
+    fn test_find_config_in_non_json_file_uses_text_search() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("CLAUDE.md");
+        std::fs::write(&path, "# Notes\nUse the testKey here\n").unwrap();
+
+        let location = find_config_in_file(path.to_str().unwrap(), "testKey").unwrap().unwrap();
+        assert_eq!((location.line_number, location.column_number), (Some(2), Some(9)));
+        assert!(location.json_pointer.is_none());
+    }
+
+    #[test]
+    fn test_find_config_in_partly_json_file_uses_text_search() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("notes.txt");
+        // Parses as the number 42 followed by trailing text
+        std::fs::write(&path, "42\nmodel: opus\n").unwrap();
+
+        let location = find_config_in_file(path.to_str().unwrap(), "model").unwrap().unwrap();
+        assert_eq!((location.line_number, location.column_number), (Some(2), Some(1)));
+        assert!(location.json_pointer.is_none());
+    }
 }
\ No newline at end of file
@@ -0,0 +1,315 @@
+//! Sub-agent discovery
+//!
+//! Reads the Markdown sub-agent definitions in `~/.claude/agents` (user
+//! scope) and `<project>/.claude/agents` (project scope).
+
+use crate::config::frontmatter::{self, MarkdownDocument};
+use crate::config::validation::{self, Diagnostic, DiagnosticSeverity};
+use crate::types::agent::SubAgent;
+use crate::types::app::{AppError, ConfigScope};
+use crate::types::error::error_codes;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Frontmatter keys Claude Code reads from an agent file
+const AGENT_KEYS: &[&str] = &["name", "description", "tools", "model", "color"];
+
+/// Model values accepted in agent frontmatter
+const AGENT_MODELS: &[&str] = &["sonnet", "opus", "haiku", "inherit"];
+
+/// Colors the agent picker can show
+const AGENT_COLORS: &[&str] = &["red", "blue", "green", "yellow", "purple", "orange", "pink", "cyan"];
+
+/// User-level agents directory (`~/.claude/agents`)
+pub fn user_agents_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".claude").join("agents"))
+}
+
+/// Project-level agents directory (`<project>/.claude/agents`)
+pub fn project_agents_dir(project_dir: &Path) -> PathBuf {
+    project_dir.join(".claude").join("agents")
+}
+
+/// Whether a path is a sub-agent definition (`agents/*.md`)
+pub fn is_agent_file(path: &Path) -> bool {
+    path.extension().and_then(|e| e.to_str()) == Some("md")
+        && path.parent().and_then(|p| p.file_name()).and_then(|n| n.to_str()) == Some("agents")
+}
+
+/// Build a sub-agent from the content of its Markdown file
+pub fn parse_sub_agent(path: &Path, content: &str, scope: ConfigScope) -> SubAgent {
+    let document = frontmatter::parse(content);
+    let text = |key: &str| document.get(key).map(|entry| entry.value.text());
+
+    let name = text("name")
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| file_stem(path));
+
+    let extra = document
+        .frontmatter
+        .iter()
+        .flatten()
+        .filter(|entry| !AGENT_KEYS.contains(&entry.key.as_str()))
+        .map(|entry| (entry.key.clone(), entry.value.text()))
+        .collect::<BTreeMap<_, _>>();
+
+    SubAgent {
+        name,
+        description: text("description"),
+        tools: document.get("tools").map(|entry| entry.value.items()),
+        model: text("model"),
+        color: text("color"),
+        prompt: document.body.trim().to_string(),
+        scope,
+        path: path.to_string_lossy().to_string(),
+        extra,
+    }
+}
+
+/// Read a single agent file
+pub fn read_sub_agent(path: &Path, scope: ConfigScope) -> Result<SubAgent, AppError> {
+    let content = std::fs::read_to_string(path).map_err(AppError::from)?;
+    Ok(parse_sub_agent(path, &content, scope))
+}
+
+/// Agent files in a directory, sorted by file name
+pub fn agent_files(agents_dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = match std::fs::read_dir(agents_dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.is_file() && path.extension().and_then(|e| e.to_str()) == Some("md"))
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    files.sort();
+    files
+}
+
+/// Read every agent in a directory, skipping unreadable files
+pub fn discover_agents(agents_dir: &Path, scope: ConfigScope) -> Vec<SubAgent> {
+    agent_files(agents_dir)
+        .iter()
+        .filter_map(|path| match read_sub_agent(path, scope) {
+            Ok(agent) => Some(agent),
+            Err(e) => {
+                eprintln!("Warning: Failed to read agent {}: {}", path.display(), e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// User agents followed by the project's agents
+///
+/// A project agent with the same name as a user agent takes precedence in
+/// Claude Code; both are returned so the override is visible.
+pub fn list_agents(project_dir: Option<&Path>) -> Vec<SubAgent> {
+    let mut agents = user_agents_dir()
+        .map(|dir| discover_agents(&dir, ConfigScope::User))
+        .unwrap_or_default();
+
+    if let Some(project_dir) = project_dir {
+        agents.extend(discover_agents(&project_agents_dir(project_dir), ConfigScope::Project));
+    }
+
+    agents
+}
+
+/// Validate an agent file's frontmatter
+pub fn validate_agent(content: &str) -> Vec<Diagnostic> {
+    let document = frontmatter::parse(content);
+
+    if document.frontmatter.is_none() {
+        return vec![located(
+            validation::diagnostic(
+                "",
+                DiagnosticSeverity::Error,
+                error_codes::PR001,
+                "Missing YAML frontmatter with 'name' and 'description'".to_string(),
+            ),
+            1,
+        )];
+    }
+
+    let mut diagnostics: Vec<Diagnostic> = document
+        .issues
+        .iter()
+        .map(|issue| {
+            located(
+                validation::diagnostic("", DiagnosticSeverity::Error, error_codes::PR001, issue.message.clone()),
+                issue.line,
+            )
+        })
+        .collect();
+
+    check_required(&document, "name", &mut diagnostics);
+    check_required(&document, "description", &mut diagnostics);
+
+    for entry in document.frontmatter.iter().flatten() {
+        let pointer = format!("/{}", entry.key);
+        let value = entry.value.text();
+
+        let problem = match entry.key.as_str() {
+            "name" if !is_valid_name(&value) => Some((
+                DiagnosticSeverity::Error,
+                format!("Agent name '{}' must use lowercase letters, digits and hyphens", value),
+            )),
+            "model" if !AGENT_MODELS.contains(&value.as_str()) => Some((
+                DiagnosticSeverity::Warning,
+                format!("Unknown model '{}', expected one of: {}", value, AGENT_MODELS.join(", ")),
+            )),
+            "color" if !AGENT_COLORS.contains(&value.as_str()) => Some((
+                DiagnosticSeverity::Warning,
+                format!("Unknown color '{}', expected one of: {}", value, AGENT_COLORS.join(", ")),
+            )),
+            "tools" if entry.value.items().is_empty() => Some((
+                DiagnosticSeverity::Warning,
+                "Empty 'tools' list; omit the key to inherit every tool".to_string(),
+            )),
+            key if !AGENT_KEYS.contains(&key) => {
+                diagnostics.push(located(validation::unknown_key(key, AGENT_KEYS, &pointer), entry.line));
+                None
+            }
+            _ => None,
+        };
+
+        if let Some((severity, message)) = problem {
+            diagnostics.push(located(
+                validation::diagnostic(&pointer, severity, error_codes::PR004, message),
+                entry.line,
+            ));
+        }
+    }
+
+    if document.body.trim().is_empty() {
+        diagnostics.push(located(
+            validation::diagnostic(
+                "",
+                DiagnosticSeverity::Warning,
+                error_codes::PR004,
+                "Agent has no system prompt".to_string(),
+            ),
+            document.body_line,
+        ));
+    }
+
+    diagnostics
+}
+
+fn check_required(document: &MarkdownDocument, key: &str, diagnostics: &mut Vec<Diagnostic>) {
+    let present = document.get(key).is_some_and(|entry| !entry.value.text().is_empty());
+    if !present {
+        diagnostics.push(located(
+            validation::diagnostic(
+                &format!("/{}", key),
+                DiagnosticSeverity::Error,
+                error_codes::PR004,
+                format!("Missing required frontmatter key '{}'", key),
+            ),
+            1,
+        ));
+    }
+}
+
+fn located(mut diagnostic: Diagnostic, line: u32) -> Diagnostic {
+    diagnostic.line = Some(line);
+    diagnostic.column = Some(1);
+    diagnostic
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("unknown")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REVIEWER: &str = "---\nname: code-reviewer\ndescription: Reviews changes for bugs\ntools: Read, Grep, Glob\nmodel: opus\ncolor: blue\n---\nYou are a senior reviewer.\n";
+
+    #[test]
+    fn test_parse_sub_agent() {
+        let agent = parse_sub_agent(Path::new("/p/.claude/agents/reviewer.md"), REVIEWER, ConfigScope::Project);
+
+        assert_eq!(agent.name, "code-reviewer");
+        assert_eq!(agent.description.as_deref(), Some("Reviews changes for bugs"));
+        assert_eq!(agent.tools, Some(vec!["Read".to_string(), "Grep".to_string(), "Glob".to_string()]));
+        assert_eq!(agent.model.as_deref(), Some("opus"));
+        assert_eq!(agent.color.as_deref(), Some("blue"));
+        assert_eq!(agent.prompt, "You are a senior reviewer.");
+        assert_eq!(agent.scope, ConfigScope::Project);
+    }
+
+    #[test]
+    fn test_parse_sub_agent_without_frontmatter_uses_file_name() {
+        let agent = parse_sub_agent(Path::new("/a/agents/helper.md"), "# Helper", ConfigScope::User);
+        assert_eq!(agent.name, "helper");
+        assert!(agent.tools.is_none());
+        assert_eq!(agent.prompt, "# Helper");
+    }
+
+    #[test]
+    fn test_discover_agents_sorted_md_only() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::fs::write(temp_dir.path().join("b.md"), REVIEWER).unwrap();
+        std::fs::write(temp_dir.path().join("a.md"), "---\nname: alpha\n---\n").unwrap();
+        std::fs::write(temp_dir.path().join("notes.txt"), "x").unwrap();
+
+        let agents = discover_agents(temp_dir.path(), ConfigScope::Project);
+        let names: Vec<&str> = agents.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["alpha", "code-reviewer"]);
+        assert!(discover_agents(&temp_dir.path().join("missing"), ConfigScope::User).is_empty());
+    }
+
+    #[test]
+    fn test_validate_valid_agent() {
+        assert!(validate_agent(REVIEWER).is_empty());
+    }
+
+    #[test]
+    fn test_validate_agent_problems() {
+        let content = "---\nname: Code Reviewer\nmodle: opus\ncolor: teal\n---\n";
+        let diagnostics = validate_agent(content);
+        let summary: Vec<(&str, &str, Option<u32>)> = diagnostics
+            .iter()
+            .map(|d| (d.code.as_str(), d.pointer.as_str(), d.line))
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                ("PR004", "/description", Some(1)),
+                ("PR004", "/name", Some(2)),
+                ("PR003", "/modle", Some(3)),
+                ("PR004", "/color", Some(4)),
+                ("PR004", "", Some(6)),
+            ]
+        );
+        assert!(diagnostics[2].message.contains("did you mean 'model'"));
+        assert_eq!(diagnostics[3].severity, DiagnosticSeverity::Warning);
+    }
+
+    #[test]
+    fn test_validate_agent_without_frontmatter() {
+        let diagnostics = validate_agent("# Agent 1");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "PR001");
+    }
+
+    #[test]
+    fn test_is_agent_file() {
+        assert!(is_agent_file(Path::new("/p/.claude/agents/a.md")));
+        assert!(!is_agent_file(Path::new("/p/.claude/commands/a.md")));
+        assert!(!is_agent_file(Path::new("/p/.claude/agents/a.txt")));
+    }
+}
@@ -0,0 +1,337 @@
+//! `~/.claude.json` access
+//!
+//! Claude Code keeps global state in `~/.claude.json`, including user-scoped
+//! MCP servers and a `projects` map keyed by absolute project path.
+//! Capabilities from a project's entry carry the `claude-json` source, so they
+//! can be told apart from `settings.local.json`.
+
+use crate::config::validation::Diagnostic;
+use crate::config::{mcp, reader};
+use crate::types::app::{AppError, Capability, ConfigScope};
+use crate::types::claude_json::ClaudeJsonProject;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// `Capability.source` of entries in a project's `~/.claude.json` entry, which apply in local scope
+pub const SOURCE: &str = "claude-json";
+
+/// Location of `~/.claude.json`
+pub fn claude_json_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".claude.json"))
+}
+
+/// Read and parse `~/.claude.json`
+///
+/// Syntax problems that were recovered from are reported by the health check
+/// through `project_syntax_diagnostics`.
+pub fn read_claude_json(path: &Path) -> Result<Value, AppError> {
+    let content = std::fs::read_to_string(path).map_err(AppError::from)?;
+    let (value, _) = reader::parse_json_recovering(path, &content)?;
+
+    if !value.is_object() {
+        return Err(AppError::Parse(format!("Expected JSON object in {}", path.display())));
+    }
+
+    Ok(value)
+}
+
+/// Syntax problems recovered from in `~/.claude.json`, when it has an entry for the project
+pub fn project_syntax_diagnostics(path: &Path, project_dir: &Path) -> Vec<Diagnostic> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(_) => return Vec::new(),
+    };
+
+    match reader::parse_json_recovering(path, &content) {
+        Ok((value, diagnostics)) if project_entry(&value, project_dir).is_some() => diagnostics,
+        _ => Vec::new(),
+    }
+}
+
+/// Normalize a project path for comparison with `projects` keys
+fn normalize(path: &str) -> String {
+    path.replace('\\', "/").trim_end_matches('/').to_string()
+}
+
+/// The `projects` entry for a project directory
+///
+/// Keys are matched as written and after resolving symlinks, with either
+/// path separator.
+pub fn project_entry<'a>(value: &'a Value, project_dir: &Path) -> Option<&'a Value> {
+    value
+        .get("projects")?
+        .as_object()?
+        .iter()
+        .find(|(key, _)| is_same_project(key, project_dir))
+        .map(|(_, entry)| entry)
+}
+
+/// Keys of a project entry that have their own `ClaudeJsonProject` field
+const KNOWN_PROJECT_KEYS: &[&str] = &[
+    "allowedTools",
+    "mcpServers",
+    "enabledMcpjsonServers",
+    "disabledMcpjsonServers",
+    "hasTrustDialogAccepted",
+    "hasCompletedProjectOnboarding",
+];
+
+fn string_list(entry: &Value, key: &str) -> Vec<String> {
+    entry
+        .get(key)
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|item| item.as_str().map(String::from))
+        .collect()
+}
+
+fn flag(entry: &Value, key: &str) -> bool {
+    entry.get(key).and_then(|v| v.as_bool()).unwrap_or(false)
+}
+
+/// The configuration part of `~/.claude.json`: user MCP servers and the
+/// config keys of each project entry, without the usage state Claude Code
+/// rewrites on every session
+pub fn config_subset(value: &Value) -> Value {
+    let projects: serde_json::Map<String, Value> = value
+        .get("projects")
+        .and_then(|p| p.as_object())
+        .into_iter()
+        .flatten()
+        .map(|(key, entry)| {
+            let config: serde_json::Map<String, Value> = entry
+                .as_object()
+                .into_iter()
+                .flatten()
+                .filter(|(k, _)| KNOWN_PROJECT_KEYS.contains(&k.as_str()))
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect();
+            (key.clone(), Value::Object(config))
+        })
+        .collect();
+
+    serde_json::json!({
+        "mcpServers": value.get("mcpServers"),
+        "projects": projects,
+    })
+}
+
+/// Every entry of the `projects` map, skipping entries that are not objects
+///
+/// `path` is the file `value` was read from and is recorded on MCP servers.
+pub fn projects(value: &Value, path: &Path) -> Vec<ClaudeJsonProject> {
+    let projects = match value.get("projects").and_then(|p| p.as_object()) {
+        Some(projects) => projects,
+        None => return Vec::new(),
+    };
+
+    projects
+        .iter()
+        .filter_map(|(key, entry)| {
+            let object = entry.as_object()?;
+            // Prompt history is large and private, and not configuration
+            let other = object
+                .iter()
+                .filter(|(k, _)| !KNOWN_PROJECT_KEYS.contains(&k.as_str()) && k.as_str() != "history")
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect();
+
+            Some(ClaudeJsonProject {
+                path: key.clone(),
+                allowed_tools: string_list(entry, "allowedTools"),
+                mcp_servers: mcp::parse_servers(entry.get("mcpServers"), ConfigScope::Local, path),
+                enabled_mcpjson_servers: string_list(entry, "enabledMcpjsonServers"),
+                disabled_mcpjson_servers: string_list(entry, "disabledMcpjsonServers"),
+                has_trust_dialog_accepted: flag(entry, "hasTrustDialogAccepted"),
+                has_completed_project_onboarding: flag(entry, "hasCompletedProjectOnboarding"),
+                other,
+            })
+        })
+        .collect()
+}
+
+fn server_capabilities(servers: Option<&Value>, id_prefix: &str, source: &str) -> Vec<Capability> {
+    servers
+        .and_then(|servers| servers.as_object())
+        .into_iter()
+        .flatten()
+        .map(|(name, server)| Capability {
+            id: format!("{}mcp.{}", id_prefix, name),
+            key: format!("mcpServers.{}", name),
+            value: server.clone(),
+            source: source.to_string(),
+        })
+        .collect()
+}
+
+/// Capabilities a project gets from `~/.claude.json`
+///
+/// User MCP servers come from the top level; MCP servers and allowed tools
+/// come from the project's entry and carry the `SOURCE` source.
+pub fn capabilities(value: &Value, project_dir: &Path) -> Vec<Capability> {
+    let mut capabilities = server_capabilities(value.get("mcpServers"), "user.", ConfigScope::User.as_str());
+
+    if let Some(entry) = project_entry(value, project_dir) {
+        capabilities.extend(server_capabilities(entry.get("mcpServers"), "claude-json.", SOURCE));
+
+        let allowed_tools = string_list(entry, "allowedTools");
+        if !allowed_tools.is_empty() {
+            capabilities.push(Capability {
+                id: "claude-json.allowedTools".to_string(),
+                key: "allowedTools".to_string(),
+                value: serde_json::json!(allowed_tools),
+                source: SOURCE.to_string(),
+            });
+        }
+    }
+
+    capabilities
+}
+
+/// Whether a `projects` key names a project directory
+pub fn is_same_project(key: &str, project_dir: &Path) -> bool {
+    let key = normalize(key);
+    key == normalize(&project_dir.to_string_lossy())
+        || project_dir
+            .canonicalize()
+            .is_ok_and(|canonical| key == normalize(&canonical.to_string_lossy()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_project_entry_matches_normalized_keys() {
+        let value = json!({
+            "projects": {
+                "/home/a/app/": {"allowedTools": ["Read"]},
+                "C:\\work\\api": {"allowedTools": []}
+            }
+        });
+
+        assert!(project_entry(&value, Path::new("/home/a/app")).is_some());
+        assert!(project_entry(&value, Path::new("C:/work/api")).is_some());
+        assert!(project_entry(&value, Path::new("/home/a/other")).is_none());
+        assert!(project_entry(&json!({}), Path::new("/home/a/app")).is_none());
+    }
+
+    #[test]
+    fn test_projects_parses_entries() {
+        let value = json!({
+            "projects": {
+                "/home/a/app": {
+                    "allowedTools": ["Bash(npm test)"],
+                    "history": [{"display": "fix the bug"}],
+                    "mcpServers": {"db": {"command": "db-mcp"}},
+                    "enabledMcpjsonServers": ["github"],
+                    "hasTrustDialogAccepted": true,
+                    "lastCost": 0.25
+                },
+                "/home/a/broken": "not an object"
+            }
+        });
+
+        let projects = projects(&value, Path::new("/home/a/.claude.json"));
+        assert_eq!(projects.len(), 1);
+
+        let app = &projects[0];
+        assert_eq!(app.path, "/home/a/app");
+        assert_eq!(app.allowed_tools, vec!["Bash(npm test)"]);
+        assert_eq!(app.mcp_servers[0].name, "db");
+        assert_eq!(app.mcp_servers[0].scope, ConfigScope::Local);
+        assert_eq!(app.enabled_mcpjson_servers, vec!["github"]);
+        assert!(app.has_trust_dialog_accepted);
+        assert!(!app.has_completed_project_onboarding);
+        assert_eq!(app.other.keys().collect::<Vec<_>>(), vec!["lastCost"]);
+    }
+
+    #[test]
+    fn test_capabilities() {
+        let value = json!({
+            "mcpServers": {"search": {"type": "http", "url": "https://search.example.com/mcp"}},
+            "projects": {
+                "/home/a/app": {
+                    "allowedTools": ["Bash(npm test)"],
+                    "mcpServers": {"db": {"command": "db-mcp"}}
+                },
+                "/home/a/other": {"allowedTools": ["Read"]}
+            }
+        });
+
+        let app = capabilities(&value, Path::new("/home/a/app"));
+        let ids: Vec<(&str, &str, &str)> = app
+            .iter()
+            .map(|c| (c.id.as_str(), c.key.as_str(), c.source.as_str()))
+            .collect();
+        assert_eq!(
+            ids,
+            vec![
+                ("user.mcp.search", "mcpServers.search", "user"),
+                ("claude-json.mcp.db", "mcpServers.db", "claude-json"),
+                ("claude-json.allowedTools", "allowedTools", "claude-json"),
+            ]
+        );
+        assert_eq!(app[2].value, json!(["Bash(npm test)"]));
+
+        // Projects without an entry still see the user servers
+        assert_eq!(capabilities(&value, Path::new("/home/a/none")).len(), 1);
+    }
+
+    #[test]
+    fn test_config_subset_ignores_usage_state() {
+        let before = json!({
+            "numStartups": 3,
+            "mcpServers": {"search": {"url": "https://search.example.com/mcp"}},
+            "projects": {"/home/a/app": {"allowedTools": ["Read"], "lastCost": 0.25}}
+        });
+        let after = json!({
+            "numStartups": 4,
+            "tipsHistory": {"memory": 4},
+            "mcpServers": {"search": {"url": "https://search.example.com/mcp"}},
+            "projects": {"/home/a/app": {"allowedTools": ["Read"], "lastCost": 0.5}}
+        });
+        assert_eq!(config_subset(&before), config_subset(&after));
+
+        let mut changed = after.clone();
+        changed["projects"]["/home/a/app"]["allowedTools"] = json!(["Read", "Edit"]);
+        assert_ne!(config_subset(&after), config_subset(&changed));
+    }
+
+    #[test]
+    fn test_is_same_project() {
+        assert!(is_same_project("/home/a/app/", Path::new("/home/a/app")));
+        assert!(is_same_project("C:\\work\\api", Path::new("C:/work/api")));
+        assert!(!is_same_project("/home/a/app", Path::new("/home/a/application")));
+    }
+
+    #[test]
+    fn test_read_claude_json_rejects_non_objects() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join(".claude.json");
+        std::fs::write(&path, "[]").unwrap();
+        assert!(read_claude_json(&path).is_err());
+
+        std::fs::write(&path, "{\"numStartups\": 3,}").unwrap();
+        assert_eq!(read_claude_json(&path).unwrap()["numStartups"], 3);
+    }
+
+    #[test]
+    fn test_project_syntax_diagnostics() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join(".claude.json");
+        let project = temp_dir.path().join("app");
+        let content = format!(
+            "{{\"projects\": {{{}: {{\"allowedTools\": [\"Read\",]}}}}}}",
+            serde_json::to_string(&project.to_string_lossy()).unwrap()
+        );
+        std::fs::write(&path, content).unwrap();
+
+        let diagnostics = project_syntax_diagnostics(&path, &project);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("Trailing comma"));
+        assert!(project_syntax_diagnostics(&path, &temp_dir.path().join("other")).is_empty());
+    }
+}
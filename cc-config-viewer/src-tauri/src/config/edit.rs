@@ -0,0 +1,383 @@
+//! Config file editing
+//!
+//! Applies JSON Patches to config files in two steps: `preview_patch` returns
+//! the diff and validation of the patched file, then `commit_patch` writes it,
+//! provided the file still matches the hash the preview was made from.
+//! `commit_patches` changes several files together against the hashes of
+//! their previews, restoring the ones already written if a later one fails.
+
+use crate::config::resolver::ScopePaths;
+use crate::config::validation::{self, Diagnostic, DiagnosticSeverity, FileValidation};
+use crate::config::writer::{self, WriteOutcome};
+use crate::config::{claude_json, json_patch, text_diff};
+use crate::types::app::AppError;
+use crate::types::patch::{ConfigTarget, PatchOperation, PatchPreview};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Path of a config target; project targets need `project_dir`
+pub fn target_path(target: ConfigTarget, project_dir: Option<&Path>) -> Result<PathBuf, AppError> {
+    let project_dir = || {
+        project_dir.ok_or_else(|| AppError::Filesystem(format!("{:?} needs a project path", target)))
+    };
+    let home_path = |path: Option<PathBuf>| {
+        path.ok_or_else(|| AppError::Filesystem("Home directory not found".to_string()))
+    };
+
+    match target {
+        ConfigTarget::UserSettings => home_path(dirs::home_dir().map(|home| home.join(".claude").join("settings.json"))),
+        ConfigTarget::ClaudeJson => home_path(claude_json::claude_json_path()),
+        ConfigTarget::ProjectSettings => Ok(ScopePaths::for_project(project_dir()?).project),
+        ConfigTarget::LocalSettings => Ok(ScopePaths::for_project(project_dir()?).local),
+        ConfigTarget::ProjectMcp => Ok(project_dir()?.join(".mcp.json")),
+    }
+}
+
+/// SHA-256 of file content, used to detect changes between preview and commit
+pub fn content_hash(content: &str) -> String {
+    format!("{:x}", Sha256::digest(content.as_bytes()))
+}
+
+/// A patch applied in memory
+struct PatchedFile {
+    original: Option<String>,
+    base_hash: String,
+    rendered: String,
+    validation: FileValidation,
+    new_errors: Vec<Diagnostic>,
+}
+
+fn errors(validation: &FileValidation) -> impl Iterator<Item = &Diagnostic> {
+    validation.diagnostics.iter().filter(|d| d.severity == DiagnosticSeverity::Error)
+}
+
+fn apply_to_file(path: &Path, patch: &[PatchOperation]) -> Result<PatchedFile, AppError> {
+    let original = match std::fs::read_to_string(path) {
+        Ok(content) => Some(content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(AppError::from(e)),
+    };
+
+    let document: Value = match &original {
+        Some(content) => serde_json::from_str(content).map_err(|e| {
+            AppError::Parse(format!("{} is not valid JSON, fix it before editing: {}", path.display(), e))
+        })?,
+        None => Value::Object(Default::default()),
+    };
+
+    let patched = json_patch::apply(&document, patch)?;
+    let rendered = writer::render_json(&patched, original.as_deref())?;
+
+    let not_config = || AppError::Parse(format!("Not a recognized Claude Code config file: {}", path.display()));
+    let validation = validation::validate_file(path, &rendered).ok_or_else(not_config)?;
+    let previous = original.as_deref().and_then(|content| validation::validate_file(path, content));
+    let new_errors = errors(&validation)
+        .filter(|diagnostic| {
+            !previous.as_ref().is_some_and(|previous| {
+                errors(previous).any(|d| d.code == diagnostic.code && d.pointer == diagnostic.pointer)
+            })
+        })
+        .cloned()
+        .collect();
+
+    Ok(PatchedFile {
+        base_hash: content_hash(original.as_deref().unwrap_or_default()),
+        original,
+        rendered,
+        validation,
+        new_errors,
+    })
+}
+
+fn describe(path: &Path, patched: PatchedFile) -> PatchPreview {
+    let label = path.to_string_lossy().to_string();
+    let old_label = if patched.original.is_some() { label.as_str() } else { "/dev/null" };
+    let original = patched.original.unwrap_or_default();
+
+    PatchPreview {
+        diff: text_diff::unified_diff(&original, &patched.rendered, old_label, &label),
+        changed: original != patched.rendered,
+        path: label,
+        base_hash: patched.base_hash,
+        validation: patched.validation,
+        new_errors: patched.new_errors,
+    }
+}
+
+fn invalid_result(path: &Path, new_errors: &[Diagnostic]) -> AppError {
+    let messages: Vec<&str> = new_errors.iter().map(|d| d.message.as_str()).collect();
+    AppError::Parse(format!("Patch would make {} invalid: {}", path.display(), messages.join("; ")))
+}
+
+/// Apply a patch to a config file in memory and describe the result
+pub fn preview_patch(path: &Path, patch: &[PatchOperation]) -> Result<PatchPreview, AppError> {
+    Ok(describe(path, apply_to_file(path, patch)?))
+}
+
+/// Apply patches to several config files in memory and describe the results
+pub fn preview_patches(files: &[(PathBuf, Vec<PatchOperation>)]) -> Result<Vec<PatchPreview>, AppError> {
+    files.iter().map(|(path, patch)| preview_patch(path, patch)).collect()
+}
+
+/// Base hash of each preview by path, as `commit_patches` expects them
+pub fn base_hashes(previews: &[PatchPreview]) -> BTreeMap<String, String> {
+    previews
+        .iter()
+        .map(|preview| (preview.path.clone(), preview.base_hash.clone()))
+        .collect()
+}
+
+/// Fail unless a file still has the content its preview was made from
+fn check_base(path: &Path, base_hash: &str, previewed: Option<&str>) -> Result<(), AppError> {
+    match previewed {
+        Some(previewed) if previewed == base_hash => Ok(()),
+        Some(_) => Err(AppError::Filesystem(format!(
+            "{} changed since the patch was previewed; preview it again",
+            path.display()
+        ))),
+        None => Err(AppError::Filesystem(format!(
+            "{} was not part of the preview; preview it again",
+            path.display()
+        ))),
+    }
+}
+
+/// Apply a patch to a config file and write it, backing up the current content
+///
+/// Fails without writing when the file no longer matches `base_hash` or the
+/// patch introduces validation errors.
+pub fn commit_patch(
+    path: &Path,
+    patch: &[PatchOperation],
+    base_hash: &str,
+    backup_dir: Option<&Path>,
+) -> Result<WriteOutcome, AppError> {
+    let patched = apply_to_file(path, patch)?;
+
+    check_base(path, &patched.base_hash, Some(base_hash))?;
+    if !patched.new_errors.is_empty() {
+        return Err(invalid_result(path, &patched.new_errors));
+    }
+
+    writer::write_config(path, &patched.rendered, backup_dir)
+}
+
+/// Put a file back the way it was before a failed transaction
+fn roll_back(path: &Path, original: Option<&str>) -> Result<(), AppError> {
+    match original {
+        Some(content) => writer::write_atomic(path, content),
+        None => std::fs::remove_file(path).map_err(AppError::from),
+    }
+}
+
+/// Apply patches to several config files as one change
+///
+/// Nothing is written unless every file still matches its hash in
+/// `base_hashes`, as returned by `base_hashes`, and every patch applies and
+/// keeps its file valid. If a write fails, files written before it are restored.
+pub fn commit_patches(
+    files: &[(PathBuf, Vec<PatchOperation>)],
+    base_hashes: &BTreeMap<String, String>,
+    backup_dir: Option<&Path>,
+) -> Result<Vec<WriteOutcome>, AppError> {
+    let patched = files
+        .iter()
+        .map(|(path, patch)| apply_to_file(path, patch).map(|patched| (path, patched)))
+        .collect::<Result<Vec<_>, AppError>>()?;
+    for (path, file) in &patched {
+        let previewed = base_hashes.get(path.to_string_lossy().as_ref());
+        check_base(path, &file.base_hash, previewed.map(String::as_str))?;
+    }
+    if let Some((path, file)) = patched.iter().find(|(_, file)| !file.new_errors.is_empty()) {
+        return Err(invalid_result(path, &file.new_errors));
+    }
+
+    let mut outcomes = Vec::new();
+    for (index, (path, file)) in patched.iter().enumerate() {
+        match writer::write_config(path, &file.rendered, backup_dir) {
+            Ok(outcome) => outcomes.push(outcome),
+            Err(e) => {
+                for (written, file) in &patched[..index] {
+                    if let Err(rollback) = roll_back(written, file.original.as_deref()) {
+                        eprintln!("Error: Failed to restore {}: {}", written.display(), rollback);
+                    }
+                }
+                return Err(AppError::Filesystem(format!(
+                    "Failed to write {}, no files were changed: {}",
+                    path.display(),
+                    e
+                )));
+            }
+        }
+    }
+
+    Ok(outcomes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::reader;
+    use serde_json::json;
+
+    fn patch(operations: Value) -> Vec<PatchOperation> {
+        serde_json::from_value(operations).unwrap()
+    }
+
+    #[test]
+    fn test_target_path() {
+        let project = Path::new("/work/app");
+        assert_eq!(
+            target_path(ConfigTarget::LocalSettings, Some(project)).unwrap(),
+            project.join(".claude").join("settings.local.json")
+        );
+        assert_eq!(
+            target_path(ConfigTarget::ProjectMcp, Some(project)).unwrap(),
+            project.join(".mcp.json")
+        );
+        assert!(target_path(ConfigTarget::ProjectSettings, None).is_err());
+    }
+
+    #[test]
+    fn test_content_hash_is_sha256() {
+        assert_eq!(
+            content_hash(""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn test_preview_and_commit_patch() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let backups = temp_dir.path().join("backups");
+        let path = temp_dir.path().join(".claude").join("settings.json");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "{\n    \"model\": \"opus\",\n    \"permissions\": {\n        \"allow\": []\n    }\n}\n").unwrap();
+
+        let operations = patch(json!([{"op": "add", "path": "/permissions/allow/-", "value": "Bash(npm test)"}]));
+        let preview = preview_patch(&path, &operations).unwrap();
+        assert!(preview.changed);
+        assert!(preview.validation.valid);
+        assert!(preview.new_errors.is_empty());
+        assert!(preview.diff.contains("-        \"allow\": []\n+        \"allow\": [\n+            \"Bash(npm test)\"\n+        ]\n"));
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 6, "preview does not write");
+
+        let outcome = commit_patch(&path, &operations, &preview.base_hash, Some(&backups)).unwrap();
+        assert!(outcome.changed);
+        assert!(outcome.backup.is_some());
+        let written: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(written["permissions"]["allow"], json!(["Bash(npm test)"]));
+
+        // The file changed, so the old preview can no longer be committed
+        let error = commit_patch(&path, &operations, &preview.base_hash, None).unwrap_err();
+        assert!(error.to_string().contains("changed since the patch was previewed"));
+    }
+
+    #[test]
+    fn test_patch_new_file_and_invalid_result() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join(".mcp.json");
+
+        let add_server = patch(json!([{"op": "add", "path": "/mcpServers", "value": {"github": {"command": "gh"}}}]));
+        let preview = preview_patch(&path, &add_server).unwrap();
+        assert!(preview.diff.starts_with("--- /dev/null\n"));
+        commit_patch(&path, &add_server, &preview.base_hash, None).unwrap();
+        assert!(path.is_file());
+
+        let settings = temp_dir.path().join("settings.json");
+        let bad_mode = patch(json!([{"op": "add", "path": "/permissions", "value": {"defaultMode": "yolo"}}]));
+        let preview = preview_patch(&settings, &bad_mode).unwrap();
+        assert!(!preview.validation.valid);
+        assert_eq!(preview.new_errors.len(), 1);
+        let error = commit_patch(&settings, &bad_mode, &preview.base_hash, None).unwrap_err();
+        assert!(error.to_string().contains("Patch would make"));
+        assert!(!settings.exists());
+    }
+
+    #[test]
+    fn test_commit_patches_is_all_or_nothing() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let local = temp_dir.path().join("settings.local.json");
+        let shared = temp_dir.path().join("settings.json");
+        std::fs::write(&local, "{\"permissions\": {\"allow\": [\"Read\"]}}").unwrap();
+
+        let promote = vec![
+            (shared.clone(), patch(json!([{"op": "add", "path": "/permissions", "value": {"allow": ["Read"]}}]))),
+            (local.clone(), patch(json!([{"op": "remove", "path": "/permissions/allow/0"}]))),
+        ];
+        let previews = preview_patches(&promote).unwrap();
+        assert_eq!(previews.len(), 2);
+        assert!(!shared.exists(), "previews do not write");
+
+        let outcomes = commit_patches(&promote, &base_hashes(&previews), None).unwrap();
+        assert!(outcomes.iter().all(|outcome| outcome.changed));
+        let written: Value = serde_json::from_str(&std::fs::read_to_string(&local).unwrap()).unwrap();
+        assert_eq!(written["permissions"]["allow"], json!([]));
+
+        // A failing second patch leaves the first file untouched
+        let failing = vec![
+            (shared.clone(), patch(json!([{"op": "add", "path": "/model", "value": "opus"}]))),
+            (local.clone(), patch(json!([{"op": "remove", "path": "/permissions/allow/0"}]))),
+        ];
+        let current = BTreeMap::from([
+            (shared.to_string_lossy().to_string(), content_hash(&std::fs::read_to_string(&shared).unwrap())),
+            (local.to_string_lossy().to_string(), content_hash(&std::fs::read_to_string(&local).unwrap())),
+        ]);
+        assert!(commit_patches(&failing, &current, None).is_err());
+        let shared_value: Value = serde_json::from_str(&std::fs::read_to_string(&shared).unwrap()).unwrap();
+        assert_eq!(shared_value.get("model"), None);
+    }
+
+    #[test]
+    fn test_commit_patches_requires_previewed_hashes() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let local = temp_dir.path().join("settings.local.json");
+        let shared = temp_dir.path().join("settings.json");
+        std::fs::write(&local, "{\"model\": \"opus\"}").unwrap();
+
+        let files = vec![
+            (local.clone(), patch(json!([{"op": "replace", "path": "/model", "value": "sonnet"}]))),
+            (shared.clone(), patch(json!([{"op": "add", "path": "/model", "value": "opus"}]))),
+        ];
+        let hashes = base_hashes(&preview_patches(&files).unwrap());
+
+        // A file edited after the preview
+        std::fs::write(&local, "{\"model\": \"haiku\"}").unwrap();
+        let error = commit_patches(&files, &hashes, None).unwrap_err();
+        assert!(error.to_string().contains("changed since the patch was previewed"));
+        assert!(!shared.exists());
+
+        // A file the preview did not cover
+        let mut partial = base_hashes(&preview_patches(&files).unwrap());
+        partial.remove(shared.to_string_lossy().as_ref());
+        let error = commit_patches(&files, &partial, None).unwrap_err();
+        assert!(error.to_string().contains("was not part of the preview"));
+        assert!(!shared.exists());
+    }
+
+    #[test]
+    fn test_commit_patches_rolls_back_written_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let local = temp_dir.path().join("settings.local.json");
+        let created = temp_dir.path().join(".mcp.json");
+        let policy = temp_dir.path().join("managed-settings.json");
+        let original = "{\"model\": \"opus\"}";
+        std::fs::write(&local, original).unwrap();
+
+        // The policy patch applies cleanly but writing it is refused, after the other two files were written
+        let files = vec![
+            (local.clone(), patch(json!([{"op": "replace", "path": "/model", "value": "sonnet"}]))),
+            (created.clone(), patch(json!([{"op": "add", "path": "/mcpServers", "value": {}}]))),
+            (policy.clone(), patch(json!([{"op": "add", "path": "/model", "value": "opus"}]))),
+        ];
+        let hashes = base_hashes(&preview_patches(&files).unwrap());
+        let result = reader::with_managed_settings(&policy, || commit_patches(&files, &hashes, None));
+
+        assert!(result.unwrap_err().to_string().contains("no files were changed"));
+        assert_eq!(std::fs::read_to_string(&local).unwrap(), original);
+        assert!(!created.exists());
+        assert!(!policy.exists());
+    }
+}
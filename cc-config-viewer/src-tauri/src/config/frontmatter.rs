@@ -0,0 +1,272 @@
+//! Markdown frontmatter parsing
+//!
+//! Sub-agents and slash commands are Markdown files with a YAML frontmatter
+//! block. Claude Code only uses flat `key: value` pairs there, so this reads
+//! that subset of YAML: plain and quoted scalars, `|`/`>` block scalars,
+//! inline `[a, b]` lists and `- item` lists.
+
+/// Value of a frontmatter key
+#[derive(Debug, Clone, PartialEq)]
+pub enum FrontmatterValue {
+    Text(String),
+    List(Vec<String>),
+}
+
+impl FrontmatterValue {
+    /// Items of a list, or of a comma-separated text value
+    pub fn items(&self) -> Vec<String> {
+        match self {
+            FrontmatterValue::Text(text) => text
+                .split(',')
+                .map(|item| item.trim().to_string())
+                .filter(|item| !item.is_empty())
+                .collect(),
+            FrontmatterValue::List(items) => items.clone(),
+        }
+    }
+
+    /// Text value, or a list joined with `, `
+    pub fn text(&self) -> String {
+        match self {
+            FrontmatterValue::Text(text) => text.clone(),
+            FrontmatterValue::List(items) => items.join(", "),
+        }
+    }
+}
+
+/// A frontmatter key with its value and 1-based line
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrontmatterEntry {
+    pub key: String,
+    pub value: FrontmatterValue,
+    /// Text after the colon as written, for values like `[pr-number] [priority]`
+    pub raw: String,
+    pub line: u32,
+}
+
+impl FrontmatterEntry {
+    /// The value as a single scalar, unquoted but never split into a list
+    pub fn scalar(&self) -> String {
+        unquote(strip_comment(&self.raw))
+    }
+}
+
+/// Problem found while reading frontmatter
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrontmatterIssue {
+    pub line: u32,
+    pub message: String,
+}
+
+/// Markdown document split into frontmatter and body
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarkdownDocument {
+    /// Frontmatter entries, or `None` when the file has no frontmatter block
+    pub frontmatter: Option<Vec<FrontmatterEntry>>,
+    pub body: String,
+    /// Line the body starts on
+    pub body_line: u32,
+    pub issues: Vec<FrontmatterIssue>,
+}
+
+impl MarkdownDocument {
+    /// Look up a frontmatter key
+    pub fn get(&self, key: &str) -> Option<&FrontmatterEntry> {
+        self.frontmatter.as_ref()?.iter().find(|entry| entry.key == key)
+    }
+}
+
+/// Split a Markdown file into frontmatter entries and body
+pub fn parse(content: &str) -> MarkdownDocument {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let lines: Vec<&str> = content.lines().collect();
+
+    if lines.first().map(|line| line.trim_end()) != Some("---") {
+        return MarkdownDocument {
+            frontmatter: None,
+            body: content.to_string(),
+            body_line: 1,
+            issues: Vec::new(),
+        };
+    }
+
+    let mut issues = Vec::new();
+    let closing = lines.iter().skip(1).position(|line| line.trim_end() == "---");
+    let (header, body_start) = match closing {
+        Some(index) => (&lines[1..index + 1], index + 2),
+        None => {
+            issues.push(FrontmatterIssue {
+                line: 1,
+                message: "Frontmatter is not closed with '---'".to_string(),
+            });
+            (&lines[1..], lines.len())
+        }
+    };
+
+    let entries = parse_entries(header, &mut issues);
+    let body = lines.get(body_start..).unwrap_or_default().join("\n");
+
+    MarkdownDocument {
+        frontmatter: Some(entries),
+        body,
+        body_line: body_start as u32 + 1,
+        issues,
+    }
+}
+
+/// Parse `key: value` lines; `header` starts on line 2 of the file
+fn parse_entries(header: &[&str], issues: &mut Vec<FrontmatterIssue>) -> Vec<FrontmatterEntry> {
+    let mut entries: Vec<FrontmatterEntry> = Vec::new();
+    let mut index = 0;
+
+    while index < header.len() {
+        let line = header[index];
+        let line_number = index as u32 + 2;
+        index += 1;
+
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        let (key, raw) = match line.split_once(':') {
+            Some((key, raw)) if !line.starts_with(char::is_whitespace) && !key.trim().is_empty() => {
+                (key.trim().to_string(), raw.trim())
+            }
+            _ => {
+                issues.push(FrontmatterIssue {
+                    line: line_number,
+                    message: format!("Expected 'key: value' but found '{}'", trimmed),
+                });
+                continue;
+            }
+        };
+
+        // Indented continuation lines belong to this key
+        let continuation_start = index;
+        while index < header.len()
+            && (header[index].starts_with(char::is_whitespace) || header[index].trim().is_empty())
+        {
+            index += 1;
+        }
+        let continuation: Vec<&str> = header[continuation_start..index].to_vec();
+
+        let value = if raw == "|" || raw == ">" {
+            let lines: Vec<&str> = continuation.iter().map(|line| line.trim()).collect();
+            let separator = if raw == "|" { "\n" } else { " " };
+            FrontmatterValue::Text(lines.join(separator).trim().to_string())
+        } else if raw.is_empty() && continuation.iter().any(|line| line.trim_start().starts_with('-')) {
+            FrontmatterValue::List(
+                continuation
+                    .iter()
+                    .filter_map(|line| line.trim_start().strip_prefix('-'))
+                    .map(|item| unquote(item.trim()))
+                    .collect(),
+            )
+        } else if raw.starts_with('[') && raw.ends_with(']') {
+            FrontmatterValue::List(
+                raw[1..raw.len() - 1]
+                    .split(',')
+                    .map(|item| unquote(item.trim()))
+                    .filter(|item| !item.is_empty())
+                    .collect(),
+            )
+        } else {
+            // Plain scalars may wrap onto indented lines
+            let mut text = unquote(strip_comment(raw));
+            for line in &continuation {
+                if !line.trim().is_empty() {
+                    text.push(' ');
+                    text.push_str(line.trim());
+                }
+            }
+            FrontmatterValue::Text(text)
+        };
+
+        if entries.iter().any(|entry| entry.key == key) {
+            issues.push(FrontmatterIssue {
+                line: line_number,
+                message: format!("Duplicate key '{}'", key),
+            });
+            entries.retain(|entry| entry.key != key);
+        }
+
+        entries.push(FrontmatterEntry {
+            key,
+            value,
+            raw: raw.to_string(),
+            line: line_number,
+        });
+    }
+
+    entries
+}
+
+/// Remove a trailing ` # comment` from a plain scalar
+fn strip_comment(raw: &str) -> &str {
+    if raw.starts_with('"') || raw.starts_with('\'') {
+        return raw;
+    }
+    raw.find(" #").map_or(raw, |index| raw[..index].trim_end())
+}
+
+/// Remove matching quotes, unescaping `\"`, `\n` and `''`
+fn unquote(raw: &str) -> String {
+    if raw.len() >= 2 && raw.starts_with('"') && raw.ends_with('"') {
+        raw[1..raw.len() - 1]
+            .replace("\\n", "\n")
+            .replace("\\\"", "\"")
+            .replace("\\\\", "\\")
+    } else if raw.len() >= 2 && raw.starts_with('\'') && raw.ends_with('\'') {
+        raw[1..raw.len() - 1].replace("''", "'")
+    } else {
+        raw.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_frontmatter_and_body() {
+        let content = "---\nname: code-reviewer\ndescription: \"Reviews code: style \\\"and\\\" bugs\"\ntools: Read, Grep # read-only\nmodel: sonnet\n---\nYou are a reviewer.\n\nBe strict.\n";
+        let document = parse(content);
+
+        assert!(document.issues.is_empty());
+        assert_eq!(document.get("name").unwrap().value, FrontmatterValue::Text("code-reviewer".to_string()));
+        assert_eq!(document.get("description").unwrap().value.text(), "Reviews code: style \"and\" bugs");
+        assert_eq!(document.get("tools").unwrap().value.items(), vec!["Read", "Grep"]);
+        assert_eq!(document.get("model").unwrap().line, 5);
+        assert_eq!(document.body, "You are a reviewer.\n\nBe strict.");
+        assert_eq!(document.body_line, 7);
+    }
+
+    #[test]
+    fn test_parse_lists_and_block_scalars() {
+        let content = "---\ntools:\n  - Read\n  - 'Bash(git:*)'\ncolors: [red, blue]\ndescription: |\n  Line one\n  Line two\n---\nbody";
+        let document = parse(content);
+
+        assert_eq!(
+            document.get("tools").unwrap().value,
+            FrontmatterValue::List(vec!["Read".to_string(), "Bash(git:*)".to_string()])
+        );
+        assert_eq!(document.get("colors").unwrap().value.items(), vec!["red", "blue"]);
+        assert_eq!(document.get("description").unwrap().value.text(), "Line one\nLine two");
+    }
+
+    #[test]
+    fn test_no_frontmatter() {
+        let document = parse("# Agent 1\nDo things");
+        assert!(document.frontmatter.is_none());
+        assert_eq!(document.body, "# Agent 1\nDo things");
+    }
+
+    #[test]
+    fn test_frontmatter_issues() {
+        let document = parse("---\nname: a\nnot a pair\nname: b\n");
+        let lines: Vec<u32> = document.issues.iter().map(|issue| issue.line).collect();
+        assert_eq!(lines, vec![1, 3, 4]);
+        assert_eq!(document.get("name").unwrap().value.text(), "b");
+    }
+}
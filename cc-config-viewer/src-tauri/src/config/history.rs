@@ -0,0 +1,306 @@
+//! Config file version history
+//!
+//! Every version of a config file the watcher sees is stored under
+//! `<data dir>/cc-config-viewer/history`. Contents live in `objects/`, named
+//! by their SHA-256, so identical versions share one snapshot; `index.json`
+//! lists the versions of each file, oldest first. Claude Code rewrites
+//! `~/.claude.json` on every session, so its versions are only recorded when
+//! its configuration changes.
+
+use crate::config::{claude_json, text_diff, writer};
+use crate::config::writer::WriteOutcome;
+use crate::types::app::AppError;
+use crate::types::history::ConfigVersion;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Versions kept per file; older ones are forgotten
+pub const MAX_VERSIONS: usize = 100;
+
+/// Versions by file path
+type HistoryIndex = BTreeMap<String, Vec<ConfigVersion>>;
+
+/// Serializes index updates from the watcher and from commands
+static INDEX_LOCK: Mutex<()> = Mutex::new(());
+
+/// Location of the version history
+pub fn history_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("cc-config-viewer").join("history"))
+}
+
+/// Key a file's versions are stored under; canonical so different spellings share one history
+fn file_key(path: &Path) -> String {
+    path.canonicalize()
+        .unwrap_or_else(|_| path.to_path_buf())
+        .to_string_lossy()
+        .to_string()
+}
+
+fn object_path(history_dir: &Path, hash: &str) -> Result<PathBuf, AppError> {
+    if hash.len() != 64 || !hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(AppError::Parse(format!("Invalid version hash: {}", hash)));
+    }
+    Ok(history_dir.join("objects").join(hash.to_ascii_lowercase()))
+}
+
+/// Read the index, treating a missing or unreadable file as empty
+fn load_index(history_dir: &Path) -> HistoryIndex {
+    let path = history_dir.join("index.json");
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(_) => return HistoryIndex::new(),
+    };
+
+    serde_json::from_str(&content).unwrap_or_else(|e| {
+        eprintln!("Warning: Ignoring unreadable version history {}: {}", path.display(), e);
+        HistoryIndex::new()
+    })
+}
+
+fn save_index(history_dir: &Path, index: &HistoryIndex) -> Result<(), AppError> {
+    let content = serde_json::to_string_pretty(index)?;
+    writer::write_atomic(&history_dir.join("index.json"), &content)
+}
+
+/// Remove snapshots no version refers to any more
+fn remove_unreferenced(history_dir: &Path, index: &HistoryIndex, hashes: &[String]) {
+    for hash in hashes {
+        let referenced = index.values().flatten().any(|version| &version.hash == hash);
+        if referenced {
+            continue;
+        }
+        if let Ok(path) = object_path(history_dir, hash) {
+            if let Err(e) = std::fs::remove_file(&path) {
+                eprintln!("Warning: Failed to remove snapshot {}: {}", path.display(), e);
+            }
+        }
+    }
+}
+
+/// Whether two versions of `~/.claude.json` have the same configuration
+fn same_claude_json_config(old: &[u8], new: &[u8]) -> bool {
+    match (serde_json::from_slice::<Value>(old), serde_json::from_slice::<Value>(new)) {
+        (Ok(old), Ok(new)) => claude_json::config_subset(&old) == claude_json::config_subset(&new),
+        _ => false,
+    }
+}
+
+/// Record the current content of `path` as a new version
+///
+/// Returns `None` when the file does not exist or is unchanged since the last
+/// version; for `~/.claude.json`, only configuration changes count.
+pub fn record_version(history_dir: &Path, path: &Path) -> Result<Option<ConfigVersion>, AppError> {
+    let content = match std::fs::read(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(AppError::from(e)),
+    };
+    let hash = format!("{:x}", Sha256::digest(&content));
+
+    let _guard = INDEX_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut index = load_index(history_dir);
+    let versions = index.entry(file_key(path)).or_default();
+    if versions.last().is_some_and(|last| last.hash == hash) {
+        return Ok(None);
+    }
+    if path.file_name().is_some_and(|name| name == ".claude.json") {
+        let last = versions.last().and_then(|last| object_path(history_dir, &last.hash).ok());
+        if last.and_then(|last| std::fs::read(last).ok()).is_some_and(|last| same_claude_json_config(&last, &content)) {
+            return Ok(None);
+        }
+    }
+
+    let object = object_path(history_dir, &hash)?;
+    if !object.is_file() {
+        let text = String::from_utf8(content.clone())
+            .map_err(|_| AppError::Parse(format!("{} is not a text file", path.display())))?;
+        writer::write_atomic(&object, &text)?;
+    }
+
+    let version = ConfigVersion {
+        hash,
+        size: content.len() as u64,
+        recorded_at: chrono::Utc::now().to_rfc3339(),
+    };
+    versions.push(version.clone());
+    let excess = versions.len().saturating_sub(MAX_VERSIONS);
+    let forgotten: Vec<String> = versions.drain(..excess).map(|v| v.hash).collect();
+
+    save_index(history_dir, &index)?;
+    remove_unreferenced(history_dir, &index, &forgotten);
+
+    Ok(Some(version))
+}
+
+/// Recorded versions of `path`, newest first
+pub fn list_versions(history_dir: &Path, path: &Path) -> Vec<ConfigVersion> {
+    let mut versions = load_index(history_dir).remove(&file_key(path)).unwrap_or_default();
+    versions.reverse();
+    versions
+}
+
+/// Content of a version of `path`
+pub fn read_version(history_dir: &Path, path: &Path, hash: &str) -> Result<String, AppError> {
+    if !list_versions(history_dir, path).iter().any(|v| v.hash.eq_ignore_ascii_case(hash)) {
+        return Err(AppError::Filesystem(format!(
+            "{} has no recorded version {}",
+            path.display(),
+            hash
+        )));
+    }
+    std::fs::read_to_string(object_path(history_dir, hash)?).map_err(AppError::from)
+}
+
+/// Unified diff between two versions of `path`; `to` of `None` compares with the file on disk,
+/// treating a deleted file as empty
+pub fn diff_versions(history_dir: &Path, path: &Path, from: &str, to: Option<&str>) -> Result<String, AppError> {
+    let label = |hash: &str| format!("{}@{}", path.display(), &hash[..hash.len().min(12)]);
+    let old = read_version(history_dir, path, from)?;
+    let (new, new_label) = match to {
+        Some(to) => (read_version(history_dir, path, to)?, label(to)),
+        None => {
+            let current = match std::fs::read_to_string(path) {
+                Ok(content) => content,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+                Err(e) => return Err(AppError::from(e)),
+            };
+            (current, path.to_string_lossy().to_string())
+        }
+    };
+
+    Ok(text_diff::unified_diff(&old, &new, &label(from), &new_label))
+}
+
+/// Write a recorded version back to `path`, backing up the current content
+pub fn restore_version(
+    history_dir: &Path,
+    path: &Path,
+    hash: &str,
+    backup_dir: Option<&Path>,
+) -> Result<WriteOutcome, AppError> {
+    let content = read_version(history_dir, path, hash)?;
+    let outcome = writer::write_config(path, &content, backup_dir)?;
+    record_version(history_dir, path)?;
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_version_deduplicates() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let history = temp_dir.path().join("history");
+        let path = temp_dir.path().join("settings.json");
+
+        assert_eq!(record_version(&history, &path).unwrap(), None);
+
+        std::fs::write(&path, "{\"model\": \"opus\"}").unwrap();
+        let first = record_version(&history, &path).unwrap().unwrap();
+        assert_eq!(first.hash.len(), 64);
+        assert_eq!(first.size, 17);
+        assert_eq!(record_version(&history, &path).unwrap(), None);
+
+        std::fs::write(&path, "{\"model\": \"sonnet\"}").unwrap();
+        record_version(&history, &path).unwrap().unwrap();
+        std::fs::write(&path, "{\"model\": \"opus\"}").unwrap();
+        record_version(&history, &path).unwrap().unwrap();
+
+        let versions = list_versions(&history, &path);
+        assert_eq!(versions.len(), 3);
+        assert_eq!(versions[0].hash, first.hash);
+        assert_eq!(std::fs::read_dir(history.join("objects")).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn test_diff_and_restore_version() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let history = temp_dir.path().join("history");
+        let backups = temp_dir.path().join("backups");
+        let path = temp_dir.path().join(".mcp.json");
+
+        std::fs::write(&path, "{\n  \"mcpServers\": {}\n}\n").unwrap();
+        let good = record_version(&history, &path).unwrap().unwrap();
+        std::fs::write(&path, "{\n  \"mcpServers\": {\"broken\"\n}\n").unwrap();
+        let broken = record_version(&history, &path).unwrap().unwrap();
+
+        let diff = diff_versions(&history, &path, &good.hash, Some(&broken.hash)).unwrap();
+        assert!(diff.contains("-  \"mcpServers\": {}\n+  \"mcpServers\": {\"broken\"\n"));
+        assert!(diff.starts_with(&format!("--- {}@{}", path.display(), &good.hash[..12])));
+        assert_eq!(diff_versions(&history, &path, &broken.hash, None).unwrap(), "");
+
+        let outcome = restore_version(&history, &path, &good.hash, Some(&backups)).unwrap();
+        assert!(outcome.changed);
+        assert!(outcome.backup.is_some());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "{\n  \"mcpServers\": {}\n}\n");
+        assert_eq!(list_versions(&history, &path)[0].hash, good.hash);
+    }
+
+    #[test]
+    fn test_diff_with_unreadable_file_is_an_error() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let history = temp_dir.path().join("history");
+        let path = temp_dir.path().join("settings.json");
+        std::fs::write(&path, "{}\n").unwrap();
+        let version = record_version(&history, &path).unwrap().unwrap();
+
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            diff_versions(&history, &path, &version.hash, None).unwrap().lines().last(),
+            Some("-{}")
+        );
+
+        std::fs::create_dir(&path).unwrap();
+        assert!(diff_versions(&history, &path, &version.hash, None).is_err());
+    }
+
+    #[test]
+    fn test_claude_json_versions_skip_usage_state() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let history = temp_dir.path().join("history");
+        let path = temp_dir.path().join(".claude.json");
+
+        std::fs::write(&path, r#"{"numStartups": 1, "mcpServers": {}}"#).unwrap();
+        assert!(record_version(&history, &path).unwrap().is_some());
+        std::fs::write(&path, r#"{"numStartups": 2, "mcpServers": {}}"#).unwrap();
+        assert_eq!(record_version(&history, &path).unwrap(), None);
+        std::fs::write(&path, r#"{"numStartups": 2, "mcpServers": {"db": {"command": "db-mcp"}}}"#).unwrap();
+        assert!(record_version(&history, &path).unwrap().is_some());
+
+        assert_eq!(list_versions(&history, &path).len(), 2);
+    }
+
+    #[test]
+    fn test_versions_belong_to_their_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let history = temp_dir.path().join("history");
+        let settings = temp_dir.path().join("settings.json");
+        let other = temp_dir.path().join("other.json");
+        std::fs::write(&settings, "{}").unwrap();
+        let version = record_version(&history, &settings).unwrap().unwrap();
+
+        let error = restore_version(&history, &other, &version.hash, None).unwrap_err();
+        assert!(error.to_string().contains("has no recorded version"));
+        assert!(!other.exists());
+        assert!(read_version(&history, &settings, "../../etc/passwd").is_err());
+    }
+
+    #[test]
+    fn test_old_versions_are_pruned() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let history = temp_dir.path().join("history");
+        let path = temp_dir.path().join("settings.json");
+
+        for n in 0..MAX_VERSIONS + 5 {
+            std::fs::write(&path, format!("{{\"n\": {}}}", n)).unwrap();
+            record_version(&history, &path).unwrap();
+        }
+
+        assert_eq!(list_versions(&history, &path).len(), MAX_VERSIONS);
+        assert_eq!(std::fs::read_dir(history.join("objects")).unwrap().count(), MAX_VERSIONS);
+    }
+}
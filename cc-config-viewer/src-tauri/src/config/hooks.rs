@@ -0,0 +1,305 @@
+//! Hooks inspector
+//!
+//! Flattens the `hooks` section of every settings scope into one list of
+//! commands. Claude Code runs the hooks of all scopes rather than letting one
+//! scope replace another, so every entry is kept.
+
+use crate::config::resolver::{self, ScopePaths, SettingsLayer};
+use crate::config::spanned_json::escape_pointer;
+use crate::config::tools::{self, BUILTIN_TOOLS};
+use crate::config::validation::{self, Diagnostic, DiagnosticSeverity};
+use crate::types::app::{AppError, ConfigScope};
+use crate::types::error::error_codes;
+use crate::types::hook::{ConfiguredHook, HookInventory};
+use crate::types::settings::ClaudeSettings;
+use regex::Regex;
+use serde_json::Value;
+use std::path::Path;
+
+/// Values the `PreCompact` matcher is compared against
+const PRE_COMPACT_TRIGGERS: &[&str] = &["manual", "auto"];
+
+/// Values the `SessionStart` matcher is compared against
+const SESSION_START_SOURCES: &[&str] = &["startup", "resume", "clear", "compact"];
+
+/// What an event's matcher is compared against, or `None` when it has no matcher
+fn matcher_targets(event: &str) -> Option<&'static [&'static str]> {
+    match event {
+        "PreToolUse" | "PostToolUse" => Some(BUILTIN_TOOLS),
+        "PreCompact" => Some(PRE_COMPACT_TRIGGERS),
+        "SessionStart" => Some(SESSION_START_SOURCES),
+        _ => None,
+    }
+}
+
+/// Check that a matcher compiles as a regex and names things the event can match
+///
+/// Empty matchers and `*` match everything and are always accepted.
+pub fn check_matcher(event: &str, matcher: &str) -> Vec<(DiagnosticSeverity, String)> {
+    if matcher.is_empty() || matcher == "*" {
+        return Vec::new();
+    }
+
+    let targets = match matcher_targets(event) {
+        Some(targets) => targets,
+        None => {
+            return vec![(
+                DiagnosticSeverity::Warning,
+                format!("'{}' hooks do not use matchers; '{}' is ignored", event, matcher),
+            )]
+        }
+    };
+
+    // Claude Code matches the whole tool name
+    let regex = match Regex::new(&format!("^(?:{})$", matcher)) {
+        Ok(regex) => regex,
+        Err(e) => {
+            let reason = e.to_string();
+            let reason = reason.lines().last().unwrap_or_default().trim_start_matches("error: ");
+            return vec![(
+                DiagnosticSeverity::Error,
+                format!("Matcher '{}' is not a valid regex: {}", matcher, reason),
+            )];
+        }
+    };
+
+    let is_tool_event = targets == BUILTIN_TOOLS;
+    let names: Vec<&str> = matcher
+        .split('|')
+        .filter(|name| !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'))
+        .collect();
+
+    let unknown: Vec<(DiagnosticSeverity, String)> = names
+        .iter()
+        .filter(|name| {
+            if is_tool_event {
+                !tools::is_known_tool(name)
+            } else {
+                !targets.contains(name)
+            }
+        })
+        .map(|name| {
+            let message = if is_tool_event {
+                format!("Matcher names unknown tool '{}'", name)
+            } else {
+                format!("Unknown {} matcher '{}', expected one of: {}", event, name, targets.join(", "))
+            };
+            (DiagnosticSeverity::Warning, message)
+        })
+        .collect();
+
+    if !unknown.is_empty() {
+        return unknown;
+    }
+
+    let matches_any = targets.iter().any(|target| regex.is_match(target))
+        || (is_tool_event && matcher.contains(tools::MCP_TOOL_PREFIX));
+    if matches_any {
+        Vec::new()
+    } else {
+        vec![(
+            DiagnosticSeverity::Warning,
+            format!("Matcher '{}' does not match anything '{}' hooks receive", matcher, event),
+        )]
+    }
+}
+
+/// Every hook command in one settings file
+pub fn hooks_from_settings(settings: &ClaudeSettings, scope: ConfigScope, path: &Path) -> Vec<ConfiguredHook> {
+    let mut hooks = Vec::new();
+
+    for (event, matchers) in settings.hooks.iter().flatten() {
+        for (matcher_index, group) in matchers.iter().enumerate() {
+            let matcher_issues: Vec<String> = group
+                .matcher
+                .as_deref()
+                .map(|matcher| check_matcher(event, matcher))
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(_, message)| message)
+                .collect();
+
+            for (hook_index, hook) in group.hooks.iter().enumerate() {
+                hooks.push(ConfiguredHook {
+                    event: event.clone(),
+                    matcher: group.matcher.clone().filter(|matcher| !matcher.is_empty()),
+                    type_: hook.type_.clone(),
+                    command: hook.command.clone(),
+                    timeout: hook.timeout,
+                    scope,
+                    path: path.to_string_lossy().to_string(),
+                    pointer: format!("/hooks/{}/{}/hooks/{}", escape_pointer(event), matcher_index, hook_index),
+                    matcher_issues: matcher_issues.clone(),
+                });
+            }
+        }
+    }
+
+    hooks
+}
+
+/// Hooks from every layer, highest precedence first and in file order within a layer
+///
+/// Hook events that do not fit the settings model are dropped when the layer
+/// is loaded.
+pub fn collect_hooks(layers: &[SettingsLayer]) -> HookInventory {
+    let mut hooks = Vec::new();
+
+    for layer in layers {
+        hooks.extend(hooks_from_settings(&layer.settings, layer.scope, &layer.path));
+    }
+
+    let disabled_by = layers
+        .iter()
+        .find(|layer| layer.settings.disable_all_hooks == Some(true))
+        .map(|layer| layer.scope);
+
+    HookInventory { hooks, disabled_by }
+}
+
+/// Every hook that applies to a project
+pub fn project_hooks(project_dir: &Path) -> Result<HookInventory, AppError> {
+    if !project_dir.is_dir() {
+        return Err(AppError::Filesystem(format!(
+            "Project path does not exist or is not a directory: {}",
+            project_dir.display()
+        )));
+    }
+
+    let layers = resolver::load_layers(&ScopePaths::for_project(project_dir));
+    Ok(collect_hooks(&layers))
+}
+
+/// Matcher diagnostics for the `hooks` section of a settings value
+pub fn validate_hooks(value: &Value) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    let events = match value.get("hooks").and_then(|hooks| hooks.as_object()) {
+        Some(events) => events,
+        None => return diagnostics,
+    };
+
+    for (event, matchers) in events {
+        for (index, group) in matchers.as_array().into_iter().flatten().enumerate() {
+            let matcher = match group.get("matcher").and_then(|m| m.as_str()) {
+                Some(matcher) => matcher,
+                None => continue,
+            };
+
+            let pointer = format!("/hooks/{}/{}/matcher", escape_pointer(event), index);
+            for (severity, message) in check_matcher(event, matcher) {
+                diagnostics.push(validation::diagnostic(&pointer, severity, error_codes::PR004, message));
+            }
+        }
+    }
+
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::path::PathBuf;
+
+    fn layer(scope: ConfigScope, value: Value) -> SettingsLayer {
+        SettingsLayer {
+            scope,
+            path: PathBuf::from(format!("/{}/settings.json", scope.as_str())),
+            settings: ClaudeSettings::from_value(value).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_check_matcher_accepts_tools_and_wildcards() {
+        assert!(check_matcher("PreToolUse", "Bash").is_empty());
+        assert!(check_matcher("PreToolUse", "Edit|MultiEdit|Write").is_empty());
+        assert!(check_matcher("PostToolUse", "Notebook.*").is_empty());
+        assert!(check_matcher("PreToolUse", "mcp__github__.*").is_empty());
+        assert!(check_matcher("PreToolUse", "*").is_empty());
+        assert!(check_matcher("PreToolUse", "").is_empty());
+        assert!(check_matcher("SessionStart", "startup|resume").is_empty());
+    }
+
+    #[test]
+    fn test_check_matcher_problems() {
+        let invalid = check_matcher("PreToolUse", "Edit|(Write");
+        assert_eq!(invalid[0].0, DiagnosticSeverity::Error);
+        assert!(invalid[0].1.contains("not a valid regex"));
+
+        let unknown = check_matcher("PreToolUse", "Bash|Wirte");
+        assert_eq!(unknown, vec![(DiagnosticSeverity::Warning, "Matcher names unknown tool 'Wirte'".to_string())]);
+
+        assert_eq!(check_matcher("PostToolUse", "Foo.*")[0].0, DiagnosticSeverity::Warning);
+        assert!(check_matcher("Stop", "Bash")[0].1.contains("ignored"));
+        assert!(check_matcher("PreCompact", "automatic")[0].1.contains("manual, auto"));
+    }
+
+    #[test]
+    fn test_collect_hooks_across_scopes() {
+        let layers = vec![
+            layer(
+                ConfigScope::Project,
+                json!({"hooks": {"PreToolUse": [
+                    {"matcher": "Bash", "hooks": [{"type": "command", "command": "./check.sh", "timeout": 10}]}
+                ]}}),
+            ),
+            layer(
+                ConfigScope::User,
+                json!({"hooks": {
+                    "Stop": [{"hooks": [{"type": "command", "command": "say done"}]}],
+                    "PreToolUse": [{"matcher": "Wrte", "hooks": [
+                        {"type": "command", "command": "a"},
+                        {"type": "command", "command": "b"}
+                    ]}]
+                }}),
+            ),
+        ];
+
+        let inventory = collect_hooks(&layers);
+        let summary: Vec<(&str, Option<&str>, &str, ConfigScope)> = inventory
+            .hooks
+            .iter()
+            .map(|h| (h.event.as_str(), h.matcher.as_deref(), h.command.as_deref().unwrap(), h.scope))
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                ("PreToolUse", Some("Bash"), "./check.sh", ConfigScope::Project),
+                ("Stop", None, "say done", ConfigScope::User),
+                ("PreToolUse", Some("Wrte"), "a", ConfigScope::User),
+                ("PreToolUse", Some("Wrte"), "b", ConfigScope::User),
+            ]
+        );
+        assert_eq!(inventory.hooks[0].timeout, Some(10));
+        assert_eq!(inventory.hooks[3].pointer, "/hooks/PreToolUse/0/hooks/1");
+        assert_eq!(inventory.hooks[3].matcher_issues, vec!["Matcher names unknown tool 'Wrte'"]);
+        assert!(inventory.disabled_by.is_none());
+    }
+
+    #[test]
+    fn test_disable_all_hooks() {
+        let layers = vec![
+            layer(ConfigScope::Local, json!({"disableAllHooks": true})),
+            layer(ConfigScope::User, json!({"hooks": {"Stop": [{"hooks": [{"type": "command", "command": "x"}]}]}})),
+        ];
+
+        let inventory = collect_hooks(&layers);
+        assert_eq!(inventory.disabled_by, Some(ConfigScope::Local));
+        assert_eq!(inventory.hooks.len(), 1);
+    }
+
+    #[test]
+    fn test_validate_hooks_pointers() {
+        let value = json!({"hooks": {"PreToolUse": [
+            {"matcher": "Bash", "hooks": []},
+            {"matcher": "[", "hooks": []}
+        ]}});
+
+        let diagnostics = validate_hooks(&value);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].pointer, "/hooks/PreToolUse/1/matcher");
+        assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Error);
+    }
+}
@@ -0,0 +1,267 @@
+//! JSON Patch (RFC 6902)
+//!
+//! Applies `add`, `remove`, `replace`, `move`, `copy` and `test` operations to
+//! a document. A patch is applied as a whole: if any operation fails, the
+//! document is left unchanged.
+
+use crate::types::app::AppError;
+use crate::types::patch::PatchOperation;
+use serde_json::Value;
+
+/// Split a JSON pointer into unescaped segments
+fn parse_pointer(pointer: &str) -> Result<Vec<String>, String> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+    let rest = pointer
+        .strip_prefix('/')
+        .ok_or_else(|| format!("'{}' is not a JSON pointer", pointer))?;
+
+    Ok(rest
+        .split('/')
+        .map(|segment| segment.replace("~1", "/").replace("~0", "~"))
+        .collect())
+}
+
+/// Array index named by a segment; `len` is accepted only when `append` is allowed
+fn array_index(segment: &str, len: usize, append: bool) -> Result<usize, String> {
+    if append && segment == "-" {
+        return Ok(len);
+    }
+    let valid = !segment.is_empty()
+        && segment.bytes().all(|b| b.is_ascii_digit())
+        && (segment == "0" || !segment.starts_with('0'));
+    let index: usize = match segment.parse() {
+        Ok(index) if valid => index,
+        _ => return Err(format!("'{}' is not an array index", segment)),
+    };
+
+    let in_bounds = if append { index <= len } else { index < len };
+    if !in_bounds {
+        return Err(format!("index {} is out of bounds", index));
+    }
+    Ok(index)
+}
+
+fn get<'a>(document: &'a Value, segments: &[String]) -> Result<&'a Value, String> {
+    segments.iter().try_fold(document, |node, segment| match node {
+        Value::Object(map) => map.get(segment).ok_or_else(|| format!("key '{}' does not exist", segment)),
+        Value::Array(items) => array_index(segment, items.len(), false).map(|index| &items[index]),
+        _ => Err(format!("cannot index into a {} with '{}'", type_name(node), segment)),
+    })
+}
+
+fn get_mut<'a>(document: &'a mut Value, segments: &[String]) -> Result<&'a mut Value, String> {
+    let mut node = document;
+    for segment in segments {
+        node = match node {
+            Value::Object(map) => map.get_mut(segment).ok_or_else(|| format!("key '{}' does not exist", segment))?,
+            Value::Array(items) => {
+                let index = array_index(segment, items.len(), false)?;
+                &mut items[index]
+            }
+            other => return Err(format!("cannot index into a {} with '{}'", type_name(other), segment)),
+        };
+    }
+    Ok(node)
+}
+
+/// Parent container of the node named by `segments`, with the last segment
+fn parent_mut<'a>(document: &'a mut Value, segments: &'a [String]) -> Result<(&'a mut Value, &'a str), String> {
+    let (last, parents) = segments.split_last().ok_or("the document root has no parent")?;
+    Ok((get_mut(document, parents)?, last))
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn add(document: &mut Value, segments: &[String], value: Value) -> Result<(), String> {
+    if segments.is_empty() {
+        *document = value;
+        return Ok(());
+    }
+    match parent_mut(document, segments)? {
+        (Value::Object(map), key) => {
+            map.insert(key.to_string(), value);
+            Ok(())
+        }
+        (Value::Array(items), segment) => {
+            let index = array_index(segment, items.len(), true)?;
+            items.insert(index, value);
+            Ok(())
+        }
+        (other, segment) => Err(format!("cannot add '{}' to a {}", segment, type_name(other))),
+    }
+}
+
+fn remove(document: &mut Value, segments: &[String]) -> Result<Value, String> {
+    match parent_mut(document, segments)? {
+        // `shift_remove` keeps the order of the remaining keys
+        (Value::Object(map), key) => map.shift_remove(key).ok_or_else(|| format!("key '{}' does not exist", key)),
+        (Value::Array(items), segment) => {
+            let index = array_index(segment, items.len(), false)?;
+            Ok(items.remove(index))
+        }
+        (other, segment) => Err(format!("cannot remove '{}' from a {}", segment, type_name(other))),
+    }
+}
+
+fn apply_operation(document: &mut Value, operation: &PatchOperation) -> Result<(), String> {
+    match operation {
+        PatchOperation::Add { path, value } => add(document, &parse_pointer(path)?, value.clone()),
+        PatchOperation::Remove { path } => {
+            let segments = parse_pointer(path)?;
+            if segments.is_empty() {
+                return Err("cannot remove the document root".to_string());
+            }
+            remove(document, &segments).map(|_| ())
+        }
+        PatchOperation::Replace { path, value } => {
+            // Replaced in place, so an object key keeps its position
+            *get_mut(document, &parse_pointer(path)?)? = value.clone();
+            Ok(())
+        }
+        PatchOperation::Move { from, path } => {
+            let from_segments = parse_pointer(from)?;
+            let to_segments = parse_pointer(path)?;
+            if from_segments == to_segments {
+                return get(document, &from_segments).map(|_| ());
+            }
+            if to_segments.starts_with(&from_segments) {
+                return Err(format!("cannot move '{}' into its own child '{}'", from, path));
+            }
+            if from_segments.is_empty() {
+                return Err("cannot move the document root".to_string());
+            }
+            let value = remove(document, &from_segments)?;
+            add(document, &to_segments, value)
+        }
+        PatchOperation::Copy { from, path } => {
+            let value = get(document, &parse_pointer(from)?)?.clone();
+            add(document, &parse_pointer(path)?, value)
+        }
+        PatchOperation::Test { path, value } => {
+            let actual = get(document, &parse_pointer(path)?)?;
+            if actual == value {
+                Ok(())
+            } else {
+                Err(format!("value is {}, expected {}", actual, value))
+            }
+        }
+    }
+}
+
+fn describe(operation: &PatchOperation) -> String {
+    match operation {
+        PatchOperation::Add { path, .. } => format!("add {}", path),
+        PatchOperation::Remove { path } => format!("remove {}", path),
+        PatchOperation::Replace { path, .. } => format!("replace {}", path),
+        PatchOperation::Move { from, path } => format!("move {} to {}", from, path),
+        PatchOperation::Copy { from, path } => format!("copy {} to {}", from, path),
+        PatchOperation::Test { path, .. } => format!("test {}", path),
+    }
+}
+
+/// Apply a patch to a copy of `document`
+pub fn apply(document: &Value, patch: &[PatchOperation]) -> Result<Value, AppError> {
+    let mut patched = document.clone();
+    for (index, operation) in patch.iter().enumerate() {
+        apply_operation(&mut patched, operation).map_err(|e| {
+            AppError::Parse(format!("Patch operation {} ({}) failed: {}", index, describe(operation), e))
+        })?;
+    }
+    Ok(patched)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn patch(operations: Value) -> Vec<PatchOperation> {
+        serde_json::from_value(operations).unwrap()
+    }
+
+    #[test]
+    fn test_apply_add_remove_replace() {
+        let document = json!({"model": "opus", "permissions": {"allow": ["Read"]}, "env": {"A": "1"}});
+        let patched = apply(
+            &document,
+            &patch(json!([
+                {"op": "add", "path": "/permissions/allow/-", "value": "Bash(npm run:*)"},
+                {"op": "add", "path": "/permissions/allow/0", "value": "Edit"},
+                {"op": "replace", "path": "/model", "value": "sonnet"},
+                {"op": "remove", "path": "/env/A"},
+                {"op": "add", "path": "/a~1b", "value": true},
+            ])),
+        )
+        .unwrap();
+
+        assert_eq!(
+            patched,
+            json!({
+                "model": "sonnet",
+                "permissions": {"allow": ["Edit", "Read", "Bash(npm run:*)"]},
+                "env": {},
+                "a/b": true
+            })
+        );
+        let keys: Vec<&String> = patched.as_object().unwrap().keys().collect();
+        assert_eq!(keys, vec!["model", "permissions", "env", "a/b"]);
+    }
+
+    #[test]
+    fn test_apply_move_copy_test() {
+        let document = json!({"permissions": {"allow": ["Bash(rm:*)"], "deny": []}});
+        let patched = apply(
+            &document,
+            &patch(json!([
+                {"op": "test", "path": "/permissions/allow/0", "value": "Bash(rm:*)"},
+                {"op": "move", "from": "/permissions/allow/0", "path": "/permissions/deny/-"},
+                {"op": "copy", "from": "/permissions/deny", "path": "/permissions/ask"},
+            ])),
+        )
+        .unwrap();
+
+        assert_eq!(
+            patched,
+            json!({"permissions": {"allow": [], "deny": ["Bash(rm:*)"], "ask": ["Bash(rm:*)"]}})
+        );
+    }
+
+    #[test]
+    fn test_apply_failures_leave_document_unchanged() {
+        let document = json!({"model": "opus", "list": [1]});
+        let cases = [
+            (json!([{"op": "test", "path": "/model", "value": "sonnet"}]), "value is \"opus\""),
+            (json!([{"op": "remove", "path": "/missing"}]), "key 'missing' does not exist"),
+            (json!([{"op": "replace", "path": "/list/1", "value": 2}]), "out of bounds"),
+            (json!([{"op": "add", "path": "/list/01", "value": 2}]), "not an array index"),
+            (json!([{"op": "move", "from": "/list", "path": "/list/0"}]), "own child"),
+            (json!([{"op": "add", "path": "model", "value": 1}]), "not a JSON pointer"),
+        ];
+
+        for (operations, expected) in cases {
+            let error = apply(&document, &patch(operations)).unwrap_err().to_string();
+            assert!(error.contains(expected), "{} does not contain {}", error, expected);
+        }
+
+        let error = apply(
+            &document,
+            &patch(json!([
+                {"op": "replace", "path": "/model", "value": "sonnet"},
+                {"op": "remove", "path": "/nope"},
+            ])),
+        )
+        .unwrap_err();
+        assert!(error.to_string().starts_with("Parse error: Patch operation 1 (remove /nope) failed"));
+    }
+}
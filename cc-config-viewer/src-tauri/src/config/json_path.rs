@@ -0,0 +1,211 @@
+//! Structured config paths
+//!
+//! Resolves paths such as `permissions.allow[2]`, `mcpServers.github.env.TOKEN`
+//! or `hooks["PreToolUse"][0]` against a parsed document and returns the JSON
+//! pointer of every node they name. Keys may themselves contain dots, so a
+//! dotted path can match more than one node; every match is returned.
+
+use crate::config::spanned_json::escape_pointer;
+use serde_json::Value;
+
+/// One step of a structured path
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    /// Dotted name, which may join with its neighbours to form a key containing dots
+    Name(String),
+    /// Quoted key (`["a.b"]`) or JSON pointer segment, matched as-is
+    Key(String),
+    /// Array index (`[2]`)
+    Index(usize),
+}
+
+/// Split a structured path or JSON pointer into segments
+fn parse_path(path: &str) -> Vec<Segment> {
+    if let Some(pointer) = path.strip_prefix('/') {
+        return pointer
+            .split('/')
+            .map(|segment| Segment::Key(segment.replace("~1", "/").replace("~0", "~")))
+            .collect();
+    }
+
+    let mut segments = Vec::new();
+    let mut name = String::new();
+    let mut chars = path.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => {
+                if !name.is_empty() {
+                    segments.push(Segment::Name(std::mem::take(&mut name)));
+                }
+            }
+            '[' => {
+                if !name.is_empty() {
+                    segments.push(Segment::Name(std::mem::take(&mut name)));
+                }
+
+                let mut inner = String::new();
+                let mut quote = None;
+                let mut quoted = false;
+                for c in chars.by_ref() {
+                    match (quote, c) {
+                        (None, ']') => break,
+                        (None, '"' | '\'') if inner.is_empty() && !quoted => {
+                            quote = Some(c);
+                            quoted = true;
+                        }
+                        (Some(q), c) if c == q => quote = None,
+                        (_, c) => inner.push(c),
+                    }
+                }
+
+                match inner.parse::<usize>() {
+                    Ok(index) if !quoted => segments.push(Segment::Index(index)),
+                    _ => segments.push(Segment::Key(inner)),
+                }
+            }
+            c => name.push(c),
+        }
+    }
+
+    if !name.is_empty() {
+        segments.push(Segment::Name(name));
+    }
+
+    segments
+}
+
+/// Resolve a structured path to the JSON pointer of every matching node
+///
+/// Consecutive dotted names are also tried joined into one key, so
+/// `mcpServers.my.server` finds both `{"my.server": ..}` and
+/// `{"my": {"server": ..}}` when both exist, shortest keys first.
+pub fn resolve(value: &Value, path: &str) -> Vec<String> {
+    let segments = parse_path(path);
+    let mut matches = Vec::new();
+    resolve_segments(value, &segments, String::new(), &mut matches);
+    matches
+}
+
+fn resolve_segments(value: &Value, segments: &[Segment], pointer: String, matches: &mut Vec<String>) {
+    let (first, rest) = match segments.split_first() {
+        Some(split) => split,
+        None => {
+            matches.push(pointer);
+            return;
+        }
+    };
+
+    match first {
+        Segment::Index(index) => {
+            if let Some(child) = value.as_array().and_then(|array| array.get(*index)) {
+                resolve_segments(child, rest, format!("{}/{}", pointer, index), matches);
+            }
+        }
+        Segment::Key(key) => {
+            if let Some((child, segment)) = child_by_key(value, key) {
+                resolve_segments(child, rest, format!("{}/{}", pointer, segment), matches);
+            }
+        }
+        Segment::Name(_) => {
+            // Try joining this name with each following name to form keys containing dots
+            let names = segments
+                .iter()
+                .take_while(|segment| matches!(segment, Segment::Name(_)))
+                .count();
+
+            let mut key = String::new();
+            for (taken, segment) in segments[..names].iter().enumerate() {
+                if let Segment::Name(name) = segment {
+                    if taken > 0 {
+                        key.push('.');
+                    }
+                    key.push_str(name);
+                }
+
+                if let Some((child, escaped)) = child_by_key(value, &key) {
+                    resolve_segments(
+                        child,
+                        &segments[taken + 1..],
+                        format!("{}/{}", pointer, escaped),
+                        matches,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Look up an object key, or an array index written as a key
+fn child_by_key<'a>(value: &'a Value, key: &str) -> Option<(&'a Value, String)> {
+    match value {
+        Value::Object(object) => object.get(key).map(|child| (child, escape_pointer(key))),
+        Value::Array(array) => {
+            let index = key.parse::<usize>().ok()?;
+            array.get(index).map(|child| (child, index.to_string()))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_path() {
+        assert_eq!(
+            parse_path("permissions.allow[2]"),
+            vec![
+                Segment::Name("permissions".to_string()),
+                Segment::Name("allow".to_string()),
+                Segment::Index(2),
+            ]
+        );
+        assert_eq!(
+            parse_path(r#"projects["/home/a.b"].mcpServers"#),
+            vec![
+                Segment::Name("projects".to_string()),
+                Segment::Key("/home/a.b".to_string()),
+                Segment::Name("mcpServers".to_string()),
+            ]
+        );
+        assert_eq!(parse_path("/env/a~1b"), vec![Segment::Key("env".to_string()), Segment::Key("a/b".to_string())]);
+    }
+
+    #[test]
+    fn test_resolve_nested_paths() {
+        let value = json!({
+            "permissions": {"allow": ["Read", "Edit", "Bash(npm test)"]},
+            "mcpServers": {"github": {"env": {"TOKEN": "x"}}}
+        });
+
+        assert_eq!(resolve(&value, "permissions.allow[2]"), vec!["/permissions/allow/2"]);
+        assert_eq!(resolve(&value, "permissions.allow.1"), vec!["/permissions/allow/1"]);
+        assert_eq!(resolve(&value, "mcpServers.github.env.TOKEN"), vec!["/mcpServers/github/env/TOKEN"]);
+        assert!(resolve(&value, "permissions.allow[3]").is_empty());
+        assert!(resolve(&value, "permissions.deny").is_empty());
+    }
+
+    #[test]
+    fn test_resolve_keys_containing_dots() {
+        let value = json!({
+            "mcpServers": {
+                "my.server": {"command": "a"},
+                "my": {"server": {"command": "b"}}
+            }
+        });
+
+        assert_eq!(
+            resolve(&value, "mcpServers.my.server"),
+            vec!["/mcpServers/my/server", "/mcpServers/my.server"]
+        );
+        assert_eq!(resolve(&value, r#"mcpServers["my.server"]"#), vec!["/mcpServers/my.server"]);
+    }
+
+    #[test]
+    fn test_resolve_empty_path_is_root() {
+        assert_eq!(resolve(&json!({"a": 1}), ""), vec![""]);
+    }
+}
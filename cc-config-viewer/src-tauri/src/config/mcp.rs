@@ -0,0 +1,131 @@
+//! MCP server discovery
+//!
+//! Reads `mcpServers` from `.mcp.json` (project scope), the top level of
+//! `~/.claude.json` (user scope) and the project's entry in
+//! `~/.claude.json` (local scope).
+
+use crate::config::{claude_json, reader};
+use crate::types::app::{AppError, ConfigScope};
+use crate::types::mcp::{McpServer, McpServerEntry};
+use serde_json::Value;
+use std::path::Path;
+
+/// Parse an `mcpServers` object, skipping entries that do not fit the model
+pub fn parse_servers(servers: Option<&Value>, scope: ConfigScope, path: &Path) -> Vec<McpServerEntry> {
+    let servers = match servers.and_then(|s| s.as_object()) {
+        Some(servers) => servers,
+        None => return Vec::new(),
+    };
+
+    servers
+        .iter()
+        .filter_map(|(name, value)| match McpServer::from_value(value) {
+            Ok(server) => Some(McpServerEntry {
+                name: name.clone(),
+                server,
+                scope,
+                path: path.to_string_lossy().to_string(),
+            }),
+            Err(e) => {
+                eprintln!("Warning: Skipping MCP server '{}' in {}: {}", name, path.display(), e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Servers defined in a project's `.mcp.json`
+pub fn read_mcp_json(path: &Path) -> Result<Vec<McpServerEntry>, AppError> {
+    let content = std::fs::read_to_string(path).map_err(AppError::from)?;
+    // Syntax problems are reported by validation
+    let (value, _) = reader::parse_json_recovering(path, &content)?;
+    Ok(parse_servers(value.get("mcpServers"), ConfigScope::Project, path))
+}
+
+/// User servers, then the project's `.mcp.json` and local servers
+pub fn list_servers(project_dir: Option<&Path>) -> Vec<McpServerEntry> {
+    list_servers_from(claude_json::claude_json_path().as_deref(), project_dir)
+}
+
+fn list_servers_from(claude_json_path: Option<&Path>, project_dir: Option<&Path>) -> Vec<McpServerEntry> {
+    let claude_json = claude_json_path
+        .filter(|path| path.is_file())
+        .and_then(|path| match claude_json::read_claude_json(path) {
+            Ok(value) => Some((path, value)),
+            Err(e) => {
+                eprintln!("Warning: Failed to read {}: {}", path.display(), e);
+                None
+            }
+        });
+
+    let mut servers = Vec::new();
+
+    if let Some((path, value)) = &claude_json {
+        servers.extend(parse_servers(value.get("mcpServers"), ConfigScope::User, path));
+    }
+
+    if let Some(project_dir) = project_dir {
+        let mcp_json = project_dir.join(".mcp.json");
+        if mcp_json.is_file() {
+            match read_mcp_json(&mcp_json) {
+                Ok(project_servers) => servers.extend(project_servers),
+                Err(e) => eprintln!("Warning: Failed to read {}: {}", mcp_json.display(), e),
+            }
+        }
+
+        if let Some((path, value)) = &claude_json {
+            if let Some(entry) = claude_json::project_entry(value, project_dir) {
+                servers.extend(parse_servers(entry.get("mcpServers"), ConfigScope::Local, path));
+            }
+        }
+    }
+
+    servers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_servers_from_every_scope() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let project = temp_dir.path().join("app");
+        std::fs::create_dir_all(&project).unwrap();
+        std::fs::write(
+            project.join(".mcp.json"),
+            r#"{"mcpServers": {
+                "github": {"command": "gh-mcp", "env": {"TOKEN": "x"}},
+                "broken": {"type": "sse"}
+            }}"#,
+        )
+        .unwrap();
+
+        let claude_json = temp_dir.path().join(".claude.json");
+        let content = serde_json::json!({
+            "mcpServers": {"docs": {"type": "http", "url": "https://docs/mcp"}},
+            "projects": {
+                project.to_string_lossy(): {"mcpServers": {"db": {"type": "sse", "url": "http://localhost:9000/sse"}}}
+            }
+        });
+        std::fs::write(&claude_json, content.to_string()).unwrap();
+
+        let servers = list_servers_from(Some(&claude_json), Some(&project));
+        let summary: Vec<(&str, ConfigScope)> = servers.iter().map(|s| (s.name.as_str(), s.scope)).collect();
+
+        assert_eq!(
+            summary,
+            vec![("docs", ConfigScope::User), ("github", ConfigScope::Project), ("db", ConfigScope::Local)]
+        );
+        assert!(matches!(servers[0].server, McpServer::Http { .. }));
+        assert!(matches!(&servers[1].server, McpServer::Stdio { env, .. } if env["TOKEN"] == "x"));
+        assert!(matches!(servers[2].server, McpServer::Sse { .. }));
+    }
+
+    #[test]
+    fn test_list_servers_without_project() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let missing = temp_dir.path().join(".claude.json");
+        assert!(list_servers_from(Some(&missing), None).is_empty());
+    }
+}
@@ -0,0 +1,241 @@
+//! Cached MCP server inventories
+//!
+//! Probing a server is slow and may start processes, so the tools, prompts
+//! and resources of each successful probe are kept in
+//! `<data dir>/cc-config-viewer/mcp-inventory.json`, keyed by project path
+//! and server name.
+
+use crate::config::{tools, writer};
+use crate::types::app::{AppError, Capability};
+use crate::types::mcp::{McpInventory, McpProbeResult, McpServerEntry};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Inventories by project path, then server name
+type InventoryCache = BTreeMap<String, BTreeMap<String, McpInventory>>;
+
+/// Serializes cache updates from concurrent refreshes
+static CACHE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Location of the inventory cache
+pub fn cache_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("cc-config-viewer").join("mcp-inventory.json"))
+}
+
+/// Key a project is cached under; canonical so different spellings share one entry
+fn project_key(project_dir: &Path) -> String {
+    project_dir
+        .canonicalize()
+        .unwrap_or_else(|_| project_dir.to_path_buf())
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Read the cache, treating a missing or unreadable file as empty
+fn load_cache(path: &Path) -> InventoryCache {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(_) => return InventoryCache::new(),
+    };
+
+    serde_json::from_str(&content).unwrap_or_else(|e| {
+        eprintln!("Warning: Ignoring unreadable MCP inventory cache {}: {}", path.display(), e);
+        InventoryCache::new()
+    })
+}
+
+fn save_cache(path: &Path, cache: &InventoryCache) -> Result<(), AppError> {
+    let content = serde_json::to_string_pretty(cache)?;
+    writer::write_atomic(path, &content)
+}
+
+/// Store the inventory of every successful probe for a project
+///
+/// Failed probes leave the previous inventory in place.
+pub fn record_probes(
+    cache_path: &Path,
+    project_dir: &Path,
+    probes: &[(McpServerEntry, McpProbeResult)],
+) -> Result<(), AppError> {
+    let _guard = CACHE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut cache = load_cache(cache_path);
+    let project = cache.entry(project_key(project_dir)).or_default();
+    let updated_at = chrono::Utc::now().to_rfc3339();
+
+    for (entry, result) in probes.iter().filter(|(_, result)| result.success) {
+        project.insert(
+            entry.name.clone(),
+            McpInventory {
+                name: entry.name.clone(),
+                server: entry.server.clone(),
+                scope: entry.scope,
+                server_name: result.server_name.clone(),
+                server_version: result.server_version.clone(),
+                tools: result.tools.clone().unwrap_or_default(),
+                prompts: result.prompts.clone().unwrap_or_default(),
+                resources: result.resources.clone().unwrap_or_default(),
+                updated_at: updated_at.clone(),
+            },
+        );
+    }
+
+    save_cache(cache_path, &cache)
+}
+
+/// Cached inventories for the servers a project currently configures
+///
+/// Inventories recorded for a different server definition are stale and
+/// left out, as are servers that were never probed.
+pub fn project_inventory(cache_path: &Path, project_dir: &Path, servers: &[McpServerEntry]) -> Vec<McpInventory> {
+    let mut cache = load_cache(cache_path);
+    let mut project = cache.remove(&project_key(project_dir)).unwrap_or_default();
+
+    servers
+        .iter()
+        .filter_map(|entry| {
+            project
+                .remove(&entry.name)
+                .filter(|inventory| inventory.server == entry.server)
+        })
+        .collect()
+}
+
+/// One capability per cached tool, named as Claude Code names MCP tools
+pub fn tool_capabilities(inventories: &[McpInventory]) -> Vec<Capability> {
+    inventories
+        .iter()
+        .flat_map(|inventory| {
+            inventory.tools.iter().map(move |tool| Capability {
+                id: tools::mcp_tool_name(&inventory.name, &tool.name),
+                key: format!("mcpServers.{}.tools.{}", inventory.name, tool.name),
+                value: serde_json::to_value(tool).unwrap_or_default(),
+                source: inventory.scope.as_str().to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::app::ConfigScope;
+    use crate::types::mcp::{McpServer, McpTool};
+    use serde_json::json;
+
+    fn server(name: &str, command: &str) -> McpServerEntry {
+        McpServerEntry {
+            name: name.to_string(),
+            server: McpServer::from_value(&json!({"command": command})).unwrap(),
+            scope: ConfigScope::Project,
+            path: "/p/.mcp.json".to_string(),
+        }
+    }
+
+    fn probe(name: &str, success: bool, tools: &[&str]) -> McpProbeResult {
+        McpProbeResult {
+            name: name.to_string(),
+            transport: "stdio".to_string(),
+            success,
+            server_name: None,
+            server_version: None,
+            protocol_version: None,
+            capabilities: None,
+            tools: Some(
+                tools
+                    .iter()
+                    .map(|tool| McpTool {
+                        name: tool.to_string(),
+                        description: None,
+                    })
+                    .collect(),
+            ),
+            prompts: None,
+            resources: None,
+            list_errors: Vec::new(),
+            error: None,
+            stderr: None,
+            duration_ms: 1,
+        }
+    }
+
+    #[test]
+    fn test_record_and_read_inventory() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let cache = temp_dir.path().join("data").join("mcp-inventory.json");
+        let project = temp_dir.path();
+
+        let github = server("github", "gh-mcp");
+        let db = server("db", "db-mcp");
+        record_probes(
+            &cache,
+            project,
+            &[(github.clone(), probe("github", true, &["create_issue"])), (db.clone(), probe("db", false, &[]))],
+        )
+        .unwrap();
+
+        let inventory = project_inventory(&cache, project, &[github.clone(), db]);
+        assert_eq!(inventory.len(), 1);
+        assert_eq!(inventory[0].tools[0].name, "create_issue");
+
+        // A changed definition makes the cached inventory stale
+        let changed = server("github", "gh-mcp-v2");
+        assert!(project_inventory(&cache, project, &[changed]).is_empty());
+
+        // Other projects have their own inventories
+        let other = tempfile::tempdir().unwrap();
+        assert!(project_inventory(&cache, other.path(), &[github]).is_empty());
+    }
+
+    #[test]
+    fn test_failed_probe_keeps_previous_inventory() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let cache = temp_dir.path().join("mcp-inventory.json");
+        let github = server("github", "gh-mcp");
+
+        record_probes(&cache, temp_dir.path(), &[(github.clone(), probe("github", true, &["a"]))]).unwrap();
+        record_probes(&cache, temp_dir.path(), &[(github.clone(), probe("github", false, &[]))]).unwrap();
+
+        assert_eq!(project_inventory(&cache, temp_dir.path(), &[github])[0].tools.len(), 1);
+    }
+
+    #[test]
+    fn test_concurrent_records_keep_every_server() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let cache = temp_dir.path().join("mcp-inventory.json");
+        let servers: Vec<McpServerEntry> = (0..8).map(|i| server(&format!("s{}", i), "mcp")).collect();
+
+        std::thread::scope(|scope| {
+            for entry in &servers {
+                let (cache, project) = (&cache, temp_dir.path());
+                scope.spawn(move || {
+                    record_probes(cache, project, &[(entry.clone(), probe(&entry.name, true, &["a"]))]).unwrap();
+                });
+            }
+        });
+
+        assert_eq!(project_inventory(&cache, temp_dir.path(), &servers).len(), servers.len());
+    }
+
+    #[test]
+    fn test_unreadable_cache_is_empty() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let cache = temp_dir.path().join("mcp-inventory.json");
+        std::fs::write(&cache, "not json").unwrap();
+        assert!(project_inventory(&cache, temp_dir.path(), &[server("a", "a")]).is_empty());
+    }
+
+    #[test]
+    fn test_tool_capabilities() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let cache = temp_dir.path().join("mcp-inventory.json");
+        let github = server("github", "gh-mcp");
+        record_probes(&cache, temp_dir.path(), &[(github.clone(), probe("github", true, &["create_issue"]))]).unwrap();
+
+        let capabilities = tool_capabilities(&project_inventory(&cache, temp_dir.path(), &[github]));
+        assert_eq!(capabilities.len(), 1);
+        assert_eq!(capabilities[0].id, "mcp__github__create_issue");
+        assert_eq!(capabilities[0].key, "mcpServers.github.tools.create_issue");
+        assert_eq!(capabilities[0].source, "project");
+    }
+}
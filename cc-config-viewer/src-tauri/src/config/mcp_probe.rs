@@ -0,0 +1,929 @@
+//! MCP server liveness probe
+//!
+//! Starts a stdio server or connects to an SSE/HTTP server, performs the
+//! JSON-RPC `initialize` handshake and lists the server's tools, prompts and
+//! resources, so servers that crash on startup or never answer can be told
+//! apart from working ones. `${VAR}` references in the definition are
+//! expanded against the environment first, as Claude Code does.
+
+use crate::config::mcp_env;
+use crate::types::mcp::{McpProbeResult, McpPrompt, McpResource, McpServer, McpServerEntry, McpTool};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, ACCEPT, CONTENT_TYPE};
+use reqwest::Url;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{ChildStdin, ChildStdout};
+
+/// Time allowed for the whole handshake when the caller does not choose one
+pub const DEFAULT_PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Protocol revision sent in `initialize`
+const PROTOCOL_VERSION: &str = "2025-06-18";
+
+/// How long a stdio server that closed its pipes is given to exit before its status is read
+const EXIT_GRACE: Duration = Duration::from_millis(500);
+
+/// Error for a stdio server that closed its pipes, whether seen on read or write
+const SERVER_CLOSED: &str = "Server closed its stdio before responding";
+
+/// Most stderr output kept from a failed stdio server
+const MAX_STDERR_BYTES: usize = 16 * 1024;
+
+/// Session id header of the streamable HTTP transport
+const SESSION_HEADER: &str = "mcp-session-id";
+
+/// Most pages followed for one list, guarding against servers that repeat a cursor
+const MAX_LIST_PAGES: usize = 50;
+
+const INITIALIZE_ID: u64 = 1;
+
+fn initialize_request() -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": INITIALIZE_ID,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": PROTOCOL_VERSION,
+            "capabilities": {},
+            "clientInfo": {"name": "cc-config-viewer", "version": env!("CARGO_PKG_VERSION")}
+        }
+    })
+}
+
+fn initialized_notification() -> Value {
+    json!({"jsonrpc": "2.0", "method": "notifications/initialized"})
+}
+
+fn list_request(id: u64, method: &str, cursor: Option<&str>) -> Value {
+    let params = match cursor {
+        Some(cursor) => json!({"cursor": cursor}),
+        None => json!({}),
+    };
+    json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params})
+}
+
+/// The `result` of a JSON-RPC response, or its `error` as a message
+fn result_of(response: Value) -> Result<Value, String> {
+    if let Some(error) = response.get("error") {
+        let message = error.get("message").and_then(|m| m.as_str()).unwrap_or("unknown error");
+        return Err(match error.get("code") {
+            Some(code) => format!("{} (code {})", message, code),
+            None => message.to_string(),
+        });
+    }
+
+    response
+        .get("result")
+        .cloned()
+        .ok_or_else(|| "Response has neither 'result' nor 'error'".to_string())
+}
+
+/// Whether a message is the response to the request with `id`
+fn is_response_to(message: &Value, id: u64) -> bool {
+    message.get("id").and_then(|i| i.as_u64()) == Some(id)
+        && (message.get("result").is_some() || message.get("error").is_some())
+}
+
+/// A connection that can carry JSON-RPC messages to a server
+trait Transport {
+    /// Send a request and wait for the response with the same id
+    async fn request(&mut self, message: Value, id: u64) -> Result<Value, String>;
+
+    /// Send a notification, which has no response
+    async fn notify(&mut self, message: Value) -> Result<(), String>;
+}
+
+/// What a successful handshake learned about the server
+struct Handshake {
+    initialize: Value,
+    tools: Option<Vec<McpTool>>,
+    prompts: Option<Vec<McpPrompt>>,
+    resources: Option<Vec<McpResource>>,
+    /// Failures of the optional lists
+    list_errors: Vec<String>,
+}
+
+/// JSON-RPC session state shared by the requests of one handshake
+struct Session<'a, T: Transport> {
+    transport: &'a mut T,
+    next_id: u64,
+}
+
+impl<T: Transport> Session<'_, T> {
+    /// Collect every page of a list method, or `None` when the capability is not advertised
+    async fn list_all(&mut self, initialize: &Value, capability: &str, method: &str) -> Result<Option<Vec<Value>>, String> {
+        if initialize.pointer(&format!("/capabilities/{}", capability)).is_none() {
+            return Ok(None);
+        }
+
+        let mut items = Vec::new();
+        let mut cursor: Option<String> = None;
+        for _ in 0..MAX_LIST_PAGES {
+            let id = self.next_id;
+            self.next_id += 1;
+
+            let response = self.transport.request(list_request(id, method, cursor.as_deref()), id).await?;
+            let result = result_of(response).map_err(|e| format!("{} failed: {}", method, e))?;
+            items.extend(result.get(capability).and_then(|i| i.as_array()).cloned().unwrap_or_default());
+
+            cursor = result.get("nextCursor").and_then(|c| c.as_str()).map(String::from);
+            if cursor.is_none() {
+                break;
+            }
+        }
+
+        Ok(Some(items))
+    }
+}
+
+async fn handshake<T: Transport>(transport: &mut T) -> Result<Handshake, String> {
+    let response = transport.request(initialize_request(), INITIALIZE_ID).await?;
+    let initialize = result_of(response).map_err(|e| format!("initialize failed: {}", e))?;
+    transport.notify(initialized_notification()).await?;
+
+    let mut session = Session {
+        transport,
+        next_id: INITIALIZE_ID + 1,
+    };
+    let tools = session.list_all(&initialize, "tools", "tools/list").await?;
+
+    // Prompts and resources are extras; a failing list should not hide the tools
+    let mut list_errors = Vec::new();
+    let mut optional = |listed: Result<Option<Vec<Value>>, String>| {
+        listed.unwrap_or_else(|error| {
+            list_errors.push(error);
+            None
+        })
+    };
+    let prompts = optional(session.list_all(&initialize, "prompts", "prompts/list").await);
+    let resources = optional(session.list_all(&initialize, "resources", "resources/list").await);
+
+    Ok(Handshake {
+        tools: tools.map(|items| items.iter().filter_map(parse_tool).collect()),
+        prompts: prompts.map(|items| items.iter().filter_map(parse_prompt).collect()),
+        resources: resources.map(|items| items.iter().filter_map(parse_resource).collect()),
+        list_errors,
+        initialize,
+    })
+}
+
+fn text(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(|v| v.as_str()).map(String::from)
+}
+
+fn parse_tool(tool: &Value) -> Option<McpTool> {
+    Some(McpTool {
+        name: text(tool, "name")?,
+        description: text(tool, "description"),
+    })
+}
+
+fn parse_prompt(prompt: &Value) -> Option<McpPrompt> {
+    Some(McpPrompt {
+        name: text(prompt, "name")?,
+        description: text(prompt, "description"),
+    })
+}
+
+fn parse_resource(resource: &Value) -> Option<McpResource> {
+    Some(McpResource {
+        uri: text(resource, "uri")?,
+        name: text(resource, "name"),
+        description: text(resource, "description"),
+        mime_type: text(resource, "mimeType"),
+    })
+}
+
+/// Newline-delimited JSON-RPC over a child process's stdin and stdout
+struct StdioTransport {
+    stdin: ChildStdin,
+    stdout: Lines<BufReader<ChildStdout>>,
+    /// Set once the server closed stdout or stopped reading stdin
+    closed: bool,
+}
+
+impl StdioTransport {
+    /// Map a write error, treating a broken pipe like end of output
+    fn write_error(&mut self, error: std::io::Error) -> String {
+        if error.kind() == std::io::ErrorKind::BrokenPipe {
+            self.closed = true;
+            return SERVER_CLOSED.to_string();
+        }
+        format!("Failed to write to server stdin: {}", error)
+    }
+}
+
+impl Transport for StdioTransport {
+    async fn request(&mut self, message: Value, id: u64) -> Result<Value, String> {
+        self.notify(message).await?;
+
+        loop {
+            let line = match self.stdout.next_line().await {
+                Ok(Some(line)) => line,
+                Ok(None) => {
+                    self.closed = true;
+                    return Err(SERVER_CLOSED.to_string());
+                }
+                Err(e) => return Err(format!("Failed to read server output: {}", e)),
+            };
+
+            // Servers may log to stdout or send notifications before the response
+            match serde_json::from_str::<Value>(&line) {
+                Ok(message) if is_response_to(&message, id) => return Ok(message),
+                _ => continue,
+            }
+        }
+    }
+
+    async fn notify(&mut self, message: Value) -> Result<(), String> {
+        let mut line = message.to_string();
+        line.push('\n');
+        if let Err(e) = self.stdin.write_all(line.as_bytes()).await {
+            return Err(self.write_error(e));
+        }
+        if let Err(e) = self.stdin.flush().await {
+            return Err(self.write_error(e));
+        }
+        Ok(())
+    }
+}
+
+/// Read a stream to the end, keeping at most `MAX_STDERR_BYTES`
+async fn read_capped<R: AsyncReadExt + Unpin>(mut reader: R) -> String {
+    let mut kept = Vec::new();
+    let mut buffer = [0u8; 4096];
+
+    while let Ok(read) = reader.read(&mut buffer).await {
+        if read == 0 {
+            break;
+        }
+        let room = MAX_STDERR_BYTES.saturating_sub(kept.len());
+        kept.extend_from_slice(&buffer[..read.min(room)]);
+    }
+
+    String::from_utf8_lossy(&kept).to_string()
+}
+
+async fn probe_stdio(
+    command: &str,
+    args: &[String],
+    env: &BTreeMap<String, String>,
+    cwd: Option<&Path>,
+    timeout: Duration,
+) -> (Result<Handshake, String>, String) {
+    let mut process = tokio::process::Command::new(command);
+    process
+        .args(args)
+        .envs(env)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    if let Some(cwd) = cwd {
+        process.current_dir(cwd);
+    }
+
+    let mut child = match process.spawn() {
+        Ok(child) => child,
+        Err(e) => return (Err(format!("Failed to start '{}': {}", command, e)), String::new()),
+    };
+
+    let stderr_task = child.stderr.take().map(|stderr| tokio::spawn(read_capped(stderr)));
+    let (stdin, stdout) = match (child.stdin.take(), child.stdout.take()) {
+        (Some(stdin), Some(stdout)) => (stdin, stdout),
+        _ => return (Err("Failed to open server stdio".to_string()), String::new()),
+    };
+
+    let mut transport = StdioTransport {
+        stdin,
+        stdout: BufReader::new(stdout).lines(),
+        closed: false,
+    };
+    let mut outcome = tokio::time::timeout(timeout, handshake(&mut transport))
+        .await
+        .unwrap_or_else(|_| Err(format!("Timed out after {} ms", timeout.as_millis())));
+    let closed = transport.closed;
+    drop(transport);
+
+    // A server that closed its pipes is exiting; its status says more than the pipe error
+    if let Err(error) = &mut outcome {
+        if closed {
+            if let Ok(Ok(status)) = tokio::time::timeout(EXIT_GRACE, child.wait()).await {
+                *error = format!("{} (server exited with {})", error, status);
+            }
+        }
+    }
+    let _ = child.kill().await;
+
+    let stderr = match stderr_task {
+        Some(task) => tokio::time::timeout(Duration::from_secs(1), task)
+            .await
+            .ok()
+            .and_then(|joined| joined.ok())
+            .unwrap_or_default(),
+        None => String::new(),
+    };
+
+    (outcome, stderr)
+}
+
+/// Headers from the server config, skipping any that are not valid HTTP headers
+fn header_map(headers: &BTreeMap<String, String>) -> HeaderMap {
+    let mut map = HeaderMap::new();
+    for (name, value) in headers {
+        match (HeaderName::from_bytes(name.as_bytes()), HeaderValue::from_str(value)) {
+            (Ok(name), Ok(value)) => {
+                map.insert(name, value);
+            }
+            _ => eprintln!("Warning: Skipping invalid MCP header '{}'", name),
+        }
+    }
+    map
+}
+
+/// A server-sent event
+struct SseEvent {
+    event: String,
+    data: String,
+}
+
+/// Incremental reader of a `text/event-stream` response body
+struct EventStream {
+    response: reqwest::Response,
+    buffer: String,
+}
+
+impl EventStream {
+    fn new(response: reqwest::Response) -> Self {
+        Self {
+            response,
+            buffer: String::new(),
+        }
+    }
+
+    async fn next_event(&mut self) -> Result<Option<SseEvent>, String> {
+        loop {
+            if let Some(event) = self.take_event() {
+                return Ok(Some(event));
+            }
+
+            match self.response.chunk().await {
+                Ok(Some(bytes)) => {
+                    self.buffer.push_str(&String::from_utf8_lossy(&bytes).replace("\r\n", "\n"));
+                }
+                Ok(None) => return Ok(None),
+                Err(e) => return Err(format!("Event stream failed: {}", e)),
+            }
+        }
+    }
+
+    /// Remove the first complete event from the buffer
+    fn take_event(&mut self) -> Option<SseEvent> {
+        loop {
+            let end = self.buffer.find("\n\n")?;
+            let block: String = self.buffer.drain(..end + 2).collect();
+
+            let mut event = "message".to_string();
+            let mut data = Vec::new();
+            for line in block.lines() {
+                if let Some(value) = line.strip_prefix("event:") {
+                    event = value.trim().to_string();
+                } else if let Some(value) = line.strip_prefix("data:") {
+                    data.push(value.strip_prefix(' ').unwrap_or(value));
+                }
+            }
+
+            // Blocks with only comments or ids carry no event
+            if !data.is_empty() {
+                return Some(SseEvent {
+                    event,
+                    data: data.join("\n"),
+                });
+            }
+        }
+    }
+
+    /// Wait for the JSON-RPC response with `id`
+    async fn response_to(&mut self, id: u64) -> Result<Value, String> {
+        while let Some(event) = self.next_event().await? {
+            if event.event != "message" {
+                continue;
+            }
+            if let Ok(message) = serde_json::from_str::<Value>(&event.data) {
+                if is_response_to(&message, id) {
+                    return Ok(message);
+                }
+            }
+        }
+        Err("Event stream ended before the server responded".to_string())
+    }
+}
+
+/// POST a JSON-RPC message and fail on non-success statuses
+async fn post_message(
+    client: &reqwest::Client,
+    url: &str,
+    headers: &HeaderMap,
+    message: &Value,
+) -> Result<reqwest::Response, String> {
+    let response = client
+        .post(url)
+        .headers(headers.clone())
+        .header(ACCEPT, "application/json, text/event-stream")
+        .json(message)
+        .send()
+        .await
+        .map_err(|e| format!("Request to {} failed: {}", url, e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        let body: String = body.chars().take(500).collect();
+        return Err(format!("{} returned HTTP {}: {}", url, status, body.trim()));
+    }
+
+    Ok(response)
+}
+
+/// Streamable HTTP transport: every message is a POST, answered with JSON or an event stream
+struct HttpTransport {
+    client: reqwest::Client,
+    url: String,
+    headers: HeaderMap,
+}
+
+impl Transport for HttpTransport {
+    async fn request(&mut self, message: Value, id: u64) -> Result<Value, String> {
+        let response = post_message(&self.client, &self.url, &self.headers, &message).await?;
+
+        // Later requests must carry the session the server assigned during initialize
+        if let Some(session) = response.headers().get(SESSION_HEADER).cloned() {
+            self.headers.insert(SESSION_HEADER, session);
+        }
+
+        let is_stream = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with("text/event-stream"));
+
+        if is_stream {
+            EventStream::new(response).response_to(id).await
+        } else {
+            response
+                .json::<Value>()
+                .await
+                .map_err(|e| format!("Invalid JSON response from {}: {}", self.url, e))
+        }
+    }
+
+    async fn notify(&mut self, message: Value) -> Result<(), String> {
+        post_message(&self.client, &self.url, &self.headers, &message).await.map(|_| ())
+    }
+}
+
+/// Legacy SSE transport: responses arrive on a long-lived event stream and
+/// messages are POSTed to the endpoint the stream announces
+struct SseTransport {
+    client: reqwest::Client,
+    endpoint: String,
+    headers: HeaderMap,
+    events: EventStream,
+}
+
+impl SseTransport {
+    async fn connect(client: reqwest::Client, url: &str, headers: HeaderMap) -> Result<Self, String> {
+        let base = Url::parse(url).map_err(|e| format!("Invalid URL '{}': {}", url, e))?;
+        let response = client
+            .get(base.clone())
+            .headers(headers.clone())
+            .header(ACCEPT, "text/event-stream")
+            .send()
+            .await
+            .map_err(|e| format!("Connecting to {} failed: {}", url, e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("{} returned HTTP {}", url, response.status()));
+        }
+
+        let mut events = EventStream::new(response);
+        while let Some(event) = events.next_event().await? {
+            if event.event == "endpoint" {
+                let endpoint = base
+                    .join(event.data.trim())
+                    .map_err(|e| format!("Invalid endpoint '{}': {}", event.data, e))?;
+                return Ok(Self {
+                    client,
+                    endpoint: endpoint.to_string(),
+                    headers,
+                    events,
+                });
+            }
+        }
+
+        Err(format!("{} closed the event stream before announcing an endpoint", url))
+    }
+}
+
+impl Transport for SseTransport {
+    async fn request(&mut self, message: Value, id: u64) -> Result<Value, String> {
+        self.notify(message).await?;
+        self.events.response_to(id).await
+    }
+
+    async fn notify(&mut self, message: Value) -> Result<(), String> {
+        post_message(&self.client, &self.endpoint, &self.headers, &message).await.map(|_| ())
+    }
+}
+
+async fn probe_remote(server: &McpServer) -> Result<Handshake, String> {
+    let client = reqwest::Client::builder()
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    match server {
+        McpServer::Http { url, headers } => {
+            let mut transport = HttpTransport {
+                client,
+                url: url.clone(),
+                headers: header_map(headers),
+            };
+            handshake(&mut transport).await
+        }
+        McpServer::Sse { url, headers } => {
+            let mut transport = SseTransport::connect(client, url, header_map(headers)).await?;
+            handshake(&mut transport).await
+        }
+        McpServer::Stdio { .. } => Err("Not a remote server".to_string()),
+    }
+}
+
+/// Start or connect to a server and report whether it completes the handshake
+///
+/// `cwd` is the directory stdio servers are started in, normally the project.
+pub async fn probe_server(entry: &McpServerEntry, cwd: Option<&Path>, timeout: Duration) -> McpProbeResult {
+    let started = Instant::now();
+    let server = mcp_env::expand_server(&entry.server, &|name| std::env::var(name).ok());
+
+    let (transport, (outcome, stderr)) = match &server {
+        McpServer::Stdio { command, args, env } => ("stdio", probe_stdio(command, args, env, cwd, timeout).await),
+        remote => {
+            let transport = if matches!(remote, McpServer::Sse { .. }) { "sse" } else { "http" };
+            let outcome = tokio::time::timeout(timeout, probe_remote(remote))
+                .await
+                .unwrap_or_else(|_| Err(format!("Timed out after {} ms", timeout.as_millis())));
+            (transport, (outcome, String::new()))
+        }
+    };
+
+    let mut result = McpProbeResult {
+        name: entry.name.clone(),
+        transport: transport.to_string(),
+        success: outcome.is_ok(),
+        server_name: None,
+        server_version: None,
+        protocol_version: None,
+        capabilities: None,
+        tools: None,
+        prompts: None,
+        resources: None,
+        list_errors: Vec::new(),
+        error: None,
+        stderr: None,
+        duration_ms: started.elapsed().as_millis() as u64,
+    };
+
+    match outcome {
+        Ok(handshake) => {
+            let info = |pointer: &str| {
+                handshake
+                    .initialize
+                    .pointer(pointer)
+                    .and_then(|v| v.as_str())
+                    .map(String::from)
+            };
+            result.server_name = info("/serverInfo/name");
+            result.server_version = info("/serverInfo/version");
+            result.protocol_version = info("/protocolVersion");
+            result.capabilities = handshake.initialize.get("capabilities").cloned();
+            result.tools = handshake.tools;
+            result.prompts = handshake.prompts;
+            result.resources = handshake.resources;
+            result.list_errors = handshake.list_errors;
+        }
+        Err(error) => {
+            result.error = Some(error);
+            result.stderr = Some(stderr).filter(|s| !s.trim().is_empty());
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::app::ConfigScope;
+    use std::sync::Arc;
+    use tokio::io::AsyncWriteExt;
+    use tokio::net::{TcpListener, TcpStream};
+    use tokio::sync::Mutex;
+
+    const INITIALIZE_RESULT: &str = r#"{"protocolVersion":"2025-06-18","capabilities":{"tools":{}},"serverInfo":{"name":"stub","version":"1.2.3"}}"#;
+    const TOOLS_RESULT: &str = r#"{"tools":[{"name":"echo","description":"Echo input"},{"name":"add"}]}"#;
+
+    fn entry(server: McpServer) -> McpServerEntry {
+        McpServerEntry {
+            name: "stub".to_string(),
+            server,
+            scope: ConfigScope::Project,
+            path: "/p/.mcp.json".to_string(),
+        }
+    }
+
+    fn shell(script: &str) -> McpServer {
+        McpServer::Stdio {
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), script.to_string()],
+            env: BTreeMap::new(),
+        }
+    }
+
+    fn response(id: u64, result: &str) -> String {
+        format!(r#"{{"jsonrpc":"2.0","id":{},"result":{}}}"#, id, result)
+    }
+
+    #[tokio::test]
+    async fn test_probe_stdio_server() {
+        // Reads initialize, the initialized notification and tools/list in order
+        let script = format!(
+            "read _; echo 'starting up'; echo '{}'; read _; read _; echo '{}'; sleep 5",
+            response(1, INITIALIZE_RESULT),
+            response(2, TOOLS_RESULT)
+        );
+
+        let result = probe_server(&entry(shell(&script)), None, Duration::from_secs(5)).await;
+
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.transport, "stdio");
+        assert_eq!(result.server_name.as_deref(), Some("stub"));
+        assert_eq!(result.server_version.as_deref(), Some("1.2.3"));
+        assert_eq!(result.protocol_version.as_deref(), Some("2025-06-18"));
+        let tools: Vec<&str> = result.tools.as_ref().unwrap().iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(tools, vec!["echo", "add"]);
+        assert!(result.stderr.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_probe_collects_paged_lists() {
+        let initialize = r#"{"protocolVersion":"2025-06-18","capabilities":{"tools":{},"prompts":{},"resources":{}},"serverInfo":{"name":"stub","version":"1"}}"#;
+        let script = format!(
+            "read _; echo '{}'; read _; read _; echo '{}'; read _; echo '{}'; read _; echo '{}'; read _; echo '{}'; sleep 5",
+            response(1, initialize),
+            response(2, r#"{"tools":[{"name":"a"}],"nextCursor":"page-2"}"#),
+            response(3, r#"{"tools":[{"name":"b"}]}"#),
+            response(4, r#"{"prompts":[{"name":"review","description":"Review a PR"}]}"#),
+            response(5, r#"{"resources":[{"uri":"file:///README.md","name":"README","mimeType":"text/markdown"}]}"#)
+        );
+
+        let result = probe_server(&entry(shell(&script)), None, Duration::from_secs(5)).await;
+
+        assert!(result.success, "{:?}", result.error);
+        let tools: Vec<String> = result.tools.unwrap().into_iter().map(|t| t.name).collect();
+        assert_eq!(tools, vec!["a", "b"]);
+        assert_eq!(result.prompts.unwrap()[0].description.as_deref(), Some("Review a PR"));
+        assert_eq!(result.resources.unwrap()[0].mime_type.as_deref(), Some("text/markdown"));
+    }
+
+    #[tokio::test]
+    async fn test_probe_keeps_tools_when_optional_list_fails() {
+        let initialize = r#"{"protocolVersion":"2025-06-18","capabilities":{"tools":{},"prompts":{},"resources":{}},"serverInfo":{"name":"stub","version":"1"}}"#;
+        let script = format!(
+            "read _; echo '{}'; read _; read _; echo '{}'; read _; echo '{}'; read _; echo '{}'",
+            response(1, initialize),
+            response(2, TOOLS_RESULT),
+            r#"{"jsonrpc":"2.0","id":3,"error":{"code":-32601,"message":"Method not found"}}"#,
+            response(4, r#"{"resources":[]}"#)
+        );
+
+        let result = probe_server(&entry(shell(&script)), None, Duration::from_secs(5)).await;
+
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.tools.unwrap().len(), 2);
+        assert!(result.prompts.is_none());
+        assert_eq!(result.resources, Some(Vec::new()));
+        assert_eq!(result.list_errors, vec!["prompts/list failed: Method not found (code -32601)"]);
+    }
+
+    #[tokio::test]
+    async fn test_probe_stdio_server_crash_reports_stderr() {
+        let server = shell("echo 'Error: GITHUB_TOKEN is not set' >&2; exit 1");
+        let result = probe_server(&entry(server), None, Duration::from_secs(5)).await;
+
+        assert!(!result.success);
+        assert_eq!(
+            result.error.as_deref(),
+            Some("Server closed its stdio before responding (server exited with exit status: 1)")
+        );
+        assert_eq!(result.stderr.as_deref().map(str::trim), Some("Error: GITHUB_TOKEN is not set"));
+    }
+
+    #[tokio::test]
+    async fn test_probe_expands_env_references() {
+        let server = McpServer::Stdio {
+            command: "${CC_CONFIG_VIEWER_PROBE_SHELL:-sh}".to_string(),
+            args: vec![
+                "-c".to_string(),
+                format!(
+                    "read _; echo '{}'; read _; read _; echo '{}'",
+                    response(1, INITIALIZE_RESULT),
+                    response(2, TOOLS_RESULT)
+                ),
+            ],
+            env: BTreeMap::new(),
+        };
+        let result = probe_server(&entry(server), None, Duration::from_secs(5)).await;
+
+        // Without expansion the literal `${...}` command fails to start
+        assert!(result.success, "{:?}", result.error);
+    }
+
+    #[tokio::test]
+    async fn test_probe_stdio_server_timeout() {
+        let result = probe_server(&entry(shell("sleep 5")), None, Duration::from_millis(200)).await;
+        assert!(!result.success);
+        assert_eq!(result.error.as_deref(), Some("Timed out after 200 ms"));
+    }
+
+    #[tokio::test]
+    async fn test_probe_missing_command() {
+        let server = McpServer::Stdio {
+            command: "/nonexistent/mcp-server".to_string(),
+            args: Vec::new(),
+            env: BTreeMap::new(),
+        };
+        let result = probe_server(&entry(server), None, Duration::from_secs(1)).await;
+        assert!(result.error.unwrap().starts_with("Failed to start"));
+    }
+
+    #[tokio::test]
+    async fn test_probe_reports_initialize_error() {
+        let script = r#"read _; echo '{"jsonrpc":"2.0","id":1,"error":{"code":-32602,"message":"Unsupported protocol version"}}'"#;
+        let result = probe_server(&entry(shell(script)), None, Duration::from_secs(5)).await;
+        assert_eq!(
+            result.error.as_deref(),
+            Some("initialize failed: Unsupported protocol version (code -32602)")
+        );
+    }
+
+    /// Read one HTTP request, returning the request line and body
+    async fn read_request(stream: &mut TcpStream) -> (String, String) {
+        let mut reader = BufReader::new(stream);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).await.unwrap();
+
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).await.unwrap();
+            if line.trim().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap();
+                }
+            }
+        }
+
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).await.unwrap();
+        (request_line, String::from_utf8(body).unwrap())
+    }
+
+    async fn write_response(stream: &mut TcpStream, status: &str, content_type: &str, body: &str) {
+        let response = format!(
+            "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nMcp-Session-Id: s-1\r\nConnection: close\r\n\r\n{}",
+            status,
+            content_type,
+            body.len(),
+            body
+        );
+        stream.write_all(response.as_bytes()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_probe_http_server() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/mcp", listener.local_addr().unwrap());
+
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let (_, body) = read_request(&mut stream).await;
+                let message: Value = serde_json::from_str(&body).unwrap();
+
+                match message["method"].as_str().unwrap() {
+                    "initialize" => {
+                        write_response(&mut stream, "200 OK", "application/json", &response(1, INITIALIZE_RESULT)).await
+                    }
+                    "tools/list" => {
+                        // Answer as an event stream, preceded by an unrelated notification
+                        let events = format!(
+                            "event: message\ndata: {{\"jsonrpc\":\"2.0\",\"method\":\"notifications/progress\"}}\n\nevent: message\ndata: {}\n\n",
+                            response(2, TOOLS_RESULT)
+                        );
+                        write_response(&mut stream, "200 OK", "text/event-stream", &events).await
+                    }
+                    _ => write_response(&mut stream, "202 Accepted", "application/json", "").await,
+                }
+            }
+        });
+
+        let server = McpServer::Http {
+            url,
+            headers: BTreeMap::from([("Authorization".to_string(), "Bearer t".to_string())]),
+        };
+        let result = probe_server(&entry(server), None, Duration::from_secs(5)).await;
+
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.transport, "http");
+        assert_eq!(result.server_name.as_deref(), Some("stub"));
+        assert_eq!(result.tools.unwrap()[0].description.as_deref(), Some("Echo input"));
+    }
+
+    #[tokio::test]
+    async fn test_probe_http_server_error_status() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/mcp", listener.local_addr().unwrap());
+
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            read_request(&mut stream).await;
+            write_response(&mut stream, "401 Unauthorized", "text/plain", "missing token").await;
+        });
+
+        let server = McpServer::Http {
+            url,
+            headers: BTreeMap::new(),
+        };
+        let result = probe_server(&entry(server), None, Duration::from_secs(5)).await;
+
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("HTTP 401 Unauthorized: missing token"));
+    }
+
+    #[tokio::test]
+    async fn test_probe_sse_server() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/sse", listener.local_addr().unwrap());
+        let stream_slot: Arc<Mutex<Option<TcpStream>>> = Arc::new(Mutex::new(None));
+
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let (request_line, body) = read_request(&mut stream).await;
+
+                if request_line.starts_with("GET") {
+                    let head = "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\n\r\n";
+                    stream.write_all(head.as_bytes()).await.unwrap();
+                    stream
+                        .write_all(b": connected\n\nevent: endpoint\ndata: /messages?session=1\n\n")
+                        .await
+                        .unwrap();
+                    *stream_slot.lock().await = Some(stream);
+                    continue;
+                }
+
+                write_response(&mut stream, "202 Accepted", "text/plain", "").await;
+                let message: Value = serde_json::from_str(&body).unwrap();
+                let reply = match message["method"].as_str().unwrap() {
+                    "initialize" => response(1, INITIALIZE_RESULT),
+                    "tools/list" => response(2, TOOLS_RESULT),
+                    _ => continue,
+                };
+
+                let mut slot = stream_slot.lock().await;
+                let events = slot.as_mut().unwrap();
+                events
+                    .write_all(format!("event: message\r\ndata: {}\r\n\r\n", reply).as_bytes())
+                    .await
+                    .unwrap();
+            }
+        });
+
+        let server = McpServer::Sse {
+            url,
+            headers: BTreeMap::new(),
+        };
+        let result = probe_server(&entry(server), None, Duration::from_secs(5)).await;
+
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.transport, "sse");
+        assert_eq!(result.tools.unwrap().len(), 2);
+    }
+}
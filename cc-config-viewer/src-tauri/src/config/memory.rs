@@ -0,0 +1,338 @@
+//! CLAUDE.md memory discovery
+//!
+//! Finds the memory files Claude Code loads for a project: the user memory,
+//! memory files between the repo root and the project, the project's own
+//! files and those in its subdirectories. `@path` imports are followed up to
+//! Claude Code's maximum depth.
+
+use crate::types::app::AppError;
+use crate::types::memory::{ImportStatus, MemoryFile, MemoryGraph, MemoryImport, MemorySource};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Maximum number of import hops Claude Code follows
+pub const MAX_IMPORT_DEPTH: u32 = 5;
+
+/// Deepest subdirectory searched for nested memory files
+const MAX_NESTED_DEPTH: usize = 8;
+
+/// Directories never searched for nested memory files
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "dist", "build", "vendor"];
+
+const MEMORY_FILE: &str = "CLAUDE.md";
+const LOCAL_MEMORY_FILE: &str = "CLAUDE.local.md";
+
+/// User memory file (`~/.claude/CLAUDE.md`)
+pub fn user_memory_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".claude").join(MEMORY_FILE))
+}
+
+/// Nearest directory at or above the project that contains `.git`
+fn repo_root(project_dir: &Path) -> Option<&Path> {
+    project_dir.ancestors().find(|dir| dir.join(".git").exists())
+}
+
+/// Memory files loaded at startup or on demand, in Claude Code's load order
+fn discover_roots(project_dir: &Path, user_memory: Option<&Path>) -> Vec<(PathBuf, MemorySource)> {
+    let mut roots = Vec::new();
+
+    if let Some(path) = user_memory {
+        roots.push((path.to_path_buf(), MemorySource::User));
+    }
+
+    // Directories from the repo root down to, but not including, the project
+    if let Some(root) = repo_root(project_dir) {
+        let mut ancestors: Vec<&Path> = project_dir
+            .ancestors()
+            .skip(1)
+            .take_while(|dir| dir.starts_with(root))
+            .collect();
+        ancestors.reverse();
+
+        for dir in ancestors {
+            roots.push((dir.join(MEMORY_FILE), MemorySource::Ancestor));
+            roots.push((dir.join(LOCAL_MEMORY_FILE), MemorySource::Ancestor));
+        }
+    }
+
+    roots.push((project_dir.join(MEMORY_FILE), MemorySource::Project));
+    roots.push((project_dir.join(".claude").join(MEMORY_FILE), MemorySource::Project));
+    roots.push((project_dir.join(LOCAL_MEMORY_FILE), MemorySource::Local));
+
+    let mut nested = Vec::new();
+    collect_nested(project_dir, 0, &mut nested);
+    nested.sort();
+    roots.extend(nested.into_iter().map(|path| (path, MemorySource::Nested)));
+
+    roots.retain(|(path, _)| path.is_file());
+    roots
+}
+
+fn collect_nested(dir: &Path, depth: usize, files: &mut Vec<PathBuf>) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry in entries.flatten() {
+        let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !is_dir || name.starts_with('.') || SKIPPED_DIRS.contains(&name.as_str()) {
+            continue;
+        }
+
+        let path = entry.path();
+        for file_name in [MEMORY_FILE, LOCAL_MEMORY_FILE] {
+            let file = path.join(file_name);
+            if file.is_file() {
+                files.push(file);
+            }
+        }
+
+        if depth + 1 < MAX_NESTED_DEPTH {
+            collect_nested(&path, depth + 1, files);
+        }
+    }
+}
+
+/// `@path` imports in a memory file with their 1-based line
+///
+/// Imports inside code spans and fenced code blocks are ignored, as are
+/// mentions like `@team` that do not look like a path.
+pub fn extract_imports(content: &str) -> Vec<(String, u32)> {
+    let mut imports = Vec::new();
+    let mut fence: Option<&str> = None;
+
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            continue;
+        }
+        if let Some(marker) = ["```", "~~~"].into_iter().find(|m| trimmed.starts_with(m)) {
+            fence = Some(marker);
+            continue;
+        }
+
+        for word in strip_code_spans(line).split_whitespace() {
+            let path = match word.strip_prefix('@') {
+                Some(path) => path.trim_end_matches([',', ';', ':', '!', '?', ')', '.']),
+                None => continue,
+            };
+            if !path.is_empty() && (path.contains('/') || path.contains('.') || path.starts_with('~')) {
+                imports.push((path.to_string(), index as u32 + 1));
+            }
+        }
+    }
+
+    imports
+}
+
+/// Blank out text between backticks
+fn strip_code_spans(line: &str) -> String {
+    let mut in_code = false;
+    line.chars()
+        .map(|c| {
+            if c == '`' {
+                in_code = !in_code;
+                ' '
+            } else if in_code {
+                ' '
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+/// Resolve an import relative to the file containing it
+fn resolve_import(raw: &str, from: &Path) -> PathBuf {
+    if let Some(rest) = raw.strip_prefix("~/") {
+        if let Some(home) = dirs::home_dir() {
+            return home.join(rest);
+        }
+    }
+
+    let path = Path::new(raw);
+    if path.is_absolute() {
+        return path.to_path_buf();
+    }
+
+    from.parent().unwrap_or(Path::new("")).join(path)
+}
+
+struct GraphBuilder {
+    files: Vec<MemoryFile>,
+    seen: HashSet<PathBuf>,
+}
+
+impl GraphBuilder {
+    /// Add a file and, depth first, everything it imports
+    fn visit(&mut self, path: PathBuf, source: MemorySource, depth: u32, stack: &mut Vec<PathBuf>) {
+        if !self.seen.insert(path.clone()) {
+            return;
+        }
+
+        let content = std::fs::read_to_string(&path).unwrap_or_else(|e| {
+            eprintln!("Warning: Failed to read memory file {}: {}", path.display(), e);
+            String::new()
+        });
+
+        let index = self.files.len();
+        self.files.push(MemoryFile {
+            path: path.to_string_lossy().to_string(),
+            source,
+            size: std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0),
+            depth,
+            imports: Vec::new(),
+        });
+
+        stack.push(path.clone());
+        for (raw, line) in extract_imports(&content) {
+            let resolved = resolve_import(&raw, &path);
+            let target = resolved.canonicalize().ok().filter(|target| target.is_file());
+
+            let status = match &target {
+                None => ImportStatus::Missing,
+                Some(target) if stack.contains(target) => ImportStatus::Cycle,
+                Some(_) if depth + 1 > MAX_IMPORT_DEPTH => ImportStatus::TooDeep,
+                Some(target) => {
+                    self.visit(target.clone(), MemorySource::Import, depth + 1, stack);
+                    ImportStatus::Resolved
+                }
+            };
+
+            self.files[index].imports.push(MemoryImport {
+                raw,
+                line,
+                target: target.unwrap_or(resolved).to_string_lossy().to_string(),
+                status,
+            });
+        }
+        stack.pop();
+    }
+}
+
+/// Build the import graph starting from the given memory files
+fn build_graph(roots: Vec<(PathBuf, MemorySource)>) -> MemoryGraph {
+    let mut builder = GraphBuilder {
+        files: Vec::new(),
+        seen: HashSet::new(),
+    };
+
+    for (path, source) in roots {
+        let path = path.canonicalize().unwrap_or(path);
+        builder.visit(path, source, 0, &mut Vec::new());
+    }
+
+    let total_size = builder.files.iter().map(|file| file.size).sum();
+    MemoryGraph {
+        files: builder.files,
+        total_size,
+    }
+}
+
+/// Every memory file that applies to a project, with the imports between them
+pub fn project_memory(project_dir: &Path) -> Result<MemoryGraph, AppError> {
+    if !project_dir.is_dir() {
+        return Err(AppError::Filesystem(format!(
+            "Project path does not exist or is not a directory: {}",
+            project_dir.display()
+        )));
+    }
+
+    let user_memory = user_memory_path();
+    Ok(build_graph(discover_roots(project_dir, user_memory.as_deref())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, content: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_extract_imports_skips_code() {
+        let content = "See @README.md and @docs/style.md.\nEmail me@example.com or ask @team\n`@not/this.md`\n```\n@nor/this.md\n```\n- @~/.claude/shared.md\n";
+        assert_eq!(
+            extract_imports(content),
+            vec![
+                ("README.md".to_string(), 1),
+                ("docs/style.md".to_string(), 1),
+                ("~/.claude/shared.md".to_string(), 7),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_discover_roots_order() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo = temp_dir.path().canonicalize().unwrap().join("repo");
+        let project = repo.join("packages").join("app");
+        std::fs::create_dir_all(repo.join(".git")).unwrap();
+        write(&temp_dir.path().join("CLAUDE.md"), "outside the repo");
+        write(&repo.join("CLAUDE.md"), "repo");
+        write(&project.join("CLAUDE.md"), "project");
+        write(&project.join("CLAUDE.local.md"), "local");
+        write(&project.join("src").join("api").join("CLAUDE.md"), "nested");
+        write(&project.join("node_modules").join("x").join("CLAUDE.md"), "skipped");
+        let user = temp_dir.path().join("user.md");
+        write(&user, "user");
+
+        assert_eq!(
+            discover_roots(&project, Some(&user)),
+            vec![
+                (user.clone(), MemorySource::User),
+                (repo.join("CLAUDE.md"), MemorySource::Ancestor),
+                (project.join("CLAUDE.md"), MemorySource::Project),
+                (project.join("CLAUDE.local.md"), MemorySource::Local),
+                (project.join("src").join("api").join("CLAUDE.md"), MemorySource::Nested),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_import_graph_cycles_and_missing() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path().canonicalize().unwrap();
+        write(&dir.join("CLAUDE.md"), "@docs/a.md\n@missing.md\n");
+        write(&dir.join("docs").join("a.md"), "# A\n@b.md\n");
+        write(&dir.join("docs").join("b.md"), "@../CLAUDE.md\n@a.md\n");
+
+        let graph = build_graph(vec![(dir.join("CLAUDE.md"), MemorySource::Project)]);
+        let paths: Vec<(&str, u32)> = graph.files.iter().map(|f| (f.path.as_str(), f.depth)).collect();
+        let a = dir.join("docs").join("a.md").to_string_lossy().to_string();
+        let b = dir.join("docs").join("b.md").to_string_lossy().to_string();
+        let root = dir.join("CLAUDE.md").to_string_lossy().to_string();
+        assert_eq!(paths, vec![(root.as_str(), 0), (a.as_str(), 1), (b.as_str(), 2)]);
+
+        let root_imports: Vec<ImportStatus> = graph.files[0].imports.iter().map(|i| i.status).collect();
+        assert_eq!(root_imports, vec![ImportStatus::Resolved, ImportStatus::Missing]);
+        assert_eq!(graph.files[0].imports[1].line, 2);
+        let b_imports: Vec<ImportStatus> = graph.files[2].imports.iter().map(|i| i.status).collect();
+        assert_eq!(b_imports, vec![ImportStatus::Cycle, ImportStatus::Cycle]);
+        assert_eq!(graph.files[2].imports[0].target, root);
+        assert_eq!(graph.total_size, graph.files.iter().map(|f| f.size).sum::<u64>());
+        assert_eq!(graph.files[1].size, 10);
+    }
+
+    #[test]
+    fn test_import_depth_limit() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path().canonicalize().unwrap();
+        for level in 0..7 {
+            write(&dir.join(format!("{}.md", level)), &format!("@{}.md", level + 1));
+        }
+
+        let graph = build_graph(vec![(dir.join("0.md"), MemorySource::Project)]);
+        assert_eq!(graph.files.len(), MAX_IMPORT_DEPTH as usize + 1);
+        let last = graph.files.last().unwrap();
+        assert_eq!(last.depth, MAX_IMPORT_DEPTH);
+        assert_eq!(last.imports[0].status, ImportStatus::TooDeep);
+    }
+}
@@ -1,3 +1,27 @@
+pub mod agents;
+pub mod claude_json;
+pub mod edit;
+pub mod frontmatter;
+pub mod history;
+pub mod hooks;
+pub mod json_patch;
+pub mod json_path;
+pub mod mcp;
+pub mod mcp_env;
+pub mod mcp_inventory;
+pub mod mcp_probe;
+pub mod memory;
+pub mod permission_lint;
+pub mod permissions;
 pub mod reader;
+pub mod resolver;
 pub mod settings;
+pub mod slash_commands;
+pub mod spanned_json;
+pub mod sync;
+pub mod text_diff;
+pub mod tools;
+pub mod transfer;
+pub mod validation;
 pub mod watcher;
+pub mod writer;
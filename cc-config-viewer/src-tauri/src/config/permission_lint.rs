@@ -0,0 +1,354 @@
+//! Permission rule linter
+//!
+//! Analyses the `permissions` rules of every settings scope together and
+//! reports rules that can never take effect or do nothing:
+//!
+//! * `PL001` - rule names a tool that does not exist
+//! * `PL002` - rule syntax is invalid
+//! * `PL003` - allow rule is covered by a broader allow rule
+//! * `PL004` - allow rule is always overridden by a deny or ask rule
+//! * `PL005` - the same rule appears more than once
+
+use crate::config::permissions::{self, PathContext, PermissionRule};
+use crate::config::reader;
+use crate::config::resolver::{self, ScopePaths, SettingsLayer};
+use crate::config::spanned_json::{self, SpannedDocument};
+use crate::config::tools;
+use crate::types::app::{AppError, ConfigScope, DiffSeverity, HealthIssue};
+use crate::types::error::error_codes;
+use crate::types::permission::PermissionBehavior;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Source positions of rules, parsed once per settings file
+struct RuleLocator {
+    documents: HashMap<PathBuf, Option<SpannedDocument>>,
+}
+
+impl RuleLocator {
+    fn new(layers: &[SettingsLayer]) -> Self {
+        let documents = layers
+            .iter()
+            .map(|layer| {
+                let content = match layer.scope {
+                    ConfigScope::Managed => reader::read_managed_file(&layer.path).ok(),
+                    _ => std::fs::read_to_string(&layer.path).ok(),
+                };
+                let document = content.and_then(|content| spanned_json::parse_tolerant(&content).document);
+                (layer.path.clone(), document)
+            })
+            .collect();
+
+        Self { documents }
+    }
+
+    /// `path:line:column` of a rule, or just the path when it cannot be located
+    fn locate(&self, rule: &PermissionRule) -> String {
+        let path = rule.path.display();
+        let location = self
+            .documents
+            .get(&rule.path)
+            .and_then(|document| document.as_ref())
+            .and_then(|document| document.location(&rule.pointer));
+
+        match location {
+            Some((line, column)) => format!("{}:{}:{}", path, line, column),
+            None => path.to_string(),
+        }
+    }
+
+    /// One location per line, for `HealthIssue.details`
+    fn details(&self, rules: &[&PermissionRule]) -> String {
+        rules
+            .iter()
+            .map(|rule| format!("{} ({} {})", self.locate(rule), rule.scope.as_str(), rule.behavior.as_str()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+fn issue(
+    code: &str,
+    rule: &PermissionRule,
+    severity: DiffSeverity,
+    message: String,
+    details: String,
+    project_id: &str,
+) -> HealthIssue {
+    HealthIssue {
+        id: format!("{}:{}#{}", code, rule.path.display(), rule.pointer),
+        type_: "warning".to_string(),
+        severity,
+        message,
+        details: Some(details),
+        project_id: project_id.to_string(),
+    }
+}
+
+/// Lint the permission rules of settings layers ordered from highest to lowest precedence
+pub fn lint_layers(
+    layers: &[SettingsLayer],
+    project_dir: &Path,
+    home: Option<&Path>,
+    project_id: &str,
+) -> Vec<HealthIssue> {
+    let rules = permissions::collect_rules(layers);
+    let locator = RuleLocator::new(layers);
+    let context = PathContext { project_dir, home };
+    let mut issues = Vec::new();
+
+    for rule in &rules {
+        match &rule.pattern {
+            None => issues.push(issue(
+                error_codes::PL002,
+                rule,
+                DiffSeverity::Medium,
+                format!("Permission rule '{}' is not valid; expected Tool or Tool(specifier)", rule.text),
+                locator.details(&[rule]),
+                project_id,
+            )),
+            Some(pattern) if !tools::is_known_tool(&pattern.tool) => issues.push(issue(
+                error_codes::PL001,
+                rule,
+                DiffSeverity::Medium,
+                format!("Permission rule '{}' names unknown tool '{}'", rule.text, pattern.tool),
+                locator.details(&[rule]),
+                project_id,
+            )),
+            Some(_) => {}
+        }
+    }
+
+    let allow_rules: Vec<&PermissionRule> = rules
+        .iter()
+        .filter(|rule| rule.behavior == PermissionBehavior::Allow && rule.pattern.is_some())
+        .collect();
+
+    for (index, rule) in allow_rules.iter().enumerate() {
+        // A deny rule wins over ask, so it is reported first
+        let overriding = rules
+            .iter()
+            .filter(|other| other.behavior != PermissionBehavior::Allow)
+            .find(|other| permissions::rule_covers(other, rule, &context));
+        if let Some(other) = overriding {
+            let effect = match other.behavior {
+                PermissionBehavior::Deny => "denied",
+                _ => "asked",
+            };
+            issues.push(issue(
+                error_codes::PL004,
+                rule,
+                DiffSeverity::Medium,
+                format!(
+                    "Allow rule '{}' never applies: calls it matches are always {} by '{}'",
+                    rule.text, effect, other.text
+                ),
+                locator.details(&[rule, other]),
+                project_id,
+            ));
+            continue;
+        }
+
+        // Rules that cover each other are only reported once, against the earlier rule
+        let broader = allow_rules.iter().enumerate().find(|(other_index, other)| {
+            *other_index != index
+                && other.text.trim() != rule.text.trim()
+                && permissions::rule_covers(other, rule, &context)
+                && (!permissions::rule_covers(rule, other, &context) || *other_index < index)
+        });
+        if let Some((_, other)) = broader {
+            issues.push(issue(
+                error_codes::PL003,
+                rule,
+                DiffSeverity::Low,
+                format!("Allow rule '{}' is redundant: '{}' already allows it", rule.text, other.text),
+                locator.details(&[rule, other]),
+                project_id,
+            ));
+        }
+    }
+
+    // Group identical rules in the same list, keeping their first appearance order
+    let mut groups: Vec<Vec<&PermissionRule>> = Vec::new();
+    for rule in &rules {
+        match groups
+            .iter_mut()
+            .find(|group| group[0].behavior == rule.behavior && group[0].text.trim() == rule.text.trim())
+        {
+            Some(group) => group.push(rule),
+            None => groups.push(vec![rule]),
+        }
+    }
+    for group in groups.iter().filter(|group| group.len() > 1) {
+        let scopes: Vec<&str> = group.iter().map(|rule| rule.scope.as_str()).collect();
+        issues.push(issue(
+            error_codes::PL005,
+            group[0],
+            DiffSeverity::Low,
+            format!(
+                "{} rule '{}' is repeated {} times ({})",
+                group[0].behavior.as_str(),
+                group[0].text,
+                group.len(),
+                scopes.join(", ")
+            ),
+            locator.details(group),
+            project_id,
+        ));
+    }
+
+    issues
+}
+
+/// Lint the permission rules of every settings scope that applies to a project
+///
+/// User settings are read from `home` and the managed policy from `managed`.
+pub fn lint_project(
+    project_dir: &Path,
+    home: Option<&Path>,
+    managed: Option<&Path>,
+    project_id: &str,
+) -> Result<Vec<HealthIssue>, AppError> {
+    if !project_dir.is_dir() {
+        return Err(AppError::Filesystem(format!(
+            "Project path does not exist or is not a directory: {}",
+            project_dir.display()
+        )));
+    }
+
+    let layers = resolver::load_layers(&ScopePaths::new(project_dir, home, managed));
+    Ok(lint_layers(&layers, project_dir, home, project_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::settings::ClaudeSettings;
+
+    /// Write settings files and load them as layers, highest precedence first
+    fn layers(dir: &Path, files: &[(ConfigScope, &str, &str)]) -> Vec<SettingsLayer> {
+        files
+            .iter()
+            .map(|(scope, name, content)| {
+                let path = dir.join(name);
+                std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+                std::fs::write(&path, content).unwrap();
+                SettingsLayer {
+                    scope: *scope,
+                    path,
+                    settings: ClaudeSettings::from_value(serde_json::from_str(content).unwrap()).unwrap(),
+                }
+            })
+            .collect()
+    }
+
+    fn codes(issues: &[HealthIssue]) -> Vec<&str> {
+        issues.iter().map(|issue| &issue.id[..5]).collect()
+    }
+
+    #[test]
+    fn test_lint_unknown_and_invalid_rules() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let layers = layers(
+            temp_dir.path(),
+            &[(
+                ConfigScope::Project,
+                ".claude/settings.json",
+                "{\n  \"permissions\": {\n    \"allow\": [\"Bassh(ls)\", \"Bash(npm\", \"mcp__github\"]\n  }\n}",
+            )],
+        );
+
+        let issues = lint_layers(&layers, temp_dir.path(), None, "p1");
+        assert_eq!(codes(&issues), vec!["PL001", "PL002"]);
+        assert!(issues[0].message.contains("unknown tool 'Bassh'"));
+        assert!(issues[0].details.as_ref().unwrap().ends_with("settings.json:3:15 (project allow)"));
+        assert_eq!(issues[0].project_id, "p1");
+    }
+
+    #[test]
+    fn test_lint_redundant_allow_rules() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let layers = layers(
+            temp_dir.path(),
+            &[
+                (
+                    ConfigScope::Local,
+                    ".claude/settings.local.json",
+                    r#"{"permissions": {"allow": ["Bash(npm run test)", "Edit(src/main.rs)", "mcp__github__create_issue"]}}"#,
+                ),
+                (
+                    ConfigScope::Project,
+                    ".claude/settings.json",
+                    r#"{"permissions": {"allow": ["Bash(npm run:*)", "Edit(src/**)", "mcp__github", "WebFetch(domain:docs.rs)"]}}"#,
+                ),
+            ],
+        );
+
+        let issues = lint_layers(&layers, temp_dir.path(), None, "p1");
+        assert_eq!(codes(&issues), vec!["PL003", "PL003", "PL003"]);
+        assert_eq!(
+            issues[0].message,
+            "Allow rule 'Bash(npm run test)' is redundant: 'Bash(npm run:*)' already allows it"
+        );
+        let details = issues[1].details.as_ref().unwrap();
+        assert!(details.contains("settings.local.json:1:"));
+        assert!(details.contains("settings.json:1:"));
+    }
+
+    #[test]
+    fn test_lint_allow_overridden_by_deny_and_ask() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let layers = layers(
+            temp_dir.path(),
+            &[
+                (
+                    ConfigScope::Project,
+                    ".claude/settings.json",
+                    r#"{"permissions": {"allow": ["Bash(git push --force:*)", "WebFetch(domain:api.internal.corp)", "Bash(git status)"]}}"#,
+                ),
+                (
+                    ConfigScope::User,
+                    "home/.claude/settings.json",
+                    r#"{"permissions": {"deny": ["Bash(git push:*)"], "ask": ["WebFetch(domain:*.internal.corp)"]}}"#,
+                ),
+            ],
+        );
+
+        let issues = lint_layers(&layers, temp_dir.path(), None, "p1");
+        assert_eq!(codes(&issues), vec!["PL004", "PL004"]);
+        assert!(issues[0].message.contains("always denied by 'Bash(git push:*)'"));
+        assert!(issues[1].message.contains("always asked by 'WebFetch(domain:*.internal.corp)'"));
+    }
+
+    #[test]
+    fn test_lint_duplicate_rules_across_scopes() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let layers = layers(
+            temp_dir.path(),
+            &[
+                (ConfigScope::Local, ".claude/settings.local.json", r#"{"permissions": {"allow": ["Read"]}}"#),
+                (ConfigScope::Project, ".claude/settings.json", r#"{"permissions": {"allow": ["Read"], "deny": ["Read"]}}"#),
+            ],
+        );
+
+        let issues = lint_layers(&layers, temp_dir.path(), None, "p1");
+        assert_eq!(codes(&issues), vec!["PL004", "PL004", "PL005"]);
+        assert_eq!(issues[2].message, "allow rule 'Read' is repeated 2 times (local, project)");
+        assert_eq!(issues[2].details.as_ref().unwrap().lines().count(), 2);
+    }
+
+    #[test]
+    fn test_lint_clean_rules() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let layers = layers(
+            temp_dir.path(),
+            &[(
+                ConfigScope::Project,
+                ".claude/settings.json",
+                r#"{"permissions": {"allow": ["Bash(npm run:*)", "Edit(src/**)"], "deny": ["Read(.env)"]}}"#,
+            )],
+        );
+
+        assert!(lint_layers(&layers, temp_dir.path(), None, "p1").is_empty());
+    }
+}
@@ -0,0 +1,749 @@
+//! Permission rule evaluation
+//!
+//! Decides whether Claude Code would allow, ask about or deny a tool call
+//! such as `Bash(npm run test)` by checking the `permissions.deny`, `ask` and
+//! `allow` rules of every scope, then falling back to `defaultMode`.
+
+use crate::config::resolver::{self, ScopePaths, SettingsLayer};
+use crate::config::tools;
+use crate::types::app::{AppError, ConfigScope};
+use crate::types::permission::{PermissionBehavior, PermissionEvaluation, PermissionRuleMatch};
+use regex::Regex;
+use std::path::{Component, Path, PathBuf};
+
+/// Rule lists in the order Claude Code checks them
+const RULE_ORDER: [PermissionBehavior; 3] = [PermissionBehavior::Deny, PermissionBehavior::Ask, PermissionBehavior::Allow];
+
+/// A tool name with an optional argument, as in `Tool` or `Tool(argument)`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolPattern {
+    pub tool: String,
+    pub argument: Option<String>,
+}
+
+/// Split `Tool(argument)` into its parts; `None` when the syntax is invalid
+pub fn parse_tool_pattern(text: &str) -> Option<ToolPattern> {
+    let text = text.trim();
+    let (tool, argument) = match text.find('(') {
+        Some(open) => {
+            let argument = text[open + 1..].strip_suffix(')')?;
+            (&text[..open], Some(argument.to_string()))
+        }
+        None => (text, None),
+    };
+
+    if tool.is_empty() || !tool.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        return None;
+    }
+
+    Some(ToolPattern {
+        tool: tool.to_string(),
+        argument,
+    })
+}
+
+/// A permission rule from one settings file
+pub struct PermissionRule {
+    /// Rule as written
+    pub text: String,
+    /// Parsed rule; `None` when the syntax is invalid
+    pub pattern: Option<ToolPattern>,
+    pub behavior: PermissionBehavior,
+    pub scope: ConfigScope,
+    pub path: PathBuf,
+    /// JSON pointer of the rule within its settings file
+    pub pointer: String,
+    /// Directory that `/path` patterns are relative to
+    base: PathBuf,
+}
+
+/// Directory `/path` patterns are relative to: the root containing `.claude/`
+fn rule_base(settings_path: &Path) -> PathBuf {
+    let parent = settings_path.parent().unwrap_or(Path::new("/"));
+    match parent.file_name() {
+        Some(name) if name == ".claude" => parent.parent().unwrap_or(parent).to_path_buf(),
+        _ => parent.to_path_buf(),
+    }
+}
+
+/// Every string rule, deny rules first, then ask and allow, each by scope precedence
+pub fn collect_rules(layers: &[SettingsLayer]) -> Vec<PermissionRule> {
+    let mut rules = Vec::new();
+
+    for behavior in RULE_ORDER {
+        for layer in layers {
+            let pointer = format!("/permissions/{}", behavior.as_str());
+            let list = layer.settings.permissions.as_ref().and_then(|permissions| match behavior {
+                PermissionBehavior::Allow => permissions.allow.as_ref(),
+                PermissionBehavior::Ask => permissions.ask.as_ref(),
+                PermissionBehavior::Deny => permissions.deny.as_ref(),
+            });
+            let list = match list {
+                Some(list) => list,
+                None => continue,
+            };
+
+            for (index, text) in list.iter().enumerate() {
+                rules.push(PermissionRule {
+                    text: text.to_string(),
+                    pattern: parse_tool_pattern(text),
+                    behavior,
+                    scope: layer.scope,
+                    path: layer.path.clone(),
+                    pointer: format!("{}/{}", pointer, index),
+                    base: rule_base(&layer.path),
+                });
+            }
+        }
+    }
+
+    rules
+}
+
+/// Directories used to resolve relative paths
+pub struct PathContext<'a> {
+    pub project_dir: &'a Path,
+    pub home: Option<&'a Path>,
+}
+
+/// Whether a rule's tool name covers the called tool
+fn tool_matches(rule_tool: &str, tool: &str) -> bool {
+    if tools::is_mcp_tool(rule_tool) {
+        // `mcp__server` and `mcp__server__*` cover every tool of the server
+        let server_prefix = format!("{}__", rule_tool.trim_end_matches("__*"));
+        return rule_tool == tool || tool.starts_with(&server_prefix);
+    }
+
+    match rule_tool {
+        "Edit" => tools::EDIT_TOOLS.contains(&tool),
+        "Read" => tools::READ_TOOLS.contains(&tool),
+        _ => rule_tool == tool,
+    }
+}
+
+/// Whether a rule matches a tool call, with `argument` the call's argument
+fn rule_matches(rule: &PermissionRule, tool: &str, argument: Option<&str>, context: &PathContext) -> bool {
+    let pattern = match &rule.pattern {
+        Some(pattern) => pattern,
+        None => return false,
+    };
+    if !tool_matches(&pattern.tool, tool) {
+        return false;
+    }
+
+    let specifier = match pattern.argument.as_deref() {
+        None | Some("*") => return true,
+        Some(specifier) => specifier,
+    };
+    let argument = match argument {
+        Some(argument) => argument,
+        None => return false,
+    };
+
+    match pattern.tool.as_str() {
+        "Bash" => bash_matches(specifier, argument),
+        "WebFetch" => domain_matches(specifier, argument),
+        rule_tool if tools::EDIT_TOOLS.contains(&rule_tool) || tools::READ_TOOLS.contains(&rule_tool) => {
+            path_matches(specifier, argument, &rule.base, context)
+        }
+        _ => specifier == argument,
+    }
+}
+
+/// `npm run test` matches exactly, `npm run test:*` by prefix
+fn bash_matches(specifier: &str, command: &str) -> bool {
+    let command = command.trim();
+    match specifier.strip_suffix(":*") {
+        Some(prefix) => command.starts_with(prefix.trim()),
+        None => command == specifier.trim(),
+    }
+}
+
+/// Split a shell command on `&&`, `||`, `;`, `|` and newlines outside quotes
+pub fn split_compound_command(command: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    let mut chars = command.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), _) if c == q => {
+                quote = None;
+                current.push(c);
+            }
+            (Some(_), '\\') => {
+                current.push(c);
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+            }
+            (Some(_), _) => current.push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                current.push(c);
+            }
+            (None, ';' | '\n') => parts.push(std::mem::take(&mut current)),
+            (None, '&') if chars.peek() == Some(&'&') => {
+                chars.next();
+                parts.push(std::mem::take(&mut current));
+            }
+            (None, '|') => {
+                if chars.peek() == Some(&'|') {
+                    chars.next();
+                }
+                parts.push(std::mem::take(&mut current));
+            }
+            _ => current.push(c),
+        }
+    }
+    parts.push(current);
+
+    parts
+        .into_iter()
+        .map(|part| part.trim().to_string())
+        .filter(|part| !part.is_empty())
+        .collect()
+}
+
+/// `domain:example.com` matches URLs on that host; `domain:*.example.com` its subdomains
+fn domain_matches(specifier: &str, argument: &str) -> bool {
+    let domain = match specifier.strip_prefix("domain:") {
+        Some(domain) => domain.trim().to_ascii_lowercase(),
+        None => return specifier == argument,
+    };
+
+    let host = match reqwest::Url::parse(argument) {
+        Ok(url) => url.host_str().unwrap_or_default().to_ascii_lowercase(),
+        Err(_) => argument.strip_prefix("domain:").unwrap_or(argument).trim().to_ascii_lowercase(),
+    };
+
+    match domain.strip_prefix("*.") {
+        Some(parent) => host.ends_with(&format!(".{}", parent)),
+        None => host == domain,
+    }
+}
+
+/// Resolve `.` and `..` without touching the filesystem
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+fn to_slashes(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// Absolute path a tool call argument refers to
+fn resolve_argument(argument: &str, context: &PathContext) -> PathBuf {
+    let path = match (argument.strip_prefix("~/"), context.home) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ if Path::new(argument).is_absolute() => PathBuf::from(argument),
+        _ => context.project_dir.join(argument),
+    };
+    normalize_path(&path)
+}
+
+/// Absolute glob a path specifier stands for
+///
+/// `//path` is absolute, `~/path` is in the home directory, `/path` is relative
+/// to the settings file's root and anything else to the project. As in
+/// `.gitignore`, a pattern without a slash matches at any depth.
+fn resolve_specifier(specifier: &str, base: &Path, context: &PathContext) -> Option<String> {
+    let specifier = specifier.trim_end_matches('/');
+
+    let resolved = if let Some(rest) = specifier.strip_prefix("//") {
+        format!("/{}", rest)
+    } else if let Some(rest) = specifier.strip_prefix("~/") {
+        format!("{}/{}", to_slashes(context.home?), rest)
+    } else if let Some(rest) = specifier.strip_prefix('/') {
+        format!("{}/{}", to_slashes(&normalize_path(base)), rest)
+    } else if let Some(rest) = specifier.strip_prefix("./") {
+        format!("{}/{}", to_slashes(&normalize_path(context.project_dir)), rest)
+    } else if specifier.contains('/') {
+        format!("{}/{}", to_slashes(&normalize_path(context.project_dir)), specifier)
+    } else {
+        format!("{}/**/{}", to_slashes(&normalize_path(context.project_dir)), specifier)
+    };
+
+    Some(resolved)
+}
+
+/// Translate a glob into an anchored regex that also matches paths below a matched directory
+fn glob_to_regex(glob: &str) -> Option<Regex> {
+    let mut pattern = String::from("^");
+    let mut chars = glob.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    pattern.push_str("(?:.*/)?");
+                } else {
+                    pattern.push_str(".*");
+                }
+            }
+            '*' => pattern.push_str("[^/]*"),
+            '?' => pattern.push_str("[^/]"),
+            other => pattern.push_str(&regex::escape(&other.to_string())),
+        }
+    }
+    pattern.push_str("(?:/.*)?$");
+
+    Regex::new(&pattern).ok()
+}
+
+fn path_matches(specifier: &str, argument: &str, base: &Path, context: &PathContext) -> bool {
+    let glob = match resolve_specifier(specifier, base, context) {
+        Some(glob) => glob,
+        None => return false,
+    };
+    let path = to_slashes(&resolve_argument(argument, context));
+
+    glob_to_regex(&glob).is_some_and(|regex| regex.is_match(&path))
+}
+
+fn has_glob(text: &str) -> bool {
+    text.contains(['*', '?'])
+}
+
+/// Whether every call matched by `narrower` is also matched by `broader`
+///
+/// Conservative: `false` when coverage cannot be shown, for example between
+/// two unrelated globs.
+pub fn rule_covers(broader: &PermissionRule, narrower: &PermissionRule, context: &PathContext) -> bool {
+    let (outer, inner) = match (&broader.pattern, &narrower.pattern) {
+        (Some(outer), Some(inner)) => (outer, inner),
+        _ => return false,
+    };
+    if !tool_matches(&outer.tool, &inner.tool) {
+        return false;
+    }
+
+    let outer_specifier = match outer.argument.as_deref() {
+        None | Some("*") => return true,
+        Some(specifier) => specifier,
+    };
+    let inner_specifier = match inner.argument.as_deref() {
+        None | Some("*") => return false,
+        Some(specifier) => specifier,
+    };
+
+    match outer.tool.as_str() {
+        "Bash" => match (outer_specifier.strip_suffix(":*"), inner_specifier.strip_suffix(":*")) {
+            (Some(outer_prefix), Some(inner_prefix)) => inner_prefix.trim().starts_with(outer_prefix.trim()),
+            (Some(_), None) => bash_matches(outer_specifier, inner_specifier),
+            (None, _) => outer_specifier.trim() == inner_specifier.trim(),
+        },
+        "WebFetch" => match inner_specifier.strip_prefix("domain:") {
+            Some(domain) if !has_glob(domain) => domain_matches(outer_specifier, domain),
+            _ => outer_specifier == inner_specifier,
+        },
+        rule_tool if tools::EDIT_TOOLS.contains(&rule_tool) || tools::READ_TOOLS.contains(&rule_tool) => {
+            let outer_glob = resolve_specifier(outer_specifier, &broader.base, context);
+            let inner_glob = resolve_specifier(inner_specifier, &narrower.base, context);
+            let (outer_glob, inner_glob) = match (outer_glob, inner_glob) {
+                (Some(outer_glob), Some(inner_glob)) => (outer_glob, inner_glob),
+                _ => return false,
+            };
+
+            if outer_glob == inner_glob {
+                return true;
+            }
+            if !has_glob(&inner_glob) {
+                return glob_to_regex(&outer_glob).is_some_and(|regex| regex.is_match(&inner_glob));
+            }
+            // `dir/**` covers any pattern inside `dir`
+            outer_glob
+                .strip_suffix("/**")
+                .filter(|dir| !has_glob(dir))
+                .is_some_and(|dir| inner_glob.starts_with(&format!("{}/", dir)))
+        }
+        _ => outer_specifier == inner_specifier,
+    }
+}
+
+/// Effective `defaultMode` and the scope that set it
+fn effective_mode(layers: &[SettingsLayer]) -> (String, Option<ConfigScope>) {
+    layers
+        .iter()
+        .find_map(|layer| {
+            layer
+                .settings
+                .permissions
+                .as_ref()
+                .and_then(|permissions| permissions.default_mode.as_ref())
+                .map(|mode| (mode.clone(), Some(layer.scope)))
+        })
+        .unwrap_or_else(|| ("default".to_string(), None))
+}
+
+/// Decision for a tool call that no rule matches
+fn mode_decision(tool: &str, mode: &str, bypass_disabled: bool) -> (PermissionBehavior, String) {
+    if !tools::requires_permission(tool) {
+        return (PermissionBehavior::Allow, format!("{} does not require permission", tool));
+    }
+
+    let is_edit = tools::EDIT_TOOLS.contains(&tool);
+    match mode {
+        "bypassPermissions" if !bypass_disabled => (
+            PermissionBehavior::Allow,
+            "No rule matches and bypassPermissions mode allows every tool".to_string(),
+        ),
+        "bypassPermissions" => (
+            PermissionBehavior::Ask,
+            format!(
+                "No rule matches and bypassPermissions mode is disabled by disableBypassPermissionsMode, so {} asks",
+                tool
+            ),
+        ),
+        "acceptEdits" if is_edit => (
+            PermissionBehavior::Allow,
+            "No rule matches and acceptEdits mode allows file edits".to_string(),
+        ),
+        "plan" if is_edit || tool == "Bash" => (
+            PermissionBehavior::Deny,
+            format!("No rule matches and plan mode does not allow {}", tool),
+        ),
+        "dontAsk" => (
+            PermissionBehavior::Deny,
+            "No rule matches and dontAsk mode denies tools that are not allowed".to_string(),
+        ),
+        _ => (
+            PermissionBehavior::Ask,
+            format!("No rule matches, so {} asks for permission in {} mode", tool, mode),
+        ),
+    }
+}
+
+/// Evaluate a tool call against settings layers ordered from highest to lowest precedence
+///
+/// Each part of a compound Bash command is evaluated on its own and the most
+/// restrictive decision wins, so `npm test && rm -rf dist` is only allowed when
+/// both commands are.
+pub fn evaluate(
+    layers: &[SettingsLayer],
+    project_dir: &Path,
+    home: Option<&Path>,
+    tool_call: &str,
+) -> Result<PermissionEvaluation, AppError> {
+    let call = parse_tool_pattern(tool_call)
+        .ok_or_else(|| AppError::Parse(format!("Invalid tool call '{}', expected Tool or Tool(argument)", tool_call)))?;
+
+    let rules = collect_rules(layers);
+    let context = PathContext { project_dir, home };
+    let (mode, mode_scope) = effective_mode(layers);
+    let bypass_disabled = layers.iter().any(|layer| {
+        layer
+            .settings
+            .permissions
+            .as_ref()
+            .and_then(|permissions| permissions.disable_bypass_permissions_mode.as_deref())
+            == Some("disable")
+    });
+
+    let parts: Vec<Option<String>> = match call.argument.as_deref() {
+        Some(command) if call.tool == "Bash" => {
+            let parts = split_compound_command(command);
+            if parts.len() > 1 {
+                parts.into_iter().map(Some).collect()
+            } else {
+                vec![Some(command.to_string())]
+            }
+        }
+        argument => vec![argument.map(String::from)],
+    };
+    let compound = parts.len() > 1;
+
+    let mut matches = Vec::new();
+    let mut outcome: Option<(PermissionBehavior, Option<PermissionRuleMatch>, String)> = None;
+
+    for part in &parts {
+        let part_matches: Vec<PermissionRuleMatch> = rules
+            .iter()
+            .filter(|rule| rule_matches(rule, &call.tool, part.as_deref(), &context))
+            .map(|rule| PermissionRuleMatch {
+                rule: rule.text.clone(),
+                behavior: rule.behavior,
+                scope: rule.scope,
+                path: rule.path.to_string_lossy().to_string(),
+                pointer: rule.pointer.clone(),
+                subcommand: part.clone().filter(|_| compound),
+            })
+            .collect();
+
+        let (behavior, decided_by, mut reason) = match part_matches.first() {
+            Some(rule) => (
+                rule.behavior,
+                Some(rule.clone()),
+                format!(
+                    "{} rule '{}' in {} settings ({})",
+                    rule.behavior.as_str(),
+                    rule.rule,
+                    rule.scope.as_str(),
+                    rule.path
+                ),
+            ),
+            None => {
+                let (behavior, reason) = mode_decision(&call.tool, &mode, bypass_disabled);
+                (behavior, None, reason)
+            }
+        };
+        if let Some(part) = part.as_deref().filter(|_| compound) {
+            reason = format!("'{}': {}", part, reason);
+        }
+
+        matches.extend(part_matches);
+        if outcome.as_ref().is_none_or(|(current, _, _)| behavior > *current) {
+            outcome = Some((behavior, decided_by, reason));
+        }
+    }
+
+    let (decision, decided_by, reason) =
+        outcome.unwrap_or_else(|| (PermissionBehavior::Ask, None, "Nothing to evaluate".to_string()));
+
+    Ok(PermissionEvaluation {
+        tool_call: tool_call.to_string(),
+        tool: call.tool,
+        decision,
+        decided_by,
+        mode,
+        mode_scope,
+        reason,
+        matches,
+    })
+}
+
+/// Evaluate a tool call against every settings scope of a project
+pub fn evaluate_project(project_dir: &Path, tool_call: &str) -> Result<PermissionEvaluation, AppError> {
+    if !project_dir.is_dir() {
+        return Err(AppError::Filesystem(format!(
+            "Project path does not exist or is not a directory: {}",
+            project_dir.display()
+        )));
+    }
+
+    let layers = resolver::load_layers(&ScopePaths::for_project(project_dir));
+    evaluate(&layers, project_dir, dirs::home_dir().as_deref(), tool_call)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::settings::ClaudeSettings;
+    use serde_json::json;
+
+    fn layer(scope: ConfigScope, path: &str, value: serde_json::Value) -> SettingsLayer {
+        SettingsLayer {
+            scope,
+            path: PathBuf::from(path),
+            settings: ClaudeSettings::from_value(value).unwrap(),
+        }
+    }
+
+    fn eval(layers: &[SettingsLayer], tool_call: &str) -> PermissionEvaluation {
+        evaluate(layers, Path::new("/work/app"), Some(Path::new("/home/dev")), tool_call).unwrap()
+    }
+
+    #[test]
+    fn test_parse_tool_pattern() {
+        assert_eq!(
+            parse_tool_pattern("Bash(npm run test:*)"),
+            Some(ToolPattern {
+                tool: "Bash".to_string(),
+                argument: Some("npm run test:*".to_string()),
+            })
+        );
+        assert_eq!(parse_tool_pattern("mcp__github").unwrap().argument, None);
+        assert!(parse_tool_pattern("Bash(npm").is_none());
+        assert!(parse_tool_pattern("(ls)").is_none());
+        assert!(parse_tool_pattern("Web Fetch").is_none());
+    }
+
+    #[test]
+    fn test_deny_beats_allow_across_scopes() {
+        let layers = vec![
+            layer(
+                ConfigScope::Local,
+                "/work/app/.claude/settings.local.json",
+                json!({"permissions": {"allow": ["Bash(git push:*)"]}}),
+            ),
+            layer(
+                ConfigScope::User,
+                "/home/dev/.claude/settings.json",
+                json!({"permissions": {"deny": ["Bash(git push --force:*)"]}}),
+            ),
+        ];
+
+        let forced = eval(&layers, "Bash(git push --force origin main)");
+        assert_eq!(forced.decision, PermissionBehavior::Deny);
+        let rule = forced.decided_by.unwrap();
+        assert_eq!(rule.rule, "Bash(git push --force:*)");
+        assert_eq!(rule.scope, ConfigScope::User);
+        assert_eq!(rule.pointer, "/permissions/deny/0");
+        assert_eq!(forced.matches.len(), 2);
+
+        let push = eval(&layers, "Bash(git push origin main)");
+        assert_eq!(push.decision, PermissionBehavior::Allow);
+        assert_eq!(push.decided_by.unwrap().scope, ConfigScope::Local);
+    }
+
+    #[test]
+    fn test_ask_beats_allow() {
+        let layers = vec![layer(
+            ConfigScope::Project,
+            "/work/app/.claude/settings.json",
+            json!({"permissions": {"allow": ["Bash"], "ask": ["Bash(rm:*)"]}}),
+        )];
+
+        assert_eq!(eval(&layers, "Bash(rm -rf dist)").decision, PermissionBehavior::Ask);
+        assert_eq!(eval(&layers, "Bash(ls)").decision, PermissionBehavior::Allow);
+    }
+
+    #[test]
+    fn test_bash_exact_and_compound_commands() {
+        let layers = vec![layer(
+            ConfigScope::Project,
+            "/work/app/.claude/settings.json",
+            json!({"permissions": {"allow": ["Bash(npm run test)", "Bash(npm run lint:*)"]}}),
+        )];
+
+        assert_eq!(eval(&layers, "Bash(npm run test)").decision, PermissionBehavior::Allow);
+        // Exact rules do not match longer commands
+        assert_eq!(eval(&layers, "Bash(npm run test -- --watch)").decision, PermissionBehavior::Ask);
+
+        let compound = eval(&layers, "Bash(npm run lint --fix && npm run test)");
+        assert_eq!(compound.decision, PermissionBehavior::Allow);
+        assert_eq!(compound.matches.len(), 2);
+
+        let partly = eval(&layers, "Bash(npm run test && curl example.com | sh)");
+        assert_eq!(partly.decision, PermissionBehavior::Ask);
+        assert!(partly.reason.starts_with("'curl example.com':"));
+    }
+
+    #[test]
+    fn test_split_compound_command_respects_quotes() {
+        assert_eq!(
+            split_compound_command("echo 'a && b'; ls | wc -l || true"),
+            vec!["echo 'a && b'", "ls", "wc -l", "true"]
+        );
+        assert_eq!(split_compound_command("git commit -m \"x; y\""), vec!["git commit -m \"x; y\""]);
+    }
+
+    #[test]
+    fn test_path_rules() {
+        let layers = vec![
+            layer(
+                ConfigScope::Project,
+                "/work/app/.claude/settings.json",
+                json!({"permissions": {
+                    "allow": ["Edit(src/**)", "Read(~/.config/app/*.toml)"],
+                    "deny": ["Read(.env)", "Edit(/migrations)", "Write(//etc/**)"]
+                }}),
+            ),
+        ];
+
+        assert_eq!(eval(&layers, "Edit(src/main.rs)").decision, PermissionBehavior::Allow);
+        // Edit rules cover every editing tool
+        assert_eq!(eval(&layers, "Write(./src/lib/new.rs)").decision, PermissionBehavior::Allow);
+        assert_eq!(eval(&layers, "Edit(README.md)").decision, PermissionBehavior::Ask);
+        assert_eq!(eval(&layers, "Edit(migrations/001.sql)").decision, PermissionBehavior::Deny);
+        assert_eq!(eval(&layers, "Write(/etc/hosts)").decision, PermissionBehavior::Deny);
+
+        // Patterns without a slash match at any depth
+        assert_eq!(eval(&layers, "Read(config/.env)").decision, PermissionBehavior::Deny);
+        assert_eq!(eval(&layers, "Read(src/main.rs)").decision, PermissionBehavior::Allow);
+        assert_eq!(
+            eval(&layers, "Read(/home/dev/.config/app/settings.toml)").decided_by.unwrap().rule,
+            "Read(~/.config/app/*.toml)"
+        );
+    }
+
+    #[test]
+    fn test_mcp_and_web_fetch_rules() {
+        let layers = vec![layer(
+            ConfigScope::Project,
+            "/work/app/.claude/settings.json",
+            json!({"permissions": {
+                "allow": ["mcp__github", "WebFetch(domain:docs.rs)"],
+                "deny": ["mcp__github__delete_repo", "WebFetch(domain:*.internal.corp)"]
+            }}),
+        )];
+
+        assert_eq!(eval(&layers, "mcp__github__create_issue").decision, PermissionBehavior::Allow);
+        assert_eq!(eval(&layers, "mcp__github__delete_repo").decision, PermissionBehavior::Deny);
+        assert_eq!(eval(&layers, "mcp__githubx__list").decision, PermissionBehavior::Ask);
+        assert_eq!(eval(&layers, "WebFetch(https://docs.rs/regex)").decision, PermissionBehavior::Allow);
+        assert_eq!(eval(&layers, "WebFetch(https://wiki.internal.corp/x)").decision, PermissionBehavior::Deny);
+        assert_eq!(eval(&layers, "WebFetch(https://example.com)").decision, PermissionBehavior::Ask);
+    }
+
+    #[test]
+    fn test_default_modes() {
+        let with_mode = |mode: &str| {
+            vec![layer(
+                ConfigScope::Local,
+                "/work/app/.claude/settings.local.json",
+                json!({"permissions": {"defaultMode": mode}}),
+            )]
+        };
+
+        let no_rules = eval(&[], "Bash(ls)");
+        assert_eq!(no_rules.decision, PermissionBehavior::Ask);
+        assert_eq!(no_rules.mode, "default");
+        assert_eq!(no_rules.mode_scope, None);
+        assert_eq!(eval(&[], "Read(src/main.rs)").decision, PermissionBehavior::Allow);
+
+        let accept_edits = with_mode("acceptEdits");
+        assert_eq!(eval(&accept_edits, "Edit(src/main.rs)").decision, PermissionBehavior::Allow);
+        assert_eq!(eval(&accept_edits, "Bash(ls)").decision, PermissionBehavior::Ask);
+        assert_eq!(eval(&accept_edits, "Bash(ls)").mode_scope, Some(ConfigScope::Local));
+
+        assert_eq!(eval(&with_mode("plan"), "Edit(src/main.rs)").decision, PermissionBehavior::Deny);
+        assert_eq!(eval(&with_mode("bypassPermissions"), "Bash(ls)").decision, PermissionBehavior::Allow);
+        assert_eq!(eval(&with_mode("dontAsk"), "Bash(ls)").decision, PermissionBehavior::Deny);
+
+        let mut bypass_disabled = with_mode("bypassPermissions");
+        bypass_disabled.insert(
+            0,
+            layer(
+                ConfigScope::Managed,
+                "/etc/claude-code/managed-settings.json",
+                json!({"permissions": {"disableBypassPermissionsMode": "disable"}}),
+            ),
+        );
+        assert_eq!(eval(&bypass_disabled, "Bash(ls)").decision, PermissionBehavior::Ask);
+    }
+
+    #[test]
+    fn test_invalid_tool_call() {
+        let result = evaluate(&[], Path::new("/work/app"), None, "Bash(ls");
+        assert!(matches!(result, Err(AppError::Parse(_))));
+    }
+
+    #[test]
+    fn test_evaluate_project_reads_settings() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(temp_dir.path().join(".claude")).unwrap();
+        std::fs::write(
+            temp_dir.path().join(".claude").join("settings.json"),
+            r#"{"permissions": {"deny": ["Bash(curl:*)"]}}"#,
+        )
+        .unwrap();
+
+        let evaluation = evaluate_project(temp_dir.path(), "Bash(curl https://example.com)").unwrap();
+        assert_eq!(evaluation.decision, PermissionBehavior::Deny);
+        assert_eq!(evaluation.decided_by.unwrap().scope, ConfigScope::Project);
+    }
+}
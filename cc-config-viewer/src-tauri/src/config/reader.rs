@@ -3,9 +3,32 @@
 //! Provides file system access functions with path validation
 //! for secure, permission-controlled configuration file reading.
 
+use crate::config::spanned_json::{self, SpannedDocument, SyntaxError};
+use crate::config::validation::{self, Diagnostic};
 use crate::types::app::AppError;
 use serde_json::Value;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// Environment variable that overrides the managed settings location
+pub const MANAGED_SETTINGS_ENV: &str = "CC_CONFIG_VIEWER_MANAGED_SETTINGS";
+
+/// Serializes tests that point `CC_CONFIG_VIEWER_MANAGED_SETTINGS` at a temporary policy
+#[cfg(test)]
+static MANAGED_SETTINGS_ENV_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+/// Run `f` with the managed settings location overridden to `path`
+#[cfg(test)]
+pub(crate) fn with_managed_settings<T>(path: &Path, f: impl FnOnce() -> T) -> T {
+    let _guard = MANAGED_SETTINGS_ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let previous = std::env::var_os(MANAGED_SETTINGS_ENV);
+    std::env::set_var(MANAGED_SETTINGS_ENV, path);
+    let result = f();
+    match previous {
+        Some(previous) => std::env::set_var(MANAGED_SETTINGS_ENV, previous),
+        None => std::env::remove_var(MANAGED_SETTINGS_ENV),
+    }
+    result
+}
 
 /// Validate that the path is allowed (home directory or current project)
 fn validate_path(path: &str) -> Result<PathBuf, AppError> {
@@ -45,6 +68,63 @@ fn validate_path(path: &str) -> Result<PathBuf, AppError> {
     Err(AppError::Permission("Access denied: path outside allowed directories".to_string()))
 }
 
+/// Location of the enterprise managed settings file
+///
+/// Uses `CC_CONFIG_VIEWER_MANAGED_SETTINGS` when set, otherwise the platform
+/// path Claude Code reads its managed policy from.
+pub fn managed_settings_path() -> PathBuf {
+    if let Some(path) = std::env::var_os(MANAGED_SETTINGS_ENV) {
+        return PathBuf::from(path);
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        PathBuf::from("/Library/Application Support/ClaudeCode/managed-settings.json")
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        PathBuf::from(r"C:\ProgramData\ClaudeCode\managed-settings.json")
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        PathBuf::from("/etc/claude-code/managed-settings.json")
+    }
+}
+
+/// Validate that the path is the allow-listed managed settings file
+///
+/// System locations are rejected by `validate_path`, so the managed policy is
+/// the only file outside home and the current directory that may be read.
+fn validate_managed_path(path: &Path) -> Result<PathBuf, AppError> {
+    let canonical = path.canonicalize()
+        .map_err(|e| AppError::Filesystem(format!("Invalid path: {}", e)))?;
+
+    match managed_settings_path().canonicalize() {
+        Ok(allowed) if allowed == canonical => Ok(canonical),
+        _ => Err(AppError::Permission(
+            "Access denied: path is not the managed settings file".to_string(),
+        )),
+    }
+}
+
+/// Read the enterprise managed settings file
+///
+/// # Arguments
+/// * `path` - The managed settings path, which must match `managed_settings_path()`
+///
+/// # Returns
+/// * `Result<String, AppError>` - File contents or error
+pub fn read_managed_file(path: &Path) -> Result<String, AppError> {
+    let validated_path = validate_managed_path(path)?;
+    std::fs::read_to_string(validated_path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => AppError::Filesystem("File not found".to_string()),
+        std::io::ErrorKind::PermissionDenied => AppError::Permission("Access denied".to_string()),
+        _ => AppError::Filesystem("Failed to read file".to_string()),
+    })
+}
+
 /// Read a file from the specified path with security validation
 ///
 /// # Arguments
@@ -75,6 +155,57 @@ pub fn parse_json(content: String) -> Result<Value, AppError> {
     Ok(data)
 }
 
+/// Parse JSON content, keeping the source span of every node
+///
+/// # Arguments
+/// * `content` - JSON string to parse
+///
+/// # Returns
+/// * `Result<SpannedDocument, AppError>` - Parsed value with spans keyed by JSON pointer
+pub fn parse_json_spanned(content: &str) -> Result<SpannedDocument, AppError> {
+    spanned_json::parse_with_spans(content).map_err(|e| AppError::Parse(e.to_string()))
+}
+
+/// Parse hand-edited JSON, keeping whatever could be recovered
+///
+/// Comments, trailing commas and similar slips are reported instead of
+/// failing the whole file.
+///
+/// # Arguments
+/// * `content` - JSON (or JSONC) string to parse
+///
+/// # Returns
+/// * `Result<(Value, Vec<SyntaxError>), AppError>` - Recovered value and every syntax issue,
+///   or an error when nothing could be recovered
+pub fn parse_json_tolerant(content: &str) -> Result<(Value, Vec<SyntaxError>), AppError> {
+    let parsed = spanned_json::parse_tolerant(content);
+    match parsed.document {
+        Some(document) => Ok((document.value, parsed.issues)),
+        None => Err(AppError::Parse(
+            parsed
+                .issues
+                .first()
+                .map(|issue| issue.to_string())
+                .unwrap_or_else(|| "Invalid JSON".to_string()),
+        )),
+    }
+}
+
+/// Parse a config file tolerantly, keeping a diagnostic for every syntax issue that was recovered from
+///
+/// # Arguments
+/// * `path` - File the content was read from, used in errors
+/// * `content` - JSON (or JSONC) string to parse
+///
+/// # Returns
+/// * `Result<(Value, Vec<Diagnostic>), AppError>` - Recovered value and a `PR001` diagnostic
+///   per ignored part of the file, or an error when nothing could be recovered
+pub fn parse_json_recovering(path: &Path, content: &str) -> Result<(Value, Vec<Diagnostic>), AppError> {
+    let (value, issues) = parse_json_tolerant(content)
+        .map_err(|e| AppError::Parse(format!("{}: {}", path.display(), e)))?;
+    Ok((value, validation::syntax_diagnostics(&issues)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -115,6 +246,35 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_read_managed_file_rejects_other_system_paths() {
+        let result = read_managed_file(Path::new("/etc/passwd"));
+        assert!(matches!(result, Err(AppError::Permission(_))));
+    }
+
+    #[test]
+    fn test_read_managed_file_env_override() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let policy = temp_dir.path().join("managed-settings.json");
+        let other = temp_dir.path().join("other.json");
+        fs::write(&policy, r#"{"model": "sonnet"}"#).unwrap();
+        fs::write(&other, r#"{"model": "opus"}"#).unwrap();
+
+        with_managed_settings(&policy, || {
+            assert_eq!(managed_settings_path(), policy);
+            let content = read_managed_file(&policy).unwrap();
+            assert!(content.contains("sonnet"));
+
+            // Only the configured policy is allow-listed, not its neighbours
+            let result = read_managed_file(&other);
+            assert!(matches!(result, Err(AppError::Permission(_))));
+        });
+
+        // Without the override the temporary policy is not the managed file
+        let result = read_managed_file(&policy);
+        assert!(matches!(result, Err(AppError::Permission(_))));
+    }
+
     #[test]
     fn test_parse_json_valid() {
         let json = r#"{"key": "value"}"#;
@@ -138,6 +298,39 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_parse_json_spanned() {
+        let document = parse_json_spanned("{\n  \"model\": \"opus\"\n}").unwrap();
+        assert_eq!(document.value["model"], "opus");
+        assert_eq!(document.location("/model"), Some((2, 3)));
+
+        let result = parse_json_spanned("{\n  \"model\" \"opus\"\n}");
+        assert!(matches!(result, Err(AppError::Parse(message)) if message.contains("line 2")));
+    }
+
+    #[test]
+    fn test_parse_json_tolerant() {
+        let (value, issues) = parse_json_tolerant("{\"mcpServers\": {\"a\": {},}, // x\n}").unwrap();
+        assert_eq!(value["mcpServers"]["a"], serde_json::json!({}));
+        assert_eq!(issues.len(), 3);
+
+        let result = parse_json_tolerant("");
+        assert!(matches!(result, Err(AppError::Parse(_))));
+    }
+
+    #[test]
+    fn test_parse_json_recovering_returns_diagnostics() {
+        let path = Path::new("/work/.mcp.json");
+        let (value, diagnostics) = parse_json_recovering(path, "{\n  \"mcpServers\": {},\n}").unwrap();
+        assert_eq!(value["mcpServers"], serde_json::json!({}));
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "PR001");
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (Some(2), Some(19)));
+
+        let error = parse_json_recovering(path, "").unwrap_err();
+        assert!(error.to_string().contains("/work/.mcp.json"));
+    }
+
     #[test]
     fn test_parse_json_array() {
         let json = r#"[1, 2, 3]"#;
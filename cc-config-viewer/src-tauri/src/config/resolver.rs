@@ -0,0 +1,670 @@
+//! Effective configuration resolver
+//!
+//! Reads every Claude Code settings scope that applies to a project and merges
+//! them in precedence order (managed > local > project > user).
+
+use crate::config::reader::{self, managed_settings_path};
+use crate::types::app::{AppError, ConfigEntry, ConfigScope, ConfigSource};
+use crate::types::settings::ClaudeSettings;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// Locations of the settings files for each scope
+#[derive(Debug, Clone)]
+pub struct ScopePaths {
+    pub managed: Option<PathBuf>,
+    pub user: Option<PathBuf>,
+    pub project: PathBuf,
+    pub local: PathBuf,
+}
+
+impl ScopePaths {
+    /// Default settings locations for a project directory
+    pub fn for_project(project_dir: &Path) -> Self {
+        let managed = managed_settings_path();
+        Self::new(project_dir, dirs::home_dir().as_deref(), Some(&managed))
+    }
+
+    /// Settings locations for a project directory, a home directory and a managed policy file
+    pub fn new(project_dir: &Path, home: Option<&Path>, managed: Option<&Path>) -> Self {
+        Self {
+            managed: managed.map(Path::to_path_buf),
+            user: home.map(|home| home.join(".claude").join("settings.json")),
+            project: project_dir.join(".claude").join("settings.json"),
+            local: project_dir.join(".claude").join("settings.local.json"),
+        }
+    }
+
+    /// Path for a scope, if the scope has one
+    pub fn path_for(&self, scope: ConfigScope) -> Option<&Path> {
+        match scope {
+            ConfigScope::Managed => self.managed.as_deref(),
+            ConfigScope::User => self.user.as_deref(),
+            ConfigScope::Project => Some(self.project.as_path()),
+            ConfigScope::Local => Some(self.local.as_path()),
+        }
+    }
+}
+
+/// A parsed settings file belonging to one scope
+#[derive(Debug, Clone)]
+pub struct SettingsLayer {
+    pub scope: ConfigScope,
+    pub path: PathBuf,
+    pub settings: ClaudeSettings,
+}
+
+impl SettingsLayer {
+    /// The layer's settings as JSON, for lookups by pointer
+    pub fn value(&self) -> Value {
+        self.settings.to_value()
+    }
+
+    /// Build the `ConfigSource` describing this layer
+    pub fn source(&self) -> ConfigSource {
+        ConfigSource {
+            type_: self.scope.as_str().to_string(),
+            path: self.path.to_string_lossy().to_string(),
+            priority: self.scope.priority(),
+        }
+    }
+}
+
+/// Load every settings layer that exists, ordered from highest to lowest precedence
+///
+/// Files that cannot be read or parsed are skipped with a warning so one broken
+/// scope does not hide the others. Entries that do not fit the settings model
+/// are dropped with a warning and the rest of the file is kept.
+pub fn load_layers(paths: &ScopePaths) -> Vec<SettingsLayer> {
+    let mut layers = Vec::new();
+
+    for scope in ConfigScope::PRECEDENCE {
+        let path = match paths.path_for(scope) {
+            Some(path) if path.is_file() => path,
+            _ => continue,
+        };
+
+        match load_layer(scope, path) {
+            Ok(layer) => layers.push(layer),
+            Err(e) => eprintln!("Warning: Failed to load {} settings: {}", scope.as_str(), e),
+        }
+    }
+
+    layers
+}
+
+/// Read and parse a single settings file
+fn load_layer(scope: ConfigScope, path: &Path) -> Result<SettingsLayer, AppError> {
+    // The managed policy lives outside the home directory and needs the allow-listed reader
+    let content = match scope {
+        ConfigScope::Managed => reader::read_managed_file(path)?,
+        _ => std::fs::read_to_string(path).map_err(AppError::from)?,
+    };
+    let value: Value = serde_json::from_str(&content).map_err(AppError::from)?;
+
+    if !value.is_object() {
+        return Err(AppError::Parse(format!(
+            "Expected JSON object in {}",
+            path.display()
+        )));
+    }
+    let (settings, dropped) = ClaudeSettings::from_value_partial(value);
+    for (key, _) in dropped {
+        eprintln!("Warning: Ignoring invalid setting {} in {}", key, path.display());
+    }
+
+    Ok(SettingsLayer {
+        scope,
+        path: path.to_path_buf(),
+        settings,
+    })
+}
+
+/// Merge a higher-precedence value over a lower-precedence one
+///
+/// Objects are merged key by key, arrays are concatenated without duplicates
+/// (higher precedence entries first) and any other value is replaced.
+pub fn merge_values(lower: &Value, higher: &Value) -> Value {
+    match (lower, higher) {
+        (Value::Object(lower_map), Value::Object(higher_map)) => {
+            let mut merged = lower_map.clone();
+            for (key, higher_value) in higher_map {
+                let value = match lower_map.get(key) {
+                    Some(lower_value) => merge_values(lower_value, higher_value),
+                    None => higher_value.clone(),
+                };
+                merged.insert(key.clone(), value);
+            }
+            Value::Object(merged)
+        }
+        (Value::Array(lower_items), Value::Array(higher_items)) => {
+            let mut merged = higher_items.clone();
+            for item in lower_items {
+                if !merged.contains(item) {
+                    merged.push(item.clone());
+                }
+            }
+            Value::Array(merged)
+        }
+        _ => higher.clone(),
+    }
+}
+
+/// Merge settings layers into one entry per top-level key
+///
+/// `layers` must be ordered from highest to lowest precedence. Each entry's
+/// source is the highest-precedence layer that defines the key.
+pub fn resolve_layers(layers: &[SettingsLayer]) -> Vec<ConfigEntry> {
+    let mut entries: Vec<ConfigEntry> = Vec::new();
+
+    // Walk from lowest to highest precedence so higher layers merge over lower ones
+    for layer in layers.iter().rev() {
+        let object = match layer.value() {
+            Value::Object(object) => object,
+            _ => continue,
+        };
+
+        for (key, value) in &object {
+            match entries.iter_mut().find(|entry| &entry.key == key) {
+                Some(entry) => {
+                    entry.value = merge_values(&entry.value, value);
+                    entry.source = layer.source();
+                }
+                None => entries.push(ConfigEntry {
+                    key: key.clone(),
+                    value: value.clone(),
+                    source: layer.source(),
+                }),
+            }
+        }
+    }
+
+    entries.sort_by(|a, b| a.key.cmp(&b.key));
+    entries
+}
+
+/// How a layer's value contributes to the effective value of a key
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum ProvenanceStatus {
+    /// Highest-precedence layer defining the key
+    #[serde(rename = "effective")]
+    Effective,
+    /// Lower-precedence object or array combined into the effective value
+    #[serde(rename = "merged")]
+    Merged,
+    /// Lower-precedence value replaced by a higher layer
+    #[serde(rename = "overridden")]
+    Overridden,
+}
+
+/// One layer that defines a traced key
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProvenanceStep {
+    pub source: ConfigSource,
+    pub value: Value,
+    pub status: ProvenanceStatus,
+}
+
+/// Every layer defining a key, from highest to lowest precedence
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct KeyProvenance {
+    pub key: String,
+    pub pointer: String,
+    pub effective_value: Option<Value>,
+    pub chain: Vec<ProvenanceStep>,
+}
+
+/// Convert a dotted key (`permissions.allow`) or JSON pointer into a JSON pointer
+pub fn key_to_pointer(key: &str) -> String {
+    if key.is_empty() || key.starts_with('/') {
+        return key.to_string();
+    }
+
+    key.split('.')
+        .map(|segment| format!("/{}", segment.replace('~', "~0").replace('/', "~1")))
+        .collect()
+}
+
+/// Build the provenance chain of a key across settings layers
+///
+/// `layers` must be ordered from highest to lowest precedence.
+pub fn trace_key(layers: &[SettingsLayer], key: &str) -> KeyProvenance {
+    let pointer = key_to_pointer(key);
+    let mut chain: Vec<ProvenanceStep> = layers
+        .iter()
+        .filter_map(|layer| {
+            layer.value().pointer(&pointer).map(|value| ProvenanceStep {
+                source: layer.source(),
+                value: value.clone(),
+                status: ProvenanceStatus::Merged,
+            })
+        })
+        .collect();
+
+    // Merge from the lowest layer up; a layer whose value cannot be combined
+    // with the value merged so far replaces every layer below it
+    let mut effective_value: Option<Value> = None;
+    for index in (0..chain.len()).rev() {
+        let value = chain[index].value.clone();
+        effective_value = match effective_value {
+            Some(lower) if is_mergeable(&value, &lower) => Some(merge_values(&lower, &value)),
+            Some(_) => {
+                for step in &mut chain[index + 1..] {
+                    step.status = ProvenanceStatus::Overridden;
+                }
+                Some(value)
+            }
+            None => Some(value),
+        };
+    }
+    if let Some(winner) = chain.first_mut() {
+        winner.status = ProvenanceStatus::Effective;
+    }
+
+    KeyProvenance {
+        key: key.to_string(),
+        pointer,
+        effective_value,
+        chain,
+    }
+}
+
+/// Whether two values are combined rather than replaced when merging
+fn is_mergeable(higher: &Value, lower: &Value) -> bool {
+    matches!(
+        (higher, lower),
+        (Value::Object(_), Value::Object(_)) | (Value::Array(_), Value::Array(_))
+    )
+}
+
+/// Resolve the effective configuration of a project
+pub fn resolve_effective_config(project_dir: &Path) -> Result<Vec<ConfigEntry>, AppError> {
+    if !project_dir.is_dir() {
+        return Err(AppError::Filesystem(format!(
+            "Project path does not exist or is not a directory: {}",
+            project_dir.display()
+        )));
+    }
+
+    let layers = load_layers(&ScopePaths::for_project(project_dir));
+    Ok(resolve_layers(&layers))
+}
+
+/// A user, project or local value replaced by the managed policy
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PolicyOverride {
+    pub pointer: String,
+    pub policy_value: Value,
+    pub overridden_source: ConfigSource,
+    pub overridden_value: Value,
+}
+
+/// Managed policy file and the keys it forces on a project
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ManagedPolicyReport {
+    pub path: String,
+    pub present: bool,
+    pub settings: Option<Value>,
+    pub overrides: Vec<PolicyOverride>,
+}
+
+/// Find every lower-scope value that the managed policy overrides
+///
+/// Arrays are merged rather than replaced, so only scalar and object values
+/// that differ from the policy are reported.
+pub fn find_policy_overrides(layers: &[SettingsLayer]) -> Vec<PolicyOverride> {
+    let policy = match layers.iter().find(|l| l.scope == ConfigScope::Managed) {
+        Some(policy) => policy,
+        None => return Vec::new(),
+    };
+
+    let policy_settings = policy.value();
+    let mut leaves = Vec::new();
+    collect_leaves(&policy_settings, String::new(), &mut leaves);
+
+    let lower: Vec<(&SettingsLayer, Value)> = layers
+        .iter()
+        .filter(|l| l.scope != ConfigScope::Managed)
+        .map(|l| (l, l.value()))
+        .collect();
+
+    let mut overrides = Vec::new();
+    for (pointer, policy_value) in leaves {
+        for (layer, layer_value) in &lower {
+            let value = match layer_value.pointer(&pointer) {
+                Some(value) => value,
+                None => continue,
+            };
+
+            if value != policy_value && !is_mergeable(policy_value, value) {
+                overrides.push(PolicyOverride {
+                    pointer: pointer.clone(),
+                    policy_value: policy_value.clone(),
+                    overridden_source: layer.source(),
+                    overridden_value: value.clone(),
+                });
+            }
+        }
+    }
+
+    overrides
+}
+
+/// Collect the JSON pointer of every non-object value
+fn collect_leaves<'a>(value: &'a Value, pointer: String, leaves: &mut Vec<(String, &'a Value)>) {
+    match value {
+        Value::Object(object) if !object.is_empty() => {
+            for (key, child) in object {
+                let escaped = key.replace('~', "~0").replace('/', "~1");
+                collect_leaves(child, format!("{}/{}", pointer, escaped), leaves);
+            }
+        }
+        _ if pointer.is_empty() => {}
+        _ => leaves.push((pointer, value)),
+    }
+}
+
+/// Load the managed policy that applies to a project and the keys it overrides
+pub fn managed_policy_report(project_dir: &Path) -> Result<ManagedPolicyReport, AppError> {
+    if !project_dir.is_dir() {
+        return Err(AppError::Filesystem(format!(
+            "Project path does not exist or is not a directory: {}",
+            project_dir.display()
+        )));
+    }
+
+    let paths = ScopePaths::for_project(project_dir);
+    let layers = load_layers(&paths);
+    let policy = layers.iter().find(|l| l.scope == ConfigScope::Managed);
+
+    Ok(ManagedPolicyReport {
+        path: managed_settings_path().to_string_lossy().to_string(),
+        present: policy.is_some(),
+        settings: policy.map(|l| l.value()),
+        overrides: find_policy_overrides(&layers),
+    })
+}
+
+/// Explain which layers define a key in a project's settings
+pub fn trace_project_key(project_dir: &Path, key: &str) -> Result<KeyProvenance, AppError> {
+    if !project_dir.is_dir() {
+        return Err(AppError::Filesystem(format!(
+            "Project path does not exist or is not a directory: {}",
+            project_dir.display()
+        )));
+    }
+
+    let layers = load_layers(&ScopePaths::for_project(project_dir));
+    Ok(trace_key(&layers, key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::permissions;
+    use crate::types::permission::PermissionBehavior;
+    use serde_json::json;
+
+    fn write_settings(path: &Path, value: Value) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, serde_json::to_string_pretty(&value).unwrap()).unwrap();
+    }
+
+    fn temp_paths(dir: &Path) -> ScopePaths {
+        ScopePaths {
+            managed: Some(dir.join("managed").join("managed-settings.json")),
+            user: Some(dir.join("home").join(".claude").join("settings.json")),
+            project: dir.join("project").join(".claude").join("settings.json"),
+            local: dir.join("project").join(".claude").join("settings.local.json"),
+        }
+    }
+
+    #[test]
+    fn test_load_layers_orders_by_precedence() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let paths = temp_paths(temp_dir.path());
+        write_settings(paths.user.as_ref().unwrap(), json!({"model": "user"}));
+        write_settings(&paths.local, json!({"model": "local"}));
+        write_settings(&paths.project, json!({"model": "project"}));
+
+        let layers = load_layers(&paths);
+        let scopes: Vec<ConfigScope> = layers.iter().map(|l| l.scope).collect();
+        assert_eq!(
+            scopes,
+            vec![ConfigScope::Local, ConfigScope::Project, ConfigScope::User]
+        );
+    }
+
+    #[test]
+    fn test_load_layers_skips_invalid_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let paths = temp_paths(temp_dir.path());
+        std::fs::create_dir_all(paths.project.parent().unwrap()).unwrap();
+        std::fs::write(&paths.project, "{ not json").unwrap();
+        write_settings(&paths.local, json!({"model": "local"}));
+
+        let layers = load_layers(&paths);
+        assert_eq!(layers.len(), 1);
+        assert_eq!(layers[0].scope, ConfigScope::Local);
+    }
+
+    #[test]
+    fn test_load_layers_keeps_layers_with_mistyped_settings() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let paths = temp_paths(temp_dir.path());
+        write_settings(
+            &paths.project,
+            json!({"model": 5, "permissions": {"allow": "Bash", "deny": ["Bash(rm:*)"]}}),
+        );
+        write_settings(&paths.local, json!({"model": "opus", "spinnerTipsEnabled": false}));
+
+        let layers = load_layers(&paths);
+        assert_eq!(layers.len(), 2);
+        assert_eq!(layers[0].settings.model.as_deref(), Some("opus"));
+        assert_eq!(layers[0].value()["spinnerTipsEnabled"], json!(false));
+        assert_eq!(layers[1].scope, ConfigScope::Project);
+        assert_eq!(layers[1].value(), json!({"permissions": {"deny": ["Bash(rm:*)"]}}));
+
+        // Deny rules next to a mistyped entry still apply
+        let project_dir = temp_dir.path().join("project");
+        let evaluation = permissions::evaluate(&layers, &project_dir, None, "Bash(rm -rf dist)").unwrap();
+        assert_eq!(evaluation.decision, PermissionBehavior::Deny);
+    }
+
+    #[test]
+    fn test_resolve_layers_highest_scope_wins() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let paths = temp_paths(temp_dir.path());
+        write_settings(paths.managed.as_ref().unwrap(), json!({"cleanupPeriodDays": 7}));
+        write_settings(paths.user.as_ref().unwrap(), json!({"model": "opus", "cleanupPeriodDays": 30}));
+        write_settings(&paths.project, json!({"model": "sonnet"}));
+
+        let layers = reader::with_managed_settings(paths.managed.as_ref().unwrap(), || load_layers(&paths));
+        let entries = resolve_layers(&layers);
+
+        let model = entries.iter().find(|e| e.key == "model").unwrap();
+        assert_eq!(model.value, json!("sonnet"));
+        assert_eq!(model.source.type_, "project");
+        assert_eq!(model.source.priority, ConfigScope::Project.priority());
+
+        let cleanup = entries.iter().find(|e| e.key == "cleanupPeriodDays").unwrap();
+        assert_eq!(cleanup.value, json!(7));
+        assert_eq!(cleanup.source.type_, "managed");
+    }
+
+    #[test]
+    fn test_resolve_layers_merges_permissions() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let paths = temp_paths(temp_dir.path());
+        write_settings(
+            paths.user.as_ref().unwrap(),
+            json!({"permissions": {"allow": ["Bash(ls)"], "defaultMode": "default"}}),
+        );
+        write_settings(
+            &paths.local,
+            json!({"permissions": {"allow": ["Bash(npm test)", "Bash(ls)"], "defaultMode": "acceptEdits"}}),
+        );
+
+        let entries = resolve_layers(&load_layers(&paths));
+        let permissions = entries.iter().find(|e| e.key == "permissions").unwrap();
+
+        assert_eq!(permissions.value["allow"], json!(["Bash(npm test)", "Bash(ls)"]));
+        assert_eq!(permissions.value["defaultMode"], json!("acceptEdits"));
+        assert_eq!(permissions.source.type_, "local");
+    }
+
+    #[test]
+    fn test_merge_values_env_objects() {
+        let lower = json!({"env": {"A": "1", "B": "2"}});
+        let higher = json!({"env": {"B": "3"}});
+        assert_eq!(
+            merge_values(&lower, &higher),
+            json!({"env": {"A": "1", "B": "3"}})
+        );
+    }
+
+    #[test]
+    fn test_key_to_pointer() {
+        assert_eq!(key_to_pointer("model"), "/model");
+        assert_eq!(key_to_pointer("permissions.allow"), "/permissions/allow");
+        assert_eq!(key_to_pointer("/permissions/allow"), "/permissions/allow");
+        assert_eq!(key_to_pointer("env.a/b"), "/env/a~1b");
+    }
+
+    #[test]
+    fn test_trace_key_overridden_scalar() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let paths = temp_paths(temp_dir.path());
+        write_settings(paths.user.as_ref().unwrap(), json!({"model": "opus"}));
+        write_settings(&paths.project, json!({"model": "sonnet"}));
+        write_settings(&paths.local, json!({"env": {}}));
+
+        let provenance = trace_key(&load_layers(&paths), "model");
+
+        assert_eq!(provenance.effective_value, Some(json!("sonnet")));
+        assert_eq!(provenance.chain.len(), 2);
+        assert_eq!(provenance.chain[0].source.type_, "project");
+        assert_eq!(provenance.chain[0].status, ProvenanceStatus::Effective);
+        assert_eq!(provenance.chain[1].source.type_, "user");
+        assert_eq!(provenance.chain[1].status, ProvenanceStatus::Overridden);
+    }
+
+    #[test]
+    fn test_trace_key_merged_arrays() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let paths = temp_paths(temp_dir.path());
+        write_settings(
+            paths.user.as_ref().unwrap(),
+            json!({"permissions": {"allow": ["Read"]}}),
+        );
+        write_settings(&paths.local, json!({"permissions": {"allow": ["Bash(ls)"]}}));
+
+        let provenance = trace_key(&load_layers(&paths), "/permissions/allow");
+
+        assert_eq!(provenance.chain[0].source.type_, "local");
+        assert_eq!(provenance.chain[1].status, ProvenanceStatus::Merged);
+        assert_eq!(provenance.effective_value, Some(json!(["Bash(ls)", "Read"])));
+    }
+
+    #[test]
+    fn test_trace_key_status_follows_merged_value() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let paths = temp_paths(temp_dir.path());
+        write_settings(paths.user.as_ref().unwrap(), json!({"companyAnnouncements": ["Read the docs"]}));
+        write_settings(&paths.project, json!({"companyAnnouncements": "Ship it"}));
+        write_settings(&paths.local, json!({"companyAnnouncements": ["Bash(ls)"]}));
+
+        let provenance = trace_key(&load_layers(&paths), "companyAnnouncements");
+
+        let statuses: Vec<ProvenanceStatus> = provenance.chain.iter().map(|step| step.status.clone()).collect();
+        assert_eq!(
+            statuses,
+            vec![ProvenanceStatus::Effective, ProvenanceStatus::Overridden, ProvenanceStatus::Overridden]
+        );
+        assert_eq!(provenance.effective_value, Some(json!(["Bash(ls)"])));
+    }
+
+    #[test]
+    fn test_trace_key_undefined() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let paths = temp_paths(temp_dir.path());
+        write_settings(&paths.project, json!({"model": "sonnet"}));
+
+        let provenance = trace_key(&load_layers(&paths), "permissions.deny");
+        assert!(provenance.chain.is_empty());
+        assert!(provenance.effective_value.is_none());
+    }
+
+    #[test]
+    fn test_managed_policy_is_highest_priority() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let paths = temp_paths(temp_dir.path());
+        write_settings(
+            paths.managed.as_ref().unwrap(),
+            json!({"permissions": {"defaultMode": "plan"}}),
+        );
+        write_settings(
+            &paths.local,
+            json!({"permissions": {"defaultMode": "bypassPermissions"}}),
+        );
+
+        let layers = reader::with_managed_settings(paths.managed.as_ref().unwrap(), || load_layers(&paths));
+        assert_eq!(layers[0].scope, ConfigScope::Managed);
+
+        let entries = resolve_layers(&layers);
+        let permissions = entries.iter().find(|e| e.key == "permissions").unwrap();
+        assert_eq!(permissions.value["defaultMode"], json!("plan"));
+        assert_eq!(permissions.source.type_, "managed");
+    }
+
+    #[test]
+    fn test_find_policy_overrides() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let paths = temp_paths(temp_dir.path());
+        write_settings(
+            paths.managed.as_ref().unwrap(),
+            json!({
+                "model": "sonnet",
+                "permissions": {"defaultMode": "plan", "deny": ["WebFetch"]}
+            }),
+        );
+        write_settings(
+            paths.user.as_ref().unwrap(),
+            json!({"model": "opus", "permissions": {"deny": ["Bash(rm:*)"]}}),
+        );
+        write_settings(
+            &paths.project,
+            json!({"model": "sonnet", "permissions": {"defaultMode": "acceptEdits"}}),
+        );
+
+        let layers = reader::with_managed_settings(paths.managed.as_ref().unwrap(), || load_layers(&paths));
+        let overrides = find_policy_overrides(&layers);
+
+        assert_eq!(overrides.len(), 2);
+        let mode = overrides
+            .iter()
+            .find(|o| o.pointer == "/permissions/defaultMode")
+            .unwrap();
+        assert_eq!(mode.overridden_source.type_, "project");
+        assert_eq!(mode.overridden_value, json!("acceptEdits"));
+        let model = overrides.iter().find(|o| o.pointer == "/model").unwrap();
+        assert_eq!(model.overridden_source.type_, "user");
+        assert_eq!(model.policy_value, json!("sonnet"));
+    }
+
+    #[test]
+    fn test_find_policy_overrides_without_policy() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let paths = temp_paths(temp_dir.path());
+        write_settings(&paths.project, json!({"model": "sonnet"}));
+
+        assert!(find_policy_overrides(&load_layers(&paths)).is_empty());
+    }
+
+    #[test]
+    fn test_resolve_effective_config_missing_project() {
+        let result = resolve_effective_config(Path::new("/nonexistent/project"));
+        assert!(result.is_err());
+    }
+}
@@ -1,9 +1,10 @@
 //! Application settings module
 //!
-//! This module will be used in Story 1.6 (Zustand Stores Implementation)
-//! and Story 1.7 (File System Access Module) for persisting user preferences.
+//! Persists user preferences to `<config dir>/cc-config-viewer/settings.json`.
 
+use crate::config::writer;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 /// Application settings for window and theme preferences
 #[derive(Debug, Serialize, Deserialize)]
@@ -26,17 +27,26 @@ impl Default for AppSettings {
     }
 }
 
-/// Load settings from configuration file
-/// TODO: Implement in Story 1.7 (File System Access Module)
+/// Location of the settings file
+fn settings_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("cc-config-viewer").join("settings.json"))
+}
+
+/// Load settings from configuration file, using defaults when it does not exist
 #[allow(dead_code)]
 pub fn load_settings() -> Result<AppSettings, Box<dyn std::error::Error>> {
-    Ok(AppSettings::default())
+    let path = match settings_path() {
+        Some(path) if path.is_file() => path,
+        _ => return Ok(AppSettings::default()),
+    };
+    let content = std::fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&content)?)
 }
 
 /// Save settings to configuration file
-/// TODO: Implement in Story 1.7 (File System Access Module)
 #[allow(dead_code)]
 pub fn save_settings(settings: &AppSettings) -> Result<(), Box<dyn std::error::Error>> {
-    println!("Saving settings: {:?}", settings);
+    let path = settings_path().ok_or("No configuration directory available")?;
+    writer::write_json(&path, &serde_json::to_value(settings)?, writer::backup_dir().as_deref())?;
     Ok(())
 }
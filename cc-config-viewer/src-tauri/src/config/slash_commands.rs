@@ -0,0 +1,243 @@
+//! Slash command discovery
+//!
+//! Reads custom slash commands from `~/.claude/commands` (user scope) and
+//! `<project>/.claude/commands` (project scope). Subdirectories act as
+//! namespaces, so `commands/frontend/component.md` is `frontend:component`.
+
+use crate::config::frontmatter;
+use crate::types::app::{AppError, ConfigScope};
+use crate::types::slash_command::SlashCommand;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Frontmatter keys Claude Code reads from a command file
+const COMMAND_KEYS: &[&str] = &["description", "allowed-tools", "argument-hint", "model"];
+
+/// Deepest namespace nesting followed while scanning
+const MAX_NAMESPACE_DEPTH: usize = 8;
+
+/// User-level commands directory (`~/.claude/commands`)
+pub fn user_commands_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".claude").join("commands"))
+}
+
+/// Project-level commands directory (`<project>/.claude/commands`)
+pub fn project_commands_dir(project_dir: &Path) -> PathBuf {
+    project_dir.join(".claude").join("commands")
+}
+
+/// Whether a path is a command file (`.claude/commands/**/*.md`)
+pub fn is_command_file(path: &Path) -> bool {
+    if path.extension().and_then(|e| e.to_str()) != Some("md") {
+        return false;
+    }
+
+    let components: Vec<&str> = path
+        .components()
+        .filter_map(|c| c.as_os_str().to_str())
+        .collect();
+    components
+        .windows(2)
+        .any(|pair| pair[0] == ".claude" && pair[1] == "commands")
+}
+
+/// Build a slash command from its Markdown file
+///
+/// `commands_dir` is the `commands` directory the file was found in and
+/// determines the namespace.
+pub fn parse_slash_command(
+    commands_dir: &Path,
+    path: &Path,
+    content: &str,
+    scope: ConfigScope,
+) -> SlashCommand {
+    let document = frontmatter::parse(content);
+    let text = |key: &str| document.get(key).map(|entry| entry.value.text());
+
+    let name = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("unknown")
+        .to_string();
+
+    let namespace = path
+        .parent()
+        .and_then(|parent| parent.strip_prefix(commands_dir).ok())
+        .map(|relative| {
+            relative
+                .components()
+                .filter_map(|c| c.as_os_str().to_str())
+                .collect::<Vec<_>>()
+                .join(":")
+        })
+        .filter(|namespace| !namespace.is_empty());
+
+    let extra = document
+        .frontmatter
+        .iter()
+        .flatten()
+        .filter(|entry| !COMMAND_KEYS.contains(&entry.key.as_str()))
+        .map(|entry| (entry.key.clone(), entry.value.text()))
+        .collect::<BTreeMap<_, _>>();
+
+    SlashCommand {
+        name,
+        namespace,
+        description: text("description"),
+        allowed_tools: document.get("allowed-tools").map(|entry| entry.value.items()),
+        // Hints like `[pr-number] [priority]` are not YAML lists; keep them as written
+        argument_hint: document.get("argument-hint").map(|entry| entry.scalar()),
+        model: text("model"),
+        prompt: document.body.trim().to_string(),
+        scope,
+        path: path.to_string_lossy().to_string(),
+        extra,
+    }
+}
+
+/// Command files below a commands directory, sorted by path
+pub fn command_files(commands_dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    collect_command_files(commands_dir, 0, &mut files);
+    files.sort();
+    files
+}
+
+fn collect_command_files(dir: &Path, depth: usize, files: &mut Vec<PathBuf>) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        // Don't follow symlinked directories to avoid cycles
+        let is_symlink = entry.file_type().map(|t| t.is_symlink()).unwrap_or(false);
+
+        if path.is_dir() && !is_symlink && depth < MAX_NAMESPACE_DEPTH {
+            collect_command_files(&path, depth + 1, files);
+        } else if path.is_file() && path.extension().and_then(|e| e.to_str()) == Some("md") {
+            files.push(path);
+        }
+    }
+}
+
+/// Read every command below a commands directory, skipping unreadable files
+pub fn discover_commands(commands_dir: &Path, scope: ConfigScope) -> Vec<SlashCommand> {
+    command_files(commands_dir)
+        .iter()
+        .filter_map(|path| match read_command(commands_dir, path, scope) {
+            Ok(command) => Some(command),
+            Err(e) => {
+                eprintln!("Warning: Failed to read command {}: {}", path.display(), e);
+                None
+            }
+        })
+        .collect()
+}
+
+fn read_command(commands_dir: &Path, path: &Path, scope: ConfigScope) -> Result<SlashCommand, AppError> {
+    let content = std::fs::read_to_string(path).map_err(AppError::from)?;
+    Ok(parse_slash_command(commands_dir, path, &content, scope))
+}
+
+/// User commands followed by the project's commands
+pub fn list_commands(project_dir: Option<&Path>) -> Vec<SlashCommand> {
+    let mut commands = user_commands_dir()
+        .map(|dir| discover_commands(&dir, ConfigScope::User))
+        .unwrap_or_default();
+
+    if let Some(project_dir) = project_dir {
+        commands.extend(discover_commands(&project_commands_dir(project_dir), ConfigScope::Project));
+    }
+
+    commands
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_slash_command() {
+        let content = "---\ndescription: Create a git commit\nallowed-tools: Bash(git add:*), Bash(git commit:*)\nargument-hint: [message]\nmodel: haiku\n---\nCommit with message: $ARGUMENTS\n";
+        let command = parse_slash_command(
+            Path::new("/p/.claude/commands"),
+            Path::new("/p/.claude/commands/git/commit.md"),
+            content,
+            ConfigScope::Project,
+        );
+
+        assert_eq!(command.name, "commit");
+        assert_eq!(command.namespace.as_deref(), Some("git"));
+        assert_eq!(command.qualified_name(), "git:commit");
+        assert_eq!(command.description.as_deref(), Some("Create a git commit"));
+        assert_eq!(
+            command.allowed_tools,
+            Some(vec!["Bash(git add:*)".to_string(), "Bash(git commit:*)".to_string()])
+        );
+        assert_eq!(command.argument_hint.as_deref(), Some("[message]"));
+        assert_eq!(command.model.as_deref(), Some("haiku"));
+        assert_eq!(command.prompt, "Commit with message: $ARGUMENTS");
+    }
+
+    #[test]
+    fn test_quoted_argument_hint_is_unquoted() {
+        let content = "---\nargument-hint: \"[message]\"\n---\nbody\n";
+        let command = parse_slash_command(
+            Path::new("/p/.claude/commands"),
+            Path::new("/p/.claude/commands/commit.md"),
+            content,
+            ConfigScope::Project,
+        );
+        assert_eq!(command.argument_hint.as_deref(), Some("[message]"));
+
+        let content = "---\nargument-hint: [pr-number] [priority]\n---\nbody\n";
+        let command = parse_slash_command(
+            Path::new("/p/.claude/commands"),
+            Path::new("/p/.claude/commands/review.md"),
+            content,
+            ConfigScope::Project,
+        );
+        assert_eq!(command.argument_hint.as_deref(), Some("[pr-number] [priority]"));
+    }
+
+    #[test]
+    fn test_command_without_frontmatter() {
+        let command = parse_slash_command(
+            Path::new("/h/.claude/commands"),
+            Path::new("/h/.claude/commands/optimize.md"),
+            "Analyze this code for performance issues.",
+            ConfigScope::User,
+        );
+
+        assert_eq!(command.qualified_name(), "optimize");
+        assert!(command.namespace.is_none());
+        assert!(command.description.is_none());
+    }
+
+    #[test]
+    fn test_discover_commands_with_namespaces() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let commands_dir = temp_dir.path().join("commands");
+        std::fs::create_dir_all(commands_dir.join("frontend").join("forms")).unwrap();
+        std::fs::write(commands_dir.join("review.md"), "Review").unwrap();
+        std::fs::write(commands_dir.join("frontend").join("component.md"), "Build").unwrap();
+        std::fs::write(commands_dir.join("frontend").join("forms").join("validate.md"), "Check").unwrap();
+        std::fs::write(commands_dir.join("notes.txt"), "x").unwrap();
+
+        let names: Vec<String> = discover_commands(&commands_dir, ConfigScope::Project)
+            .iter()
+            .map(|c| c.qualified_name())
+            .collect();
+        assert_eq!(names, vec!["frontend:component", "frontend:forms:validate", "review"]);
+    }
+
+    #[test]
+    fn test_is_command_file() {
+        assert!(is_command_file(Path::new("/p/.claude/commands/review.md")));
+        assert!(is_command_file(Path::new("/p/.claude/commands/git/commit.md")));
+        assert!(!is_command_file(Path::new("/p/.claude/agents/review.md")));
+        assert!(!is_command_file(Path::new("/p/docs/commands/review.md")));
+    }
+}
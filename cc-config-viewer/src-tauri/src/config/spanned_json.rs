@@ -0,0 +1,761 @@
+//! Span-aware JSON parsing
+//!
+//! Parses JSON into a `serde_json::Value` while recording where every node
+//! lives in the source, keyed by JSON pointer, so diagnostics and editor jumps
+//! can point at the exact key or value instead of guessing from line content.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+use std::collections::BTreeMap;
+
+/// Location of a piece of source text
+///
+/// `start`/`end` are byte offsets; lines and columns are 1-based, with
+/// columns counted in characters.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: u32,
+    pub column: u32,
+    pub end_line: u32,
+    pub end_column: u32,
+}
+
+/// Source location of a JSON node and of the key that introduces it
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct NodeSpan {
+    pub key: Option<Span>,
+    pub value: Span,
+}
+
+impl NodeSpan {
+    /// Start of the node, preferring the key for object members
+    pub fn start(&self) -> &Span {
+        self.key.as_ref().unwrap_or(&self.value)
+    }
+}
+
+/// Parsed JSON document with the span of every node
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SpannedDocument {
+    pub value: Value,
+    /// Spans keyed by JSON pointer (`""` is the root)
+    pub spans: BTreeMap<String, NodeSpan>,
+}
+
+impl SpannedDocument {
+    /// Span of the node at a JSON pointer
+    pub fn span(&self, pointer: &str) -> Option<&NodeSpan> {
+        self.spans.get(pointer)
+    }
+
+    /// Line and column where the node at a JSON pointer starts
+    pub fn location(&self, pointer: &str) -> Option<(u32, u32)> {
+        self.span(pointer).map(|span| (span.start().line, span.start().column))
+    }
+}
+
+/// Result of a tolerant parse
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TolerantDocument {
+    /// Recovered document, or `None` when nothing could be read
+    pub document: Option<SpannedDocument>,
+    /// Every syntax problem found, in source order
+    pub issues: Vec<SyntaxError>,
+    /// Strict JSON rewrite of the recovered value, when the source needed fixing
+    pub normalized: Option<String>,
+}
+
+/// JSON syntax error with its position
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SyntaxError {
+    pub message: String,
+    pub offset: usize,
+    pub line: u32,
+    pub column: u32,
+}
+
+impl std::fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at line {} column {}", self.message, self.line, self.column)
+    }
+}
+
+/// Maps byte offsets to line and column numbers
+struct LineIndex<'a> {
+    source: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    fn new(source: &'a str) -> Self {
+        let mut line_starts = vec![0];
+        line_starts.extend(source.match_indices('\n').map(|(i, _)| i + 1));
+        Self { source, line_starts }
+    }
+
+    fn position(&self, offset: usize) -> (u32, u32) {
+        let line = match self.line_starts.binary_search(&offset) {
+            Ok(line) => line,
+            Err(next) => next - 1,
+        };
+        let line_start = self.line_starts[line];
+        let column = self.source[line_start..offset].chars().count();
+        (line as u32 + 1, column as u32 + 1)
+    }
+
+    fn span(&self, start: usize, end: usize) -> Span {
+        let (line, column) = self.position(start);
+        let (end_line, end_column) = self.position(end);
+        Span {
+            start,
+            end,
+            line,
+            column,
+            end_line,
+            end_column,
+        }
+    }
+}
+
+/// Deepest nesting of objects and arrays accepted, the same limit as serde_json
+const MAX_DEPTH: usize = 128;
+
+/// Recursive-descent JSON parser that records spans
+struct Parser<'a> {
+    source: &'a str,
+    bytes: &'a [u8],
+    pos: usize,
+    index: LineIndex<'a>,
+    spans: BTreeMap<String, NodeSpan>,
+    /// Accept comments, trailing commas and JSON5 quoting, recording each as an issue
+    tolerant: bool,
+    issues: Vec<SyntaxError>,
+    /// Objects and arrays currently open
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(source: &'a str, tolerant: bool) -> Self {
+        Self {
+            source,
+            bytes: source.as_bytes(),
+            pos: 0,
+            index: LineIndex::new(source),
+            spans: BTreeMap::new(),
+            tolerant,
+            issues: Vec::new(),
+            depth: 0,
+        }
+    }
+
+    fn error(&self, message: impl Into<String>) -> SyntaxError {
+        self.error_at(self.pos, message)
+    }
+
+    fn error_at(&self, offset: usize, message: impl Into<String>) -> SyntaxError {
+        let offset = offset.min(self.source.len());
+        let (line, column) = self.index.position(offset);
+        SyntaxError {
+            message: message.into(),
+            offset,
+            line,
+            column,
+        }
+    }
+
+    /// Record a problem in tolerant mode, or fail with it in strict mode
+    fn report(&mut self, error: SyntaxError) -> Result<(), SyntaxError> {
+        if self.tolerant {
+            self.issues.push(error);
+            Ok(())
+        } else {
+            Err(error)
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    /// Skip whitespace, and comments in tolerant mode
+    fn skip_trivia(&mut self) {
+        loop {
+            match self.peek() {
+                Some(b' ' | b'\t' | b'\n' | b'\r') => self.pos += 1,
+                Some(b'/') if self.tolerant && matches!(self.bytes.get(self.pos + 1), Some(b'/' | b'*')) => {
+                    self.skip_comment()
+                }
+                _ => return,
+            }
+        }
+    }
+
+    fn skip_comment(&mut self) {
+        self.issues.push(self.error("Comments are not allowed in JSON"));
+
+        if self.bytes[self.pos + 1] == b'/' {
+            self.pos = self.source[self.pos..]
+                .find('\n')
+                .map_or(self.source.len(), |i| self.pos + i);
+        } else {
+            match self.source[self.pos + 2..].find("*/") {
+                Some(i) => self.pos += i + 4,
+                None => {
+                    self.issues.push(self.error("Unterminated comment"));
+                    self.pos = self.source.len();
+                }
+            }
+        }
+    }
+
+    /// Skip to the next `,`, `}` or `]` that is not nested in the broken value
+    fn recover(&mut self) {
+        let mut depth = 0usize;
+
+        while let Some(byte) = self.peek() {
+            match byte {
+                b'"' | b'\'' => {
+                    self.pos += 1;
+                    while let Some(next) = self.peek() {
+                        self.pos += 1;
+                        if next == b'\\' {
+                            self.pos += 1;
+                        } else if next == byte || next == b'\n' {
+                            break;
+                        }
+                    }
+                    continue;
+                }
+                b'{' | b'[' => depth += 1,
+                b'}' | b']' | b',' if depth == 0 => break,
+                b'}' | b']' => depth -= 1,
+                _ => {}
+            }
+            self.pos += 1;
+        }
+
+        self.pos = self.pos.min(self.source.len());
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), SyntaxError> {
+        if self.peek() == Some(byte) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(format!("Expected '{}'", byte as char)))
+        }
+    }
+
+    /// Parse the root value and reject anything after it
+    fn parse_document(&mut self) -> Result<Value, SyntaxError> {
+        // Tolerate a UTF-8 byte order mark
+        if self.source.starts_with('\u{feff}') {
+            self.pos = '\u{feff}'.len_utf8();
+        }
+
+        self.skip_trivia();
+        let value = self.parse_value("", None)?;
+
+        self.skip_trivia();
+        if self.pos < self.source.len() {
+            self.report(self.error("Trailing characters after JSON value"))?;
+        }
+
+        Ok(value)
+    }
+
+    /// Parse a value and record its span under `pointer`
+    fn parse_value(&mut self, pointer: &str, key: Option<Span>) -> Result<Value, SyntaxError> {
+        self.skip_trivia();
+        let start = self.pos;
+
+        let value = match self.peek() {
+            Some(b'{' | b'[') if self.depth >= MAX_DEPTH => {
+                return Err(self.error("Recursion limit exceeded"));
+            }
+            Some(b'{') => {
+                self.depth += 1;
+                let object = self.parse_object(pointer);
+                self.depth -= 1;
+                object?
+            }
+            Some(b'[') => {
+                self.depth += 1;
+                let array = self.parse_array(pointer);
+                self.depth -= 1;
+                array?
+            }
+            Some(b'"') => Value::String(self.parse_string(b'"')?),
+            Some(b'\'') if self.tolerant => {
+                self.report(self.error("Strings must use double quotes"))?;
+                Value::String(self.parse_string(b'\'')?)
+            }
+            Some(b't') => self.parse_literal("true", Value::Bool(true))?,
+            Some(b'f') => self.parse_literal("false", Value::Bool(false))?,
+            Some(b'n') => self.parse_literal("null", Value::Null)?,
+            Some(b'-' | b'0'..=b'9') => self.parse_number()?,
+            Some(_) => return Err(self.error("Unexpected character")),
+            None => return Err(self.error("Unexpected end of input")),
+        };
+
+        self.spans.insert(
+            pointer.to_string(),
+            NodeSpan {
+                key,
+                value: self.index.span(start, self.pos),
+            },
+        );
+        Ok(value)
+    }
+
+    fn parse_object(&mut self, pointer: &str) -> Result<Value, SyntaxError> {
+        self.expect(b'{')?;
+        let mut object = Map::new();
+
+        loop {
+            self.skip_trivia();
+            match self.peek() {
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(Value::Object(object));
+                }
+                None => {
+                    self.report(self.error("Unterminated object"))?;
+                    return Ok(Value::Object(object));
+                }
+                _ => {}
+            }
+
+            if let Err(error) = self.parse_member(pointer, &mut object) {
+                self.report(error)?;
+                self.recover();
+            }
+
+            self.skip_trivia();
+            match self.peek() {
+                Some(b',') => self.parse_separator(b'}')?,
+                Some(b'}') | None => {}
+                Some(b']') => {
+                    self.report(self.error("Unexpected ']' in object"))?;
+                    self.pos += 1;
+                }
+                // A missing comma between members; carry on with the next key
+                Some(byte) if byte == b'"' || byte == b'\'' || is_identifier_start(byte) => {
+                    self.report(self.error("Expected ',' or '}'"))?
+                }
+                Some(_) => {
+                    self.report(self.error("Expected ',' or '}'"))?;
+                    self.recover();
+                }
+            }
+        }
+    }
+
+    fn parse_member(&mut self, pointer: &str, object: &mut Map<String, Value>) -> Result<(), SyntaxError> {
+        let key_start = self.pos;
+        let key = match self.peek() {
+            Some(b'"') => self.parse_string(b'"')?,
+            Some(b'\'') if self.tolerant => {
+                self.report(self.error("Strings must use double quotes"))?;
+                self.parse_string(b'\'')?
+            }
+            Some(byte) if self.tolerant && is_identifier_start(byte) => {
+                self.report(self.error("Object keys must be quoted"))?;
+                self.parse_identifier()
+            }
+            _ => return Err(self.error("Expected object key")),
+        };
+        let key_span = self.index.span(key_start, self.pos);
+
+        self.skip_trivia();
+        self.expect(b':')?;
+
+        let child_pointer = format!("{}/{}", pointer, escape_pointer(&key));
+        let value = self.parse_value(&child_pointer, Some(key_span))?;
+        object.insert(key, value);
+        Ok(())
+    }
+
+    fn parse_array(&mut self, pointer: &str) -> Result<Value, SyntaxError> {
+        self.expect(b'[')?;
+        let mut array = Vec::new();
+
+        loop {
+            self.skip_trivia();
+            match self.peek() {
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(Value::Array(array));
+                }
+                None => {
+                    self.report(self.error("Unterminated array"))?;
+                    return Ok(Value::Array(array));
+                }
+                _ => {}
+            }
+
+            let child_pointer = format!("{}/{}", pointer, array.len());
+            match self.parse_value(&child_pointer, None) {
+                Ok(value) => array.push(value),
+                Err(error) => {
+                    self.report(error)?;
+                    self.recover();
+                }
+            }
+
+            self.skip_trivia();
+            match self.peek() {
+                Some(b',') => self.parse_separator(b']')?,
+                Some(b']') | None => {}
+                Some(b'}') => {
+                    self.report(self.error("Unexpected '}' in array"))?;
+                    self.pos += 1;
+                }
+                Some(_) => {
+                    self.report(self.error("Expected ',' or ']'"))?;
+                    self.recover();
+                }
+            }
+        }
+    }
+
+    /// Consume a `,`, reporting it when it trails the last item before `close`
+    fn parse_separator(&mut self, close: u8) -> Result<(), SyntaxError> {
+        let comma = self.pos;
+        self.pos += 1;
+        self.skip_trivia();
+
+        if self.peek() == Some(close) {
+            self.report(self.error_at(comma, "Trailing comma"))?;
+        }
+        Ok(())
+    }
+
+    fn parse_identifier(&mut self) -> String {
+        let start = self.pos;
+        while let Some(byte) = self.peek() {
+            if !is_identifier_start(byte) && !byte.is_ascii_digit() {
+                break;
+            }
+            self.pos += 1;
+        }
+        self.source[start..self.pos].to_string()
+    }
+
+    fn parse_string(&mut self, quote: u8) -> Result<String, SyntaxError> {
+        self.expect(quote)?;
+        let mut result = String::new();
+
+        loop {
+            let chunk_start = self.pos;
+            while let Some(byte) = self.peek() {
+                if byte == quote || byte == b'\\' || byte < 0x20 {
+                    break;
+                }
+                self.pos += 1;
+            }
+            result.push_str(&self.source[chunk_start..self.pos]);
+
+            match self.peek() {
+                Some(byte) if byte == quote => {
+                    self.pos += 1;
+                    return Ok(result);
+                }
+                Some(b'\\') => {
+                    self.pos += 1;
+                    result.push(self.parse_escape()?);
+                }
+                Some(_) => return Err(self.error("Control character in string")),
+                None => return Err(self.error("Unterminated string")),
+            }
+        }
+    }
+
+    fn parse_escape(&mut self) -> Result<char, SyntaxError> {
+        let escaped = self.peek().ok_or_else(|| self.error("Unterminated string"))?;
+        self.pos += 1;
+
+        let c = match escaped {
+            b'"' => '"',
+            b'\'' if self.tolerant => '\'',
+            b'\\' => '\\',
+            b'/' => '/',
+            b'b' => '\u{8}',
+            b'f' => '\u{c}',
+            b'n' => '\n',
+            b'r' => '\r',
+            b't' => '\t',
+            b'u' => {
+                let high = self.parse_hex4()?;
+                if (0xD800..0xDC00).contains(&high) {
+                    if self.source[self.pos..].starts_with("\\u") {
+                        self.pos += 2;
+                        let low = self.parse_hex4()?;
+                        let code = 0x10000 + ((high - 0xD800) << 10) + (low.wrapping_sub(0xDC00) & 0x3FF);
+                        char::from_u32(code).unwrap_or('\u{FFFD}')
+                    } else {
+                        '\u{FFFD}'
+                    }
+                } else {
+                    char::from_u32(high).unwrap_or('\u{FFFD}')
+                }
+            }
+            _ => return Err(self.error("Invalid escape sequence")),
+        };
+
+        Ok(c)
+    }
+
+    fn parse_hex4(&mut self) -> Result<u32, SyntaxError> {
+        let digits = self
+            .source
+            .get(self.pos..self.pos + 4)
+            .ok_or_else(|| self.error("Invalid unicode escape"))?;
+        let code = u32::from_str_radix(digits, 16).map_err(|_| self.error("Invalid unicode escape"))?;
+        self.pos += 4;
+        Ok(code)
+    }
+
+    fn parse_literal(&mut self, literal: &str, value: Value) -> Result<Value, SyntaxError> {
+        if self.source[self.pos..].starts_with(literal) {
+            self.pos += literal.len();
+            Ok(value)
+        } else {
+            Err(self.error("Unexpected token"))
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<Value, SyntaxError> {
+        let start = self.pos;
+        while let Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9') = self.peek() {
+            self.pos += 1;
+        }
+
+        let text = &self.source[start..self.pos];
+        let number: Number = serde_json::from_str(text).map_err(|_| {
+            let mut error = self.error(format!("Invalid number '{}'", text));
+            let (line, column) = self.index.position(start);
+            error.offset = start;
+            error.line = line;
+            error.column = column;
+            error
+        })?;
+        Ok(Value::Number(number))
+    }
+}
+
+/// Escape a key for use as a JSON pointer segment
+pub fn escape_pointer(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+fn is_identifier_start(byte: u8) -> bool {
+    byte.is_ascii_alphabetic() || byte == b'_' || byte == b'$'
+}
+
+/// Parse JSON, recording the span of every node by JSON pointer
+pub fn parse_with_spans(content: &str) -> Result<SpannedDocument, SyntaxError> {
+    let mut parser = Parser::new(content, false);
+    let value = parser.parse_document()?;
+
+    Ok(SpannedDocument {
+        value,
+        spans: parser.spans,
+    })
+}
+
+/// Parse hand-edited JSON, recovering what can be read
+///
+/// Comments, trailing commas, single-quoted strings, unquoted keys and
+/// missing commas are accepted; members that cannot be read are dropped.
+/// Every problem is returned in `issues`, and `normalized` holds a strict
+/// JSON rewrite of the recovered value whenever the source needed fixing.
+pub fn parse_tolerant(content: &str) -> TolerantDocument {
+    let mut parser = Parser::new(content, true);
+    let result = parser.parse_document();
+    let mut issues = std::mem::take(&mut parser.issues);
+
+    let document = match result {
+        Ok(value) => Some(SpannedDocument {
+            value,
+            spans: parser.spans,
+        }),
+        Err(error) => {
+            issues.push(error);
+            None
+        }
+    };
+    issues.sort_by_key(|issue| issue.offset);
+
+    let normalized = match &document {
+        Some(document) if !issues.is_empty() => serde_json::to_string_pretty(&document.value).ok(),
+        _ => None,
+    };
+
+    TolerantDocument {
+        document,
+        issues,
+        normalized,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn recovered(parsed: &TolerantDocument) -> Option<&Value> {
+        parsed.document.as_ref().map(|document| &document.value)
+    }
+
+    #[test]
+    fn test_value_matches_serde_json() {
+        let content = r#"{"a": [1, -2.5, 3e2, true, null], "b": {"c": "dé\n"}, "e": ""}"#;
+        let document = parse_with_spans(content).unwrap();
+        let expected: Value = serde_json::from_str(content).unwrap();
+        assert_eq!(document.value, expected);
+    }
+
+    #[test]
+    fn test_spans_for_nested_nodes() {
+        let content = "{\n  \"permissions\": {\n    \"allow\": [\"Read\", \"Bash\"]\n  }\n}";
+        let document = parse_with_spans(content).unwrap();
+
+        let permissions = document.span("/permissions").unwrap();
+        assert_eq!(permissions.key.unwrap().line, 2);
+        assert_eq!(permissions.key.unwrap().column, 3);
+
+        let bash = document.span("/permissions/allow/1").unwrap();
+        assert!(bash.key.is_none());
+        assert_eq!(bash.value.line, 3);
+        assert_eq!(bash.value.column, 23);
+        assert_eq!(&content[bash.value.start..bash.value.end], "\"Bash\"");
+
+        assert_eq!(document.location("/permissions/allow"), Some((3, 5)));
+        assert_eq!(document.span("").unwrap().value.end, content.len());
+    }
+
+    #[test]
+    fn test_repeated_strings_resolve_to_their_own_node() {
+        let content = "{\n  \"allow\": [\"Bash\"],\n  \"deny\": [\"Bash\"]\n}";
+        let document = parse_with_spans(content).unwrap();
+
+        assert_eq!(document.span("/allow/0").unwrap().value.line, 2);
+        assert_eq!(document.span("/deny/0").unwrap().value.line, 3);
+    }
+
+    #[test]
+    fn test_escaped_pointer_segments() {
+        let document = parse_with_spans(r#"{"projects": {"/home/a~b": {}}}"#).unwrap();
+        assert!(document.span("/projects/~1home~1a~0b").is_some());
+    }
+
+    #[test]
+    fn test_columns_count_characters() {
+        let document = parse_with_spans(r#"{"é": 1, "x": 2}"#).unwrap();
+        assert_eq!(document.location("/x"), Some((1, 10)));
+    }
+
+    #[test]
+    fn test_syntax_error_position() {
+        let error = parse_with_spans("{\n  \"a\": 1,\n  \"b\" 2\n}").unwrap_err();
+        assert_eq!(error.line, 3);
+        assert_eq!(error.column, 7);
+    }
+
+    #[test]
+    fn test_strict_mode_rejects_jsonc() {
+        assert!(parse_with_spans("{\"a\": 1, // note\n}").is_err());
+        let error = parse_with_spans("{\"a\": [1, 2,]}").unwrap_err();
+        assert_eq!(error.message, "Trailing comma");
+        assert_eq!(error.column, 12);
+    }
+
+    #[test]
+    fn test_tolerant_comments_and_trailing_commas() {
+        let content = "{\n  // servers\n  \"mcpServers\": {\n    \"github\": {\"command\": \"gh\",},\n  }, /* end */\n}";
+        let parsed = parse_tolerant(content);
+
+        assert_eq!(recovered(&parsed), Some(&json!({"mcpServers": {"github": {"command": "gh"}}})));
+        let messages: Vec<&str> = parsed.issues.iter().map(|i| i.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Comments are not allowed in JSON",
+                "Trailing comma",
+                "Trailing comma",
+                "Trailing comma",
+                "Comments are not allowed in JSON",
+            ]
+        );
+        assert_eq!((parsed.issues[0].line, parsed.issues[0].column), (2, 3));
+        assert_eq!((parsed.issues[1].line, parsed.issues[1].column), (4, 31));
+        assert_eq!((parsed.issues[3].line, parsed.issues[3].column), (5, 4));
+
+        let normalized = parsed.normalized.clone().unwrap();
+        assert_eq!(parse_with_spans(&normalized).unwrap().value, *recovered(&parsed).unwrap());
+    }
+
+    #[test]
+    fn test_tolerant_json5_quoting() {
+        let parsed = parse_tolerant("{model: 'opus', 'env': {\"A\": 'it\\'s'}}");
+        assert_eq!(recovered(&parsed), Some(&json!({"model": "opus", "env": {"A": "it's"}})));
+        assert_eq!(parsed.issues.len(), 4);
+    }
+
+    #[test]
+    fn test_tolerant_recovers_around_broken_members() {
+        let content = "{\n  \"a\": tru,\n  \"b\": [1, @, 3]\n  \"c\": {\"d\": 1}\n}";
+        let parsed = parse_tolerant(content);
+
+        assert_eq!(recovered(&parsed), Some(&json!({"b": [1, 3], "c": {"d": 1}})));
+        assert_eq!(parsed.issues.len(), 3);
+        assert_eq!(parsed.issues[2].line, 4);
+        let document = parsed.document.as_ref().unwrap();
+        assert_eq!(document.location("/c/d"), Some((4, 9)));
+    }
+
+    #[test]
+    fn test_deep_nesting_is_rejected() {
+        let content = format!("{}{}", "[".repeat(100_000), "]".repeat(100_000));
+
+        let error = parse_with_spans(&content).unwrap_err();
+        assert_eq!(error.message, "Recursion limit exceeded");
+        assert_eq!(error.offset, MAX_DEPTH);
+
+        let parsed = parse_tolerant(&content);
+        assert!(parsed.issues.iter().any(|issue| issue.message == "Recursion limit exceeded"));
+
+        let nested = format!("{}{}", "[".repeat(MAX_DEPTH), "]".repeat(MAX_DEPTH));
+        assert!(parse_with_spans(&nested).is_ok());
+    }
+
+    #[test]
+    fn test_tolerant_truncated_file() {
+        let parsed = parse_tolerant("{\"mcpServers\": {\"github\": {\"command\": \"gh\"}");
+        assert_eq!(recovered(&parsed), Some(&json!({"mcpServers": {"github": {"command": "gh"}}})));
+        assert_eq!(parsed.issues.len(), 2);
+        assert!(parsed.normalized.is_some());
+    }
+
+    #[test]
+    fn test_tolerant_clean_and_unreadable_input() {
+        let clean = parse_tolerant(r#"{"a": 1}"#);
+        assert!(clean.issues.is_empty());
+        assert!(clean.normalized.is_none());
+
+        let unreadable = parse_tolerant("not json");
+        assert!(unreadable.document.is_none());
+        assert_eq!(unreadable.issues.len(), 1);
+        assert!(unreadable.normalized.is_none());
+    }
+
+    #[test]
+    fn test_trailing_characters_rejected() {
+        assert!(parse_with_spans("{} {}").is_err());
+        assert!(parse_with_spans("").is_err());
+        assert_eq!(parse_with_spans("  [1]  ").unwrap().value, json!([1]));
+    }
+}
@@ -0,0 +1,460 @@
+//! Sync compared capabilities between projects
+//!
+//! Applies differences selected from a project comparison to one project so
+//! its capabilities match the other's: missing capabilities are added,
+//! different ones replaced and extra ones removed, each in the target's file
+//! for the capability's scope. All files are written as one transaction.
+//! Hooks are found by their definition rather than their position, since
+//! their keys number them per event and matcher. Agents and slash commands
+//! are files of their own and are not synced.
+
+use crate::config::spanned_json::escape_pointer;
+use crate::config::transfer::{self, Location};
+use crate::config::{edit, json_patch};
+use crate::types::app::{AppError, Capability, ConfigScope, DiffResult};
+use crate::types::patch::{PatchOperation, SkippedDifference, SyncDirection, SyncResult};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// A target file, its patched content so far and the operations that produce it
+struct PendingFile {
+    path: PathBuf,
+    document: Value,
+    operations: Vec<PatchOperation>,
+}
+
+/// Queue the operations that bring the target in line with the source side of
+/// `diff`, or return why it is skipped
+fn plan_difference(
+    target_dir: &Path,
+    home: Option<&Path>,
+    diff: &DiffResult,
+    direction: SyncDirection,
+    pending: &mut Vec<PendingFile>,
+) -> Result<(), String> {
+    let (source, target) = match direction {
+        SyncDirection::LeftToRight => (diff.left_value.as_ref(), diff.right_value.as_ref()),
+        SyncDirection::RightToLeft => (diff.right_value.as_ref(), diff.left_value.as_ref()),
+    };
+    let capability: &Capability = source.or(target).ok_or_else(|| "no capability on either side".to_string())?;
+
+    // Derived from the server definition, not stored anywhere
+    if capability.id.starts_with("mcp-env.") || capability.id.starts_with("mcp__") {
+        return Err("derived from an MCP server; sync the server instead".to_string());
+    }
+    let scope = transfer::source_scope(capability).map_err(|e| e.to_string())?;
+    match scope {
+        ConfigScope::User => return Err("user scope is shared by both projects".to_string()),
+        ConfigScope::Managed => return Err("managed settings are read-only".to_string()),
+        ConfigScope::Project | ConfigScope::Local => {}
+    }
+    let is_hook = capability.key.starts_with("hooks.");
+    let location = if is_hook {
+        None
+    } else {
+        Some(transfer::capability_location(capability, target_dir, home).map_err(|e| e.to_string())?)
+    };
+    let path = match &location {
+        Some(location) => location.path.clone(),
+        None => transfer::settings_path(scope, target_dir, home).map_err(|e| e.to_string())?,
+    };
+
+    let index = match pending.iter().position(|file| file.path == path) {
+        Some(index) => index,
+        None => {
+            let document = transfer::read_document(&path).map_err(|e| e.to_string())?;
+            pending.push(PendingFile {
+                path: path.clone(),
+                document,
+                operations: Vec::new(),
+            });
+            pending.len() - 1
+        }
+    };
+    let file = &mut pending[index];
+
+    let changed = || format!("changed in {} since the comparison", path.display());
+    let operations = match location {
+        Some(location) => value_operations(&file.document, location, source, target).ok_or_else(changed)??,
+        None => hook_operations(&file.document, source, target).ok_or_else(changed)??,
+    };
+
+    // Later differences in the same file see this one applied
+    file.document = json_patch::apply(&file.document, &operations).map_err(|e| e.to_string())?;
+    file.operations.extend(operations);
+    Ok(())
+}
+
+/// Operations that set the value at `location` to the source's, or `None` when
+/// the target no longer holds the compared value
+fn value_operations(
+    document: &Value,
+    location: Location,
+    source: Option<&Capability>,
+    target: Option<&Capability>,
+) -> Option<Result<Vec<PatchOperation>, String>> {
+    let pointer = location.pointer();
+    let segments = location.segments;
+
+    let current = transfer::lookup(document, &segments).cloned();
+    if current.as_ref() != target.map(|capability| &capability.value) {
+        return None;
+    }
+
+    let operations = match (source, current) {
+        (Some(source), None) => match transfer::add_operations(document, &segments, source.value.clone(), false) {
+            Some(operations) => operations,
+            None => return Some(Err("already set to a different value".to_string())),
+        },
+        (Some(source), Some(current)) if source.value == current => {
+            return Some(Err("already matches".to_string()));
+        }
+        (Some(source), Some(current)) => vec![
+            PatchOperation::Test {
+                path: pointer.clone(),
+                value: current,
+            },
+            PatchOperation::Replace {
+                path: pointer,
+                value: source.value.clone(),
+            },
+        ],
+        (None, Some(current)) => vec![
+            PatchOperation::Test {
+                path: pointer.clone(),
+                value: current,
+            },
+            PatchOperation::Remove { path: pointer },
+        ],
+        (None, None) => return Some(Err("already matches".to_string())),
+    };
+    Some(Ok(operations))
+}
+
+/// Matcher of a hook group; an empty matcher applies to every tool like a missing one
+fn group_matcher(group: &Value) -> Option<&str> {
+    group.get("matcher").and_then(|m| m.as_str()).filter(|m| !m.is_empty())
+}
+
+/// The settings entry of a hook capability's definition
+fn hook_entry(definition: &Value) -> Value {
+    let entry = ["type", "command", "timeout"]
+        .into_iter()
+        .filter_map(|key| {
+            let value = definition.get(key).filter(|value| !value.is_null())?;
+            Some((key.to_string(), value.clone()))
+        })
+        .collect();
+    Value::Object(entry)
+}
+
+/// Group and hook index of the hook with `definition` under `event`
+fn find_hook(groups: &[Value], event: &str, definition: &Value) -> Option<(usize, usize)> {
+    groups.iter().enumerate().find_map(|(group_index, group)| {
+        let matcher = group_matcher(group);
+        let hooks = group.get("hooks")?.as_array()?;
+        let hook_index = hooks.iter().position(|hook| {
+            let found = serde_json::json!({
+                "event": event,
+                "matcher": matcher,
+                "type": hook.get("type"),
+                "command": hook.get("command"),
+                "timeout": hook.get("timeout"),
+            });
+            found == *definition
+        })?;
+        Some((group_index, hook_index))
+    })
+}
+
+/// Operations that add, replace or remove one hook command, or `None` when
+/// the target no longer has the compared hook
+fn hook_operations(
+    document: &Value,
+    source: Option<&Capability>,
+    target: Option<&Capability>,
+) -> Option<Result<Vec<PatchOperation>, String>> {
+    let definition = &source.or(target)?.value;
+    let Some(event) = definition.get("event").and_then(|e| e.as_str()) else {
+        return Some(Err("hook has no event".to_string()));
+    };
+    let matcher = definition.get("matcher").and_then(|m| m.as_str()).filter(|m| !m.is_empty());
+    let event_pointer = format!("/hooks/{}", escape_pointer(event));
+    let groups = document.pointer(&event_pointer).and_then(|g| g.as_array()).cloned().unwrap_or_default();
+
+    let current = match target {
+        Some(target) => Some(find_hook(&groups, event, &target.value)?),
+        None => None,
+    };
+
+    let operations = match (source, current) {
+        (Some(source), None) => {
+            if find_hook(&groups, event, &source.value).is_some() {
+                return Some(Err("already matches".to_string()));
+            }
+            let entry = hook_entry(&source.value);
+            match groups.iter().rposition(|group| group_matcher(group) == matcher) {
+                Some(group_index) => vec![PatchOperation::Add {
+                    path: format!("{}/{}/hooks/-", event_pointer, group_index),
+                    value: entry,
+                }],
+                None => {
+                    let mut group = serde_json::Map::new();
+                    if let Some(matcher) = matcher {
+                        group.insert("matcher".to_string(), Value::String(matcher.to_string()));
+                    }
+                    group.insert("hooks".to_string(), Value::Array(vec![entry]));
+                    let segments = ["hooks".to_string(), event.to_string()];
+                    transfer::add_operations(document, &segments, Value::Object(group), true)?
+                }
+            }
+        }
+        (Some(source), Some(_)) if target.is_some_and(|target| target.value == source.value) => {
+            return Some(Err("already matches".to_string()));
+        }
+        (Some(source), Some((group_index, hook_index))) => {
+            let path = format!("{}/{}/hooks/{}", event_pointer, group_index, hook_index);
+            vec![
+                PatchOperation::Test {
+                    path: path.clone(),
+                    value: document.pointer(&path)?.clone(),
+                },
+                PatchOperation::Replace {
+                    path,
+                    value: hook_entry(&source.value),
+                },
+            ]
+        }
+        (None, Some((group_index, hook_index))) => {
+            // Drop the whole group with its last hook rather than leave it empty
+            let group_path = format!("{}/{}", event_pointer, group_index);
+            let hook_count = groups[group_index].get("hooks").and_then(|h| h.as_array()).map_or(0, Vec::len);
+            let path = if hook_count == 1 {
+                group_path
+            } else {
+                format!("{}/hooks/{}", group_path, hook_index)
+            };
+            vec![
+                PatchOperation::Test {
+                    path: path.clone(),
+                    value: document.pointer(&path)?.clone(),
+                },
+                PatchOperation::Remove { path },
+            ]
+        }
+        (None, None) => return Some(Err("already matches".to_string())),
+    };
+    Some(Ok(operations))
+}
+
+/// Preview applying `diffs` from a comparison to the target project of
+/// `direction`, or write them when given the `base_hashes` of an earlier preview
+///
+/// Differences that cannot be applied are skipped with a reason; the rest
+/// are written together or not at all.
+pub fn sync(
+    target_dir: &Path,
+    home: Option<&Path>,
+    diffs: &[DiffResult],
+    direction: SyncDirection,
+    base_hashes: Option<&BTreeMap<String, String>>,
+    backup_dir: Option<&Path>,
+) -> Result<SyncResult, AppError> {
+    let mut pending = Vec::new();
+    let mut applied = Vec::new();
+    let mut skipped = Vec::new();
+
+    for diff in diffs {
+        match plan_difference(target_dir, home, diff, direction, &mut pending) {
+            Ok(()) => applied.push(diff.capability_id.clone()),
+            Err(reason) => skipped.push(SkippedDifference {
+                capability_id: diff.capability_id.clone(),
+                reason,
+            }),
+        }
+    }
+
+    let files: Vec<(PathBuf, Vec<PatchOperation>)> = pending
+        .into_iter()
+        .filter(|file| !file.operations.is_empty())
+        .map(|file| (file.path, file.operations))
+        .collect();
+
+    let previews = edit::preview_patches(&files)?;
+    let written = match base_hashes {
+        Some(base_hashes) if !files.is_empty() => edit::commit_patches(&files, base_hashes, backup_dir)?,
+        _ => Vec::new(),
+    };
+
+    Ok(SyncResult {
+        direction,
+        target_path: target_dir.to_string_lossy().to_string(),
+        applied,
+        skipped,
+        base_hashes: edit::base_hashes(&previews),
+        files: previews,
+        written,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::app::{DiffSeverity, DiffStatus};
+    use serde_json::json;
+
+    fn capability(id: &str, key: &str, value: Value, source: &str) -> Capability {
+        Capability {
+            id: id.to_string(),
+            key: key.to_string(),
+            value,
+            source: source.to_string(),
+        }
+    }
+
+    fn diff(left: Option<Capability>, right: Option<Capability>) -> DiffResult {
+        let status = match (&left, &right) {
+            (Some(_), None) => DiffStatus::OnlyLeft,
+            (None, Some(_)) => DiffStatus::OnlyRight,
+            _ => DiffStatus::Different,
+        };
+        DiffResult {
+            capability_id: left.as_ref().or(right.as_ref()).unwrap().id.clone(),
+            left_value: left,
+            right_value: right,
+            status,
+            severity: DiffSeverity::Medium,
+            highlight_class: None,
+        }
+    }
+
+    fn read(path: &Path) -> Value {
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    /// Preview a sync, then write it against the previewed hashes
+    fn commit(
+        target_dir: &Path,
+        home: Option<&Path>,
+        diffs: &[DiffResult],
+        direction: SyncDirection,
+    ) -> Result<SyncResult, AppError> {
+        let preview = sync(target_dir, home, diffs, direction, None, None)?;
+        sync(target_dir, home, diffs, direction, Some(&preview.base_hashes), None)
+    }
+
+    #[test]
+    fn test_sync_settings_left_to_right() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let right = temp_dir.path();
+        let claude_dir = right.join(".claude");
+        std::fs::create_dir_all(&claude_dir).unwrap();
+        std::fs::write(claude_dir.join("settings.json"), r#"{"model": "sonnet", "env": {"DEBUG": "1"}}"#).unwrap();
+
+        let diffs = vec![
+            diff(
+                Some(capability("model", "model", json!("opus"), "project")),
+                Some(capability("model", "model", json!("sonnet"), "project")),
+            ),
+            diff(None, Some(capability("env.DEBUG", "env.DEBUG", json!("1"), "project"))),
+            diff(Some(capability("env.CI", "env.CI", json!("true"), "project")), None),
+            diff(Some(capability("local.model", "model", json!("haiku"), "local")), None),
+        ];
+
+        let preview = sync(right, None, &diffs, SyncDirection::LeftToRight, None, None).unwrap();
+        assert_eq!(preview.applied.len(), 4);
+        assert_eq!(preview.files.len(), 2);
+        assert!(preview.files.iter().all(|file| file.new_errors.is_empty()));
+        assert!(preview.written.is_empty());
+        assert!(!claude_dir.join("settings.local.json").exists());
+
+        let result = commit(right, None, &diffs, SyncDirection::LeftToRight).unwrap();
+        assert_eq!(result.written.len(), 2);
+        assert_eq!(read(&claude_dir.join("settings.json")), json!({"model": "opus", "env": {"CI": "true"}}));
+        assert_eq!(read(&claude_dir.join("settings.local.json")), json!({"model": "haiku"}));
+
+        // The target no longer matches the comparison
+        let again = commit(right, None, &diffs, SyncDirection::LeftToRight).unwrap();
+        assert!(again.applied.is_empty());
+        assert!(again.skipped.iter().all(|skip| skip.reason.contains("since the comparison")));
+    }
+
+    #[test]
+    fn test_sync_mcp_server_right_to_left() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let left = temp_dir.path();
+        let server = json!({"command": "npx", "args": ["-y", "@modelcontextprotocol/server-github"]});
+        std::fs::write(left.join(".mcp.json"), r#"{"mcpServers": {}}"#).unwrap();
+
+        let diffs = vec![diff(
+            None,
+            Some(capability("mcp.github", "mcpServers.github", server.clone(), "project")),
+        )];
+        let result = commit(left, None, &diffs, SyncDirection::RightToLeft).unwrap();
+
+        assert_eq!(result.applied, vec!["mcp.github".to_string()]);
+        assert_eq!(read(&left.join(".mcp.json")), json!({"mcpServers": {"github": server}}));
+    }
+
+    fn hook(matcher: Option<&str>, command: &str) -> Value {
+        json!({"event": "PreToolUse", "matcher": matcher, "type": "command", "command": command, "timeout": null})
+    }
+
+    #[test]
+    fn test_sync_hooks_left_to_right() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let right = temp_dir.path();
+        let claude_dir = right.join(".claude");
+        std::fs::create_dir_all(&claude_dir).unwrap();
+        let settings = json!({"hooks": {"PreToolUse": [
+            {"matcher": "Bash", "hooks": [{"type": "command", "command": "old-check"}, {"type": "command", "command": "audit"}]},
+            {"matcher": "Edit", "hooks": [{"type": "command", "command": "fmt"}]}
+        ]}});
+        std::fs::write(claude_dir.join("settings.json"), settings.to_string()).unwrap();
+
+        let hook_capability = |key: &str, value: Value| capability(key, key, value, "project");
+        let diffs = vec![
+            diff(
+                Some(hook_capability("hooks.PreToolUse.Bash.0", hook(Some("Bash"), "new-check"))),
+                Some(hook_capability("hooks.PreToolUse.Bash.0", hook(Some("Bash"), "old-check"))),
+            ),
+            diff(None, Some(hook_capability("hooks.PreToolUse.Bash.1", hook(Some("Bash"), "audit")))),
+            diff(None, Some(hook_capability("hooks.PreToolUse.Edit.0", hook(Some("Edit"), "fmt")))),
+            diff(Some(hook_capability("hooks.PreToolUse.*.0", hook(None, "log"))), None),
+        ];
+        let result = commit(right, None, &diffs, SyncDirection::LeftToRight).unwrap();
+
+        assert_eq!(result.applied.len(), 4, "{:?}", result.skipped);
+        assert_eq!(
+            read(&claude_dir.join("settings.json")),
+            json!({"hooks": {"PreToolUse": [
+                {"matcher": "Bash", "hooks": [{"type": "command", "command": "new-check"}]},
+                {"hooks": [{"type": "command", "command": "log"}]}
+            ]}})
+        );
+
+        // The replaced hook is no longer what the comparison saw
+        let again = commit(right, None, &diffs[..1], SyncDirection::LeftToRight).unwrap();
+        assert!(again.skipped[0].reason.contains("since the comparison"));
+    }
+
+    #[test]
+    fn test_sync_skips_unsupported_differences() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let right = temp_dir.path();
+
+        let diffs = vec![
+            diff(Some(capability("command.review", "commands.review", json!({}), "project")), None),
+            diff(Some(capability("model", "model", json!("opus"), "user")), None),
+            diff(Some(capability("mcp-env.github", "mcpServers.github.expansion", json!({}), "project")), None),
+            diff(Some(capability("agent.reviewer", "agents.reviewer", json!({}), "project")), None),
+        ];
+        let result = commit(right, None, &diffs, SyncDirection::LeftToRight).unwrap();
+
+        assert!(result.applied.is_empty());
+        assert_eq!(result.skipped.len(), 4);
+        assert!(result.files.is_empty());
+        assert!(std::fs::read_dir(right).unwrap().next().is_none());
+    }
+}
@@ -0,0 +1,209 @@
+//! Line-based unified diffs
+//!
+//! Produces the `diff -u` format used to preview config edits. Common leading
+//! and trailing lines are skipped before the longest common subsequence is
+//! computed, so a small edit to a large file stays cheap.
+
+/// Unchanged lines shown around each change
+const CONTEXT: usize = 3;
+
+/// Largest changed region compared line by line; larger regions are shown as replaced
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Edit<'a> {
+    Equal(&'a str),
+    Delete(&'a str),
+    Insert(&'a str),
+}
+
+/// Edit script turning `old` into `new`
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<Edit<'a>> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_middle = &old[prefix..old.len() - suffix];
+    let new_middle = &new[prefix..new.len() - suffix];
+
+    let mut edits: Vec<Edit> = old[..prefix].iter().map(|line| Edit::Equal(line)).collect();
+
+    if old_middle.len() * new_middle.len() > MAX_LCS_CELLS {
+        edits.extend(old_middle.iter().map(|line| Edit::Delete(line)));
+        edits.extend(new_middle.iter().map(|line| Edit::Insert(line)));
+    } else {
+        // lengths[i][j] is the LCS length of old_middle[i..] and new_middle[j..]
+        let (n, m) = (old_middle.len(), new_middle.len());
+        let mut lengths = vec![vec![0usize; m + 1]; n + 1];
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                lengths[i][j] = if old_middle[i] == new_middle[j] {
+                    lengths[i + 1][j + 1] + 1
+                } else {
+                    lengths[i + 1][j].max(lengths[i][j + 1])
+                };
+            }
+        }
+
+        let (mut i, mut j) = (0, 0);
+        while i < n || j < m {
+            if i < n && j < m && old_middle[i] == new_middle[j] {
+                edits.push(Edit::Equal(old_middle[i]));
+                i += 1;
+                j += 1;
+            } else if i < n && (j == m || lengths[i + 1][j] >= lengths[i][j + 1]) {
+                edits.push(Edit::Delete(old_middle[i]));
+                i += 1;
+            } else {
+                edits.push(Edit::Insert(new_middle[j]));
+                j += 1;
+            }
+        }
+    }
+
+    edits.extend(old[old.len() - suffix..].iter().map(|line| Edit::Equal(line)));
+    edits
+}
+
+/// Hunk range in `start,count` form, omitting a count of one
+fn range(start: usize, count: usize) -> String {
+    match count {
+        0 => format!("{},0", start),
+        1 => format!("{}", start + 1),
+        _ => format!("{},{}", start + 1, count),
+    }
+}
+
+fn push_line(output: &mut String, marker: char, line: &str) {
+    output.push(marker);
+    match line.strip_suffix('\n') {
+        Some(text) => {
+            output.push_str(text.strip_suffix('\r').unwrap_or(text));
+            output.push('\n');
+        }
+        None => {
+            output.push_str(line);
+            output.push_str("\n\\ No newline at end of file\n");
+        }
+    }
+}
+
+/// Unified diff between two texts, or an empty string when they are equal
+pub fn unified_diff(old: &str, new: &str, old_label: &str, new_label: &str) -> String {
+    if old == new {
+        return String::new();
+    }
+
+    let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+    let new_lines: Vec<&str> = new.split_inclusive('\n').collect();
+    let edits = diff_lines(&old_lines, &new_lines);
+
+    // Line positions in the old and new text before each edit
+    let mut positions = Vec::with_capacity(edits.len() + 1);
+    let (mut old_pos, mut new_pos) = (0, 0);
+    for edit in &edits {
+        positions.push((old_pos, new_pos));
+        match edit {
+            Edit::Equal(_) => {
+                old_pos += 1;
+                new_pos += 1;
+            }
+            Edit::Delete(_) => old_pos += 1,
+            Edit::Insert(_) => new_pos += 1,
+        }
+    }
+    positions.push((old_pos, new_pos));
+
+    let mut output = format!("--- {}\n+++ {}\n", old_label, new_label);
+    let is_equal = |index: usize| matches!(edits[index], Edit::Equal(_));
+    let mut index = 0;
+
+    while index < edits.len() {
+        if is_equal(index) {
+            index += 1;
+            continue;
+        }
+
+        // Extend the hunk over changes separated by at most twice the context
+        let start = index.saturating_sub(CONTEXT);
+        let mut end = index;
+        let mut cursor = index;
+        while cursor < edits.len() {
+            if !is_equal(cursor) {
+                cursor += 1;
+                end = cursor;
+                continue;
+            }
+            let run_start = cursor;
+            while cursor < edits.len() && is_equal(cursor) {
+                cursor += 1;
+            }
+            if cursor == edits.len() || cursor - run_start > 2 * CONTEXT {
+                break;
+            }
+        }
+        let stop = (end + CONTEXT).min(edits.len());
+
+        let (old_start, new_start) = positions[start];
+        let (old_stop, new_stop) = positions[stop];
+        output.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            range(old_start, old_stop - old_start),
+            range(new_start, new_stop - new_start)
+        ));
+        for edit in &edits[start..stop] {
+            match edit {
+                Edit::Equal(line) => push_line(&mut output, ' ', line),
+                Edit::Delete(line) => push_line(&mut output, '-', line),
+                Edit::Insert(line) => push_line(&mut output, '+', line),
+            }
+        }
+
+        index = stop;
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unified_diff_single_hunk() {
+        let old = "{\n  \"model\": \"opus\",\n  \"env\": {}\n}\n";
+        let new = "{\n  \"model\": \"sonnet\",\n  \"env\": {}\n}\n";
+
+        assert_eq!(
+            unified_diff(old, new, "settings.json", "settings.json"),
+            "--- settings.json\n+++ settings.json\n@@ -1,4 +1,4 @@\n {\n-  \"model\": \"opus\",\n+  \"model\": \"sonnet\",\n   \"env\": {}\n }\n"
+        );
+        assert_eq!(unified_diff(old, old, "a", "b"), "");
+    }
+
+    #[test]
+    fn test_unified_diff_separate_hunks() {
+        let old: String = (1..=20).map(|n| format!("line {}\n", n)).collect();
+        let new = old.replace("line 2\n", "line two\n").replace("line 18\n", "");
+
+        let diff = unified_diff(&old, &new, "old", "new");
+        let headers: Vec<&str> = diff.lines().filter(|line| line.starts_with("@@")).collect();
+        assert_eq!(headers, vec!["@@ -1,5 +1,5 @@", "@@ -15,6 +15,5 @@"]);
+        assert!(diff.contains("-line 18\n"));
+    }
+
+    #[test]
+    fn test_unified_diff_new_file_and_missing_newline() {
+        assert_eq!(
+            unified_diff("", "{}\n", "/dev/null", "settings.json"),
+            "--- /dev/null\n+++ settings.json\n@@ -0,0 +1 @@\n+{}\n"
+        );
+        assert_eq!(
+            unified_diff("{}", "{}\n", "a", "b"),
+            "--- a\n+++ b\n@@ -1 +1 @@\n-{}\n\\ No newline at end of file\n+{}\n"
+        );
+    }
+}
@@ -0,0 +1,103 @@
+//! Claude Code tool names
+//!
+//! Built-in tools that hook matchers and permission rules can name. MCP tools
+//! are named `mcp__<server>__<tool>` and are not listed here.
+
+/// Tools built into Claude Code
+pub const BUILTIN_TOOLS: &[&str] = &[
+    "Bash",
+    "BashOutput",
+    "Edit",
+    "ExitPlanMode",
+    "Glob",
+    "Grep",
+    "KillShell",
+    "LS",
+    "MultiEdit",
+    "NotebookEdit",
+    "NotebookRead",
+    "Read",
+    "SlashCommand",
+    "Task",
+    "TodoWrite",
+    "WebFetch",
+    "WebSearch",
+    "Write",
+];
+
+/// Tools covered by `Edit(...)` permission rules
+pub const EDIT_TOOLS: &[&str] = &["Edit", "MultiEdit", "NotebookEdit", "Write"];
+
+/// Tools covered by `Read(...)` permission rules
+pub const READ_TOOLS: &[&str] = &["Glob", "Grep", "LS", "NotebookRead", "Read"];
+
+/// Built-in tools that prompt in the default permission mode
+const PERMISSION_TOOLS: &[&str] = &[
+    "Bash",
+    "Edit",
+    "MultiEdit",
+    "NotebookEdit",
+    "SlashCommand",
+    "WebFetch",
+    "WebSearch",
+    "Write",
+];
+
+/// Whether using a tool asks for permission when no rule covers it
+///
+/// MCP tools and unknown tools always do.
+pub fn requires_permission(name: &str) -> bool {
+    PERMISSION_TOOLS.contains(&name) || !BUILTIN_TOOLS.contains(&name)
+}
+
+/// Prefix of tools provided by MCP servers
+pub const MCP_TOOL_PREFIX: &str = "mcp__";
+
+/// Whether a tool name is a built-in tool or an MCP tool
+pub fn is_known_tool(name: &str) -> bool {
+    BUILTIN_TOOLS.contains(&name) || is_mcp_tool(name)
+}
+
+/// Whether a tool name refers to an MCP tool (`mcp__server` or `mcp__server__tool`)
+pub fn is_mcp_tool(name: &str) -> bool {
+    name.strip_prefix(MCP_TOOL_PREFIX).is_some_and(|rest| !rest.is_empty())
+}
+
+/// The name Claude Code gives an MCP server's tool, `mcp__<server>__<tool>`
+///
+/// Characters other than ASCII letters, digits, `_` and `-` become `_`.
+pub fn mcp_tool_name(server: &str, tool: &str) -> String {
+    let sanitize = |name: &str| -> String {
+        name.chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == '-' { c } else { '_' })
+            .collect()
+    };
+    format!("{}{}__{}", MCP_TOOL_PREFIX, sanitize(server), sanitize(tool))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_known_tool() {
+        assert!(is_known_tool("Bash"));
+        assert!(is_known_tool("mcp__github__create_issue"));
+        assert!(!is_known_tool("bash"));
+        assert!(!is_known_tool("mcp__"));
+    }
+
+    #[test]
+    fn test_requires_permission() {
+        assert!(requires_permission("Bash"));
+        assert!(requires_permission("mcp__github__create_issue"));
+        assert!(!requires_permission("Read"));
+        assert!(!requires_permission("TodoWrite"));
+    }
+
+    #[test]
+    fn test_mcp_tool_name() {
+        assert_eq!(mcp_tool_name("github", "create_issue"), "mcp__github__create_issue");
+        assert_eq!(mcp_tool_name("my.server", "run query"), "mcp__my_server__run_query");
+    }
+}
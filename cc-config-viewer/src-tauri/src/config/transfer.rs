@@ -0,0 +1,504 @@
+//! Capability transfers between scopes
+//!
+//! Moves or copies a setting, permission rule or MCP server from the config
+//! file of one scope to another as one transaction. Settings live in each
+//! scope's `settings.json`; MCP servers live in `.mcp.json` (project), the top
+//! level of `~/.claude.json` (user) or the project's entry in `~/.claude.json`
+//! (local). Capabilities with the `claude-json` source come from that entry
+//! and are moved out of it.
+
+use crate::config::spanned_json::escape_pointer;
+use crate::config::{claude_json, edit, reader};
+use crate::types::app::{AppError, Capability, ConfigScope};
+use crate::types::patch::{CapabilityTransfer, PatchOperation, TransferMode, TransferRequest};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Where a capability is stored in one scope
+#[derive(Debug, Clone, PartialEq)]
+pub struct Location {
+    pub path: PathBuf,
+    /// Unescaped JSON pointer segments
+    pub segments: Vec<String>,
+}
+
+impl Location {
+    pub fn pointer(&self) -> String {
+        pointer(&self.segments)
+    }
+}
+
+fn pointer(segments: &[String]) -> String {
+    segments.iter().map(|segment| format!("/{}", escape_pointer(segment))).collect()
+}
+
+/// Scope a capability was extracted from
+///
+/// Entries of the project's `~/.claude.json` entry apply in local scope.
+pub fn source_scope(capability: &Capability) -> Result<ConfigScope, AppError> {
+    if capability.source == claude_json::SOURCE {
+        return Ok(ConfigScope::Local);
+    }
+    serde_json::from_value(Value::String(capability.source.clone()))
+        .map_err(|_| AppError::Parse(format!("Unknown capability source '{}'", capability.source)))
+}
+
+/// Read a JSON config file; a missing file is an empty object
+pub fn read_document(path: &Path) -> Result<Value, AppError> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => serde_json::from_str(&content)
+            .map_err(|e| AppError::Parse(format!("{} is not valid JSON: {}", path.display(), e))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Value::Object(Default::default())),
+        Err(e) => Err(AppError::from(e)),
+    }
+}
+
+/// Node at `segments`, if it exists
+pub fn lookup<'a>(document: &'a Value, segments: &[String]) -> Option<&'a Value> {
+    segments.iter().try_fold(document, |node, segment| match node {
+        Value::Object(map) => map.get(segment),
+        Value::Array(items) => segment.parse::<usize>().ok().and_then(|index| items.get(index)),
+        _ => None,
+    })
+}
+
+/// Settings file of `scope` for the project in `project_dir`
+pub fn settings_path(scope: ConfigScope, project_dir: &Path, home: Option<&Path>) -> Result<PathBuf, AppError> {
+    match scope {
+        ConfigScope::Managed => Ok(reader::managed_settings_path()),
+        ConfigScope::User => home
+            .map(|home| home.join(".claude").join("settings.json"))
+            .ok_or_else(|| AppError::Filesystem("Home directory not found".to_string())),
+        ConfigScope::Project => Ok(project_dir.join(".claude").join("settings.json")),
+        ConfigScope::Local => Ok(project_dir.join(".claude").join("settings.local.json")),
+    }
+}
+
+fn claude_json_path(home: Option<&Path>) -> Result<PathBuf, AppError> {
+    home.map(|home| home.join(".claude.json"))
+        .ok_or_else(|| AppError::Filesystem("Home directory not found".to_string()))
+}
+
+/// The project's entry in `~/.claude.json`, reusing an existing entry whichever
+/// spelling of the path it has
+fn project_entry_location(project_dir: &Path, home: Option<&Path>) -> Result<Location, AppError> {
+    let path = claude_json_path(home)?;
+    let document = read_document(&path)?;
+    let project_key = document
+        .get("projects")
+        .and_then(|projects| projects.as_object())
+        .and_then(|projects| projects.keys().find(|k| claude_json::is_same_project(k, project_dir)).cloned())
+        .unwrap_or_else(|| {
+            project_dir
+                .canonicalize()
+                .unwrap_or_else(|_| project_dir.to_path_buf())
+                .to_string_lossy()
+                .to_string()
+        });
+
+    Ok(Location {
+        path,
+        segments: vec!["projects".to_string(), project_key],
+    })
+}
+
+/// Where `capability` is stored for the project in `project_dir`
+///
+/// Like `locate` in the capability's source scope, except that `claude-json`
+/// capabilities are found in the project's `~/.claude.json` entry.
+pub fn capability_location(capability: &Capability, project_dir: &Path, home: Option<&Path>) -> Result<Location, AppError> {
+    if capability.source != claude_json::SOURCE {
+        return locate(&capability.key, source_scope(capability)?, project_dir, home);
+    }
+
+    let mut location = project_entry_location(project_dir, home)?;
+    match capability.key.split_once('.') {
+        Some(("mcpServers", name)) => location.segments.extend(["mcpServers".to_string(), name.to_string()]),
+        _ => location.segments.push(capability.key.clone()),
+    }
+    Ok(location)
+}
+
+/// Where the capability with `key` is stored in `scope`
+///
+/// Hooks, agents and slash commands are stored per file or per entry and
+/// have no single location, so they cannot be located.
+pub fn locate(key: &str, scope: ConfigScope, project_dir: &Path, home: Option<&Path>) -> Result<Location, AppError> {
+    if let Some(name) = key.strip_prefix("mcpServers.") {
+        let segments = vec!["mcpServers".to_string(), name.to_string()];
+
+        return match scope {
+            ConfigScope::Project => Ok(Location {
+                path: project_dir.join(".mcp.json"),
+                segments,
+            }),
+            ConfigScope::User => Ok(Location {
+                path: claude_json_path(home)?,
+                segments,
+            }),
+            ConfigScope::Local => {
+                let mut location = project_entry_location(project_dir, home)?;
+                location.segments.extend(segments);
+                Ok(location)
+            }
+            ConfigScope::Managed => Err(AppError::Permission(
+                "Managed MCP servers cannot be transferred".to_string(),
+            )),
+        };
+    }
+
+    let segments = match key.split_once('.') {
+        Some((section, _)) if ["hooks", "agents", "commands"].contains(&section) => {
+            return Err(AppError::Parse(format!("'{}' cannot be transferred between scopes", key)));
+        }
+        Some((section, child)) if section == "permissions" || section == "env" => {
+            vec![section.to_string(), child.to_string()]
+        }
+        _ => vec![key.to_string()],
+    };
+
+    Ok(Location {
+        path: settings_path(scope, project_dir, home)?,
+        segments,
+    })
+}
+
+/// Operations adding `value` at `segments`, creating missing parent objects
+///
+/// When `list` is set, the value's items are appended to an existing list
+/// instead, skipping items it already has. Returns `None` when a different
+/// value is already there.
+pub fn add_operations(document: &Value, segments: &[String], value: Value, list: bool) -> Option<Vec<PatchOperation>> {
+    let path = pointer(segments);
+
+    match lookup(document, segments) {
+        None => {
+            let mut operations: Vec<PatchOperation> = (1..segments.len())
+                .filter(|&depth| lookup(document, &segments[..depth]).is_none())
+                .map(|depth| PatchOperation::Add {
+                    path: pointer(&segments[..depth]),
+                    value: Value::Object(Default::default()),
+                })
+                .collect();
+            let value = match value {
+                Value::Array(_) => value,
+                item if list => Value::Array(vec![item]),
+                value => value,
+            };
+            operations.push(PatchOperation::Add { path, value });
+            Some(operations)
+        }
+        Some(Value::Array(existing)) if list => {
+            let items = match value {
+                Value::Array(items) => items,
+                item => vec![item],
+            };
+            let mut added: Vec<Value> = Vec::new();
+            for item in items {
+                if !existing.contains(&item) && !added.contains(&item) {
+                    added.push(item);
+                }
+            }
+            Some(
+                added
+                    .into_iter()
+                    .map(|item| PatchOperation::Add {
+                        path: format!("{}/-", path),
+                        value: item,
+                    })
+                    .collect(),
+            )
+        }
+        Some(existing) if *existing == value => Some(Vec::new()),
+        Some(_) => None,
+    }
+}
+
+/// Patches that carry out a transfer, target file first
+fn plan(
+    project_dir: &Path,
+    home: Option<&Path>,
+    request: &TransferRequest,
+    from: ConfigScope,
+) -> Result<Vec<(PathBuf, Vec<PatchOperation>)>, AppError> {
+    let capability = &request.capability;
+    let key = &capability.key;
+    let to = request.to;
+
+    if from == to {
+        return Err(AppError::Parse(format!("'{}' is already in {} scope", key, to.as_str())));
+    }
+    if to == ConfigScope::Managed || (from == ConfigScope::Managed && request.mode == TransferMode::Move) {
+        return Err(AppError::Permission("Managed settings are read-only".to_string()));
+    }
+    // Derived from the server definition, not stored anywhere
+    if capability.id.starts_with("mcp-env.") || capability.id.starts_with("mcp__") {
+        return Err(AppError::Parse(format!("'{}' cannot be transferred between scopes", key)));
+    }
+
+    let source = capability_location(capability, project_dir, home)?;
+    let target = locate(key, to, project_dir, home)?;
+
+    let source_document = read_document(&source.path)?;
+    let current = lookup(&source_document, &source.segments).ok_or_else(|| {
+        AppError::Filesystem(format!("'{}' is not set in {}", key, source.path.display()))
+    })?;
+    if *current != capability.value {
+        return Err(AppError::Filesystem(format!(
+            "'{}' changed in {} since it was read; refresh and try again",
+            key,
+            source.path.display()
+        )));
+    }
+
+    let (value, removed) = match &request.item {
+        Some(item) => {
+            let index = current
+                .as_array()
+                .and_then(|items| items.iter().position(|value| value == item))
+                .ok_or_else(|| AppError::Parse(format!("{} is not an entry of '{}'", item, key)))?;
+            (item.clone(), format!("{}/{}", source.pointer(), index))
+        }
+        None => (current.clone(), source.pointer()),
+    };
+    let list = request.item.is_some() || current.is_array();
+
+    let target_document = if target.path == source.path {
+        source_document.clone()
+    } else {
+        read_document(&target.path)?
+    };
+    let target_operations = add_operations(&target_document, &target.segments, value, list).ok_or_else(|| {
+        AppError::Filesystem(format!(
+            "'{}' is already set to a different value in {}",
+            key,
+            target.path.display()
+        ))
+    })?;
+
+    let source_operations = match request.mode {
+        TransferMode::Copy => Vec::new(),
+        TransferMode::Move => vec![
+            // Fails the transaction if the source changes before it is written
+            PatchOperation::Test {
+                path: source.pointer(),
+                value: current.clone(),
+            },
+            PatchOperation::Remove { path: removed },
+        ],
+    };
+
+    let files = if target.path == source.path {
+        vec![(target.path, target_operations.into_iter().chain(source_operations).collect())]
+    } else {
+        vec![(target.path, target_operations), (source.path, source_operations)]
+    };
+
+    Ok(files.into_iter().filter(|(_, operations)| !operations.is_empty()).collect())
+}
+
+/// Preview moving or copying a capability to another scope, or write it
+/// when given the `base_hashes` of an earlier preview
+pub fn transfer(
+    project_dir: &Path,
+    home: Option<&Path>,
+    request: &TransferRequest,
+    base_hashes: Option<&BTreeMap<String, String>>,
+    backup_dir: Option<&Path>,
+) -> Result<CapabilityTransfer, AppError> {
+    let from = source_scope(&request.capability)?;
+    let files = plan(project_dir, home, request, from)?;
+
+    let previews = edit::preview_patches(&files)?;
+    let written = match base_hashes {
+        Some(base_hashes) => edit::commit_patches(&files, base_hashes, backup_dir)?,
+        None => Vec::new(),
+    };
+
+    Ok(CapabilityTransfer {
+        key: request.capability.key.clone(),
+        from,
+        to: request.to,
+        mode: request.mode,
+        base_hashes: edit::base_hashes(&previews),
+        files: previews,
+        written,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn request(key: &str, value: Value, source: &str, item: Option<Value>, to: ConfigScope, mode: TransferMode) -> TransferRequest {
+        TransferRequest {
+            capability: Capability {
+                id: key.to_string(),
+                key: key.to_string(),
+                value,
+                source: source.to_string(),
+            },
+            item,
+            to,
+            mode,
+        }
+    }
+
+    fn read(path: &Path) -> Value {
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    /// Preview a transfer, then write it against the previewed hashes
+    fn commit(project_dir: &Path, home: Option<&Path>, request: &TransferRequest) -> Result<CapabilityTransfer, AppError> {
+        let preview = transfer(project_dir, home, request, None, None)?;
+        transfer(project_dir, home, request, Some(&preview.base_hashes), None)
+    }
+
+    #[test]
+    fn test_move_allow_rule_from_local_to_project() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let project = temp_dir.path();
+        let claude_dir = project.join(".claude");
+        std::fs::create_dir_all(&claude_dir).unwrap();
+        std::fs::write(claude_dir.join("settings.local.json"), r#"{"permissions": {"allow": ["Bash(npm test)", "Read"]}}"#).unwrap();
+        std::fs::write(claude_dir.join("settings.json"), r#"{"permissions": {"allow": ["Edit"]}}"#).unwrap();
+
+        let promote = request(
+            "permissions.allow",
+            json!(["Bash(npm test)", "Read"]),
+            "local",
+            Some(json!("Bash(npm test)")),
+            ConfigScope::Project,
+            TransferMode::Move,
+        );
+
+        let preview = transfer(project, None, &promote, None, None).unwrap();
+        assert_eq!(preview.files.len(), 2);
+        assert!(preview.written.is_empty());
+        assert!(preview.files[0].diff.lines().any(|line| line.starts_with('+') && line.contains("Bash(npm test)")));
+
+        // An edit after the preview invalidates it
+        std::fs::write(claude_dir.join("settings.json"), r#"{"permissions": {"allow": ["Edit"]}, "model": "opus"}"#).unwrap();
+        let stale = transfer(project, None, &promote, Some(&preview.base_hashes), None).unwrap_err();
+        assert!(stale.to_string().contains("changed since the patch was previewed"));
+        assert_eq!(read(&claude_dir.join("settings.local.json"))["permissions"]["allow"], json!(["Bash(npm test)", "Read"]));
+
+        let result = commit(project, None, &promote).unwrap();
+        assert_eq!(result.written.len(), 2);
+        assert_eq!(read(&claude_dir.join("settings.json"))["permissions"]["allow"], json!(["Edit", "Bash(npm test)"]));
+        assert_eq!(read(&claude_dir.join("settings.local.json"))["permissions"]["allow"], json!(["Read"]));
+
+        // The capability that was read no longer matches the file
+        let error = commit(project, None, &promote).unwrap_err();
+        assert!(error.to_string().contains("changed"));
+    }
+
+    #[test]
+    fn test_move_mcp_server_from_user_to_project() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let home = temp_dir.path().join("home");
+        let project = temp_dir.path().join("project");
+        std::fs::create_dir_all(&home).unwrap();
+        std::fs::create_dir_all(&project).unwrap();
+        let server = json!({"command": "npx", "args": ["-y", "@modelcontextprotocol/server-github"]});
+        std::fs::write(
+            home.join(".claude.json"),
+            serde_json::to_string_pretty(&json!({"numStartups": 3, "mcpServers": {"github": server}})).unwrap(),
+        )
+        .unwrap();
+
+        // The capability as the comparison extracts it
+        let capability = claude_json::capabilities(&read(&home.join(".claude.json")), &project).remove(0);
+        assert_eq!(capability.source, "user");
+        let move_server = TransferRequest {
+            capability,
+            item: None,
+            to: ConfigScope::Project,
+            mode: TransferMode::Move,
+        };
+        commit(&project, Some(&home), &move_server).unwrap();
+
+        assert_eq!(read(&project.join(".mcp.json")), json!({"mcpServers": {"github": server}}));
+        assert_eq!(read(&home.join(".claude.json")), json!({"numStartups": 3, "mcpServers": {}}));
+    }
+
+    #[test]
+    fn test_move_claude_json_entries_into_project_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let home = temp_dir.path().join("home");
+        let project = temp_dir.path().join("project");
+        std::fs::create_dir_all(&home).unwrap();
+        std::fs::create_dir_all(&project).unwrap();
+        let server = json!({"command": "db-mcp"});
+        let key = project.to_string_lossy().to_string();
+        let entry = json!({"allowedTools": ["Bash(npm test)"], "mcpServers": {"db": server}, "lastCost": 0.5});
+        std::fs::write(
+            home.join(".claude.json"),
+            serde_json::to_string_pretty(&json!({"projects": {&key: entry}})).unwrap(),
+        )
+        .unwrap();
+
+        let capabilities = claude_json::capabilities(&read(&home.join(".claude.json")), &project);
+        for capability in capabilities {
+            assert_eq!(capability.source, "claude-json");
+            let promote = TransferRequest {
+                capability,
+                item: None,
+                to: ConfigScope::Project,
+                mode: TransferMode::Move,
+            };
+            let result = commit(&project, Some(&home), &promote).unwrap();
+            assert_eq!(result.from, ConfigScope::Local);
+        }
+
+        assert_eq!(read(&project.join(".mcp.json")), json!({"mcpServers": {"db": server}}));
+        assert_eq!(read(&project.join(".claude").join("settings.json")), json!({"allowedTools": ["Bash(npm test)"]}));
+        assert_eq!(
+            read(&home.join(".claude.json")),
+            json!({"projects": {&key: {"mcpServers": {}, "lastCost": 0.5}}})
+        );
+    }
+
+    #[test]
+    fn test_copy_mcp_server_to_local_scope_creates_project_entry() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let home = temp_dir.path().join("home");
+        let project = temp_dir.path().join("project");
+        std::fs::create_dir_all(&home).unwrap();
+        std::fs::create_dir_all(&project).unwrap();
+        let server = json!({"type": "http", "url": "https://mcp.example.com"});
+        std::fs::write(project.join(".mcp.json"), serde_json::to_string(&json!({"mcpServers": {"docs": server}})).unwrap()).unwrap();
+
+        let copy = request("mcpServers.docs", server.clone(), "project", None, ConfigScope::Local, TransferMode::Copy);
+        let result = commit(&project, Some(&home), &copy).unwrap();
+        assert_eq!(result.files.len(), 1, "copying leaves the source file alone");
+
+        let claude_json = read(&home.join(".claude.json"));
+        let key = project.canonicalize().unwrap().to_string_lossy().to_string();
+        assert_eq!(claude_json["projects"][&key]["mcpServers"]["docs"], server);
+        assert_eq!(read(&project.join(".mcp.json"))["mcpServers"]["docs"], server);
+    }
+
+    #[test]
+    fn test_transfer_conflicts_and_unsupported_capabilities() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let project = temp_dir.path();
+        let claude_dir = project.join(".claude");
+        std::fs::create_dir_all(&claude_dir).unwrap();
+        std::fs::write(claude_dir.join("settings.local.json"), r#"{"model": "opus"}"#).unwrap();
+        std::fs::write(claude_dir.join("settings.json"), r#"{"model": "sonnet"}"#).unwrap();
+
+        let conflict = request("model", json!("opus"), "local", None, ConfigScope::Project, TransferMode::Move);
+        let error = commit(project, None, &conflict).unwrap_err();
+        assert!(error.to_string().contains("already set to a different value"));
+        assert_eq!(read(&claude_dir.join("settings.local.json")), json!({"model": "opus"}));
+
+        let to_managed = request("model", json!("opus"), "local", None, ConfigScope::Managed, TransferMode::Copy);
+        assert!(matches!(commit(project, None, &to_managed), Err(AppError::Permission(_))));
+
+        let hook = request("hooks.PreToolUse.Bash.0", json!({}), "local", None, ConfigScope::Project, TransferMode::Copy);
+        assert!(commit(project, None, &hook).unwrap_err().to_string().contains("cannot be transferred"));
+    }
+}
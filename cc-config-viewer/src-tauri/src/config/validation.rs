@@ -0,0 +1,692 @@
+//! Configuration file validation
+//!
+//! Checks Claude Code config files against built-in schemas and reports
+//! diagnostics with a JSON pointer, position, severity and error code:
+//!
+//! * `PR001` - JSON syntax error
+//! * `PR002` - value has the wrong type
+//! * `PR003` - unknown key
+//! * `PR004` - invalid value
+
+use crate::config::{agents, hooks};
+use crate::config::spanned_json::{self, escape_pointer, SyntaxError};
+use crate::types::error::error_codes;
+use crate::types::settings::ClaudeSettings;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+
+/// Severity of a validation diagnostic
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum DiagnosticSeverity {
+    #[serde(rename = "error")]
+    Error,
+    #[serde(rename = "warning")]
+    Warning,
+    #[serde(rename = "info")]
+    Info,
+}
+
+/// A single validation finding
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Diagnostic {
+    pub pointer: String,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    pub severity: DiagnosticSeverity,
+    pub code: String,
+    pub message: String,
+}
+
+/// Kind of Claude Code config file, which selects the schema
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum ConfigFileKind {
+    /// `settings.json`, `settings.local.json` or `managed-settings.json`
+    #[serde(rename = "settings")]
+    Settings,
+    /// Project `.mcp.json`
+    #[serde(rename = "mcp")]
+    Mcp,
+    /// User `~/.claude.json`
+    #[serde(rename = "claude-json")]
+    ClaudeJson,
+    /// Sub-agent definition in an `agents` directory
+    #[serde(rename = "agent")]
+    Agent,
+}
+
+impl ConfigFileKind {
+    /// Detect the kind of config file from its name
+    pub fn from_path(path: &Path) -> Option<Self> {
+        if agents::is_agent_file(path) {
+            return Some(ConfigFileKind::Agent);
+        }
+
+        match path.file_name().and_then(|n| n.to_str())? {
+            "settings.json" | "settings.local.json" | "managed-settings.json" => {
+                Some(ConfigFileKind::Settings)
+            }
+            ".mcp.json" => Some(ConfigFileKind::Mcp),
+            ".claude.json" => Some(ConfigFileKind::ClaudeJson),
+            _ => None,
+        }
+    }
+
+    fn schema(&self) -> &'static Schema {
+        match self {
+            ConfigFileKind::Settings => &SETTINGS,
+            ConfigFileKind::Mcp => &MCP_JSON,
+            ConfigFileKind::ClaudeJson => &CLAUDE_JSON,
+            // Agents are Markdown and checked by `agents::validate_agent`
+            ConfigFileKind::Agent => &ANY,
+        }
+    }
+}
+
+/// Validation result for one file
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FileValidation {
+    pub path: String,
+    pub kind: ConfigFileKind,
+    pub valid: bool,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// How an object treats keys its schema does not list
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum UnknownKeys {
+    /// Report unknown keys (as errors when they look like a typo)
+    Report,
+    /// Accept any extra key
+    Allow,
+}
+
+/// Minimal schema description for Claude Code config files
+#[derive(Debug)]
+enum Schema {
+    Any,
+    String,
+    Bool,
+    Integer,
+    Enum(&'static [&'static str]),
+    Array(&'static Schema),
+    Object(&'static [(&'static str, &'static Schema)], UnknownKeys),
+    /// Free-form keys, optionally restricted to a known set, with one value schema
+    Map(&'static Schema, Option<&'static [&'static str]>),
+}
+
+static ANY: Schema = Schema::Any;
+static STRING: Schema = Schema::String;
+static BOOL: Schema = Schema::Bool;
+static INTEGER: Schema = Schema::Integer;
+static STRING_ARRAY: Schema = Schema::Array(&STRING);
+static STRING_MAP: Schema = Schema::Map(&STRING, None);
+
+/// Permission modes understood by `permissions::evaluate`
+pub(crate) const PERMISSION_MODES: &[&str] =
+    &["default", "acceptEdits", "plan", "bypassPermissions", "dontAsk"];
+
+static PERMISSION_MODE: Schema = Schema::Enum(PERMISSION_MODES);
+
+static PERMISSIONS: Schema = Schema::Object(
+    &[
+        ("allow", &STRING_ARRAY),
+        ("ask", &STRING_ARRAY),
+        ("deny", &STRING_ARRAY),
+        ("additionalDirectories", &STRING_ARRAY),
+        ("defaultMode", &PERMISSION_MODE),
+        ("disableBypassPermissionsMode", &Schema::Enum(&["disable"])),
+    ],
+    UnknownKeys::Report,
+);
+
+static HOOK_COMMAND: Schema = Schema::Object(
+    &[
+        ("type", &Schema::Enum(&["command"])),
+        ("command", &STRING),
+        ("timeout", &INTEGER),
+    ],
+    UnknownKeys::Report,
+);
+
+static HOOK_MATCHER: Schema = Schema::Object(
+    &[("matcher", &STRING), ("hooks", &Schema::Array(&HOOK_COMMAND))],
+    UnknownKeys::Report,
+);
+
+/// Hook events Claude Code emits
+pub const HOOK_EVENTS: &[&str] = &[
+    "PreToolUse",
+    "PostToolUse",
+    "Notification",
+    "UserPromptSubmit",
+    "Stop",
+    "SubagentStop",
+    "PreCompact",
+    "SessionStart",
+    "SessionEnd",
+];
+
+static HOOKS: Schema = Schema::Map(&Schema::Array(&HOOK_MATCHER), Some(HOOK_EVENTS));
+
+static STATUS_LINE: Schema = Schema::Object(
+    &[
+        ("type", &Schema::Enum(&["command"])),
+        ("command", &STRING),
+        ("padding", &INTEGER),
+    ],
+    UnknownKeys::Report,
+);
+
+static SETTINGS: Schema = Schema::Object(
+    &[
+        ("$schema", &STRING),
+        ("apiKeyHelper", &STRING),
+        ("cleanupPeriodDays", &INTEGER),
+        ("companyAnnouncements", &STRING_ARRAY),
+        ("env", &STRING_MAP),
+        ("includeCoAuthoredBy", &BOOL),
+        ("permissions", &PERMISSIONS),
+        ("hooks", &HOOKS),
+        ("disableAllHooks", &BOOL),
+        ("model", &STRING),
+        ("statusLine", &STATUS_LINE),
+        ("outputStyle", &STRING),
+        ("forceLoginMethod", &Schema::Enum(&["claudeai", "console"])),
+        ("forceLoginOrgUUID", &STRING),
+        ("enableAllProjectMcpServers", &BOOL),
+        ("enabledMcpjsonServers", &STRING_ARRAY),
+        ("disabledMcpjsonServers", &STRING_ARRAY),
+        ("awsAuthRefresh", &STRING),
+        ("awsCredentialExport", &STRING),
+        ("spinnerTipsEnabled", &BOOL),
+        ("alwaysThinkingEnabled", &BOOL),
+        ("allowedTools", &STRING_ARRAY),
+        ("disallowedTools", &STRING_ARRAY),
+    ],
+    UnknownKeys::Report,
+);
+
+static MCP_SERVER: Schema = Schema::Object(
+    &[
+        ("type", &Schema::Enum(&["stdio", "sse", "http"])),
+        ("command", &STRING),
+        ("args", &STRING_ARRAY),
+        ("env", &STRING_MAP),
+        ("url", &STRING),
+        ("headers", &STRING_MAP),
+    ],
+    UnknownKeys::Report,
+);
+
+static MCP_SERVERS: Schema = Schema::Map(&MCP_SERVER, None);
+
+static MCP_JSON: Schema = Schema::Object(&[("mcpServers", &MCP_SERVERS)], UnknownKeys::Report);
+
+static CLAUDE_JSON_PROJECT: Schema = Schema::Object(
+    &[
+        ("allowedTools", &STRING_ARRAY),
+        ("mcpServers", &MCP_SERVERS),
+        ("enabledMcpjsonServers", &STRING_ARRAY),
+        ("disabledMcpjsonServers", &STRING_ARRAY),
+        ("mcpContextUris", &STRING_ARRAY),
+        ("hasTrustDialogAccepted", &BOOL),
+        ("hasCompletedProjectOnboarding", &BOOL),
+        ("projectOnboardingSeenCount", &INTEGER),
+        ("ignorePatterns", &STRING_ARRAY),
+        ("history", &Schema::Array(&ANY)),
+    ],
+    UnknownKeys::Allow,
+);
+
+static CLAUDE_JSON: Schema = Schema::Object(
+    &[
+        ("mcpServers", &MCP_SERVERS),
+        ("projects", &Schema::Map(&CLAUDE_JSON_PROJECT, None)),
+    ],
+    UnknownKeys::Allow,
+);
+
+/// Validate config file content against the schema for its kind
+pub fn validate_content(content: &str, kind: ConfigFileKind) -> Vec<Diagnostic> {
+    if kind == ConfigFileKind::Agent {
+        return agents::validate_agent(content);
+    }
+
+    // Recover what we can so one stray comma does not hide every other problem
+    let parsed = spanned_json::parse_tolerant(content);
+    let syntax_errors = syntax_diagnostics(&parsed.issues);
+
+    let document = match parsed.document {
+        Some(document) => document,
+        None => return syntax_errors,
+    };
+
+    let value = &document.value;
+    let mut diagnostics = Vec::new();
+    check_value(value, kind.schema(), "", &mut diagnostics);
+
+    match kind {
+        ConfigFileKind::Mcp => check_mcp_servers(value.get("mcpServers"), "/mcpServers", &mut diagnostics),
+        ConfigFileKind::ClaudeJson => {
+            check_mcp_servers(value.get("mcpServers"), "/mcpServers", &mut diagnostics);
+            if let Some(projects) = value.get("projects").and_then(|p| p.as_object()) {
+                for (project, entry) in projects {
+                    let pointer = format!("/projects/{}/mcpServers", escape_pointer(project));
+                    check_mcp_servers(entry.get("mcpServers"), &pointer, &mut diagnostics);
+                }
+            }
+        }
+        ConfigFileKind::Agent => {}
+        ConfigFileKind::Settings => {
+            // Schema errors already explain failures; otherwise the typed model must accept the file
+            let has_errors = diagnostics.iter().any(|d| d.severity == DiagnosticSeverity::Error);
+            if !has_errors {
+                if let Err(e) = ClaudeSettings::from_value(value.clone()) {
+                    diagnostics.push(diagnostic(
+                        "",
+                        DiagnosticSeverity::Error,
+                        error_codes::PR002,
+                        e.to_string(),
+                    ));
+                }
+            }
+            diagnostics.extend(hooks::validate_hooks(value));
+        }
+    }
+
+    for diagnostic in diagnostics.iter_mut() {
+        if let Some((line, column)) = document.location(&diagnostic.pointer) {
+            diagnostic.line = Some(line);
+            diagnostic.column = Some(column);
+        }
+    }
+
+    syntax_errors.into_iter().chain(diagnostics).collect()
+}
+
+/// Validate a config file on disk, detecting its kind from the file name
+pub fn validate_file(path: &Path, content: &str) -> Option<FileValidation> {
+    let kind = ConfigFileKind::from_path(path)?;
+    let diagnostics = validate_content(content, kind);
+
+    Some(FileValidation {
+        path: path.to_string_lossy().to_string(),
+        kind,
+        valid: !diagnostics.iter().any(|d| d.severity == DiagnosticSeverity::Error),
+        diagnostics,
+    })
+}
+
+/// Validate every project-level config file and agent that exists
+pub fn validate_project(project_dir: &Path) -> Vec<FileValidation> {
+    let mut candidates = vec![
+        project_dir.join(".mcp.json"),
+        project_dir.join(".claude").join("settings.json"),
+        project_dir.join(".claude").join("settings.local.json"),
+    ];
+    candidates.extend(agents::agent_files(&agents::project_agents_dir(project_dir)));
+
+    candidates
+        .iter()
+        .filter(|path| path.is_file())
+        .filter_map(|path| match std::fs::read_to_string(path) {
+            Ok(content) => validate_file(path, &content),
+            Err(e) => {
+                eprintln!("Warning: Failed to read {}: {}", path.display(), e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// A `PR001` diagnostic for each syntax problem the tolerant parser recovered from
+pub fn syntax_diagnostics(issues: &[SyntaxError]) -> Vec<Diagnostic> {
+    issues
+        .iter()
+        .map(|issue| Diagnostic {
+            pointer: String::new(),
+            line: Some(issue.line),
+            column: Some(issue.column),
+            severity: DiagnosticSeverity::Error,
+            code: error_codes::PR001.to_string(),
+            message: format!("Invalid JSON: {}", issue.message),
+        })
+        .collect()
+}
+
+pub(crate) fn diagnostic(
+    pointer: &str,
+    severity: DiagnosticSeverity,
+    code: &str,
+    message: String,
+) -> Diagnostic {
+    Diagnostic {
+        pointer: pointer.to_string(),
+        line: None,
+        column: None,
+        severity,
+        code: code.to_string(),
+        message,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn type_mismatch(pointer: &str, expected: &str, value: &Value, diagnostics: &mut Vec<Diagnostic>) {
+    diagnostics.push(diagnostic(
+        pointer,
+        DiagnosticSeverity::Error,
+        error_codes::PR002,
+        format!("Expected {} but found {}", expected, type_name(value)),
+    ));
+}
+
+/// Recursively check a value against a schema
+fn check_value(value: &Value, schema: &Schema, pointer: &str, diagnostics: &mut Vec<Diagnostic>) {
+    match schema {
+        Schema::Any => {}
+        Schema::String => {
+            if !value.is_string() {
+                type_mismatch(pointer, "string", value, diagnostics);
+            }
+        }
+        Schema::Bool => {
+            if !value.is_boolean() {
+                type_mismatch(pointer, "boolean", value, diagnostics);
+            }
+        }
+        Schema::Integer => {
+            if !value.is_i64() && !value.is_u64() {
+                type_mismatch(pointer, "integer", value, diagnostics);
+            }
+        }
+        Schema::Enum(allowed) => match value.as_str() {
+            Some(text) if allowed.contains(&text) => {}
+            Some(text) => diagnostics.push(diagnostic(
+                pointer,
+                DiagnosticSeverity::Error,
+                error_codes::PR004,
+                format!("Invalid value '{}', expected one of: {}", text, allowed.join(", ")),
+            )),
+            None => type_mismatch(pointer, "string", value, diagnostics),
+        },
+        Schema::Array(items) => match value.as_array() {
+            Some(array) => {
+                for (index, item) in array.iter().enumerate() {
+                    check_value(item, items, &format!("{}/{}", pointer, index), diagnostics);
+                }
+            }
+            None => type_mismatch(pointer, "array", value, diagnostics),
+        },
+        Schema::Object(fields, unknown_keys) => {
+            let object = match value.as_object() {
+                Some(object) => object,
+                None => return type_mismatch(pointer, "object", value, diagnostics),
+            };
+
+            for (key, child) in object {
+                let child_pointer = format!("{}/{}", pointer, escape_pointer(key));
+                match fields.iter().find(|(name, _)| name == key) {
+                    Some((_, field_schema)) => check_value(child, field_schema, &child_pointer, diagnostics),
+                    None if *unknown_keys == UnknownKeys::Report => {
+                        let known: Vec<&str> = fields.iter().map(|(name, _)| *name).collect();
+                        diagnostics.push(unknown_key(key, &known, &child_pointer));
+                    }
+                    None => {}
+                }
+            }
+        }
+        Schema::Map(values, known_keys) => {
+            let object = match value.as_object() {
+                Some(object) => object,
+                None => return type_mismatch(pointer, "object", value, diagnostics),
+            };
+
+            for (key, child) in object {
+                let child_pointer = format!("{}/{}", pointer, escape_pointer(key));
+                if let Some(known) = known_keys {
+                    if !known.contains(&key.as_str()) {
+                        diagnostics.push(unknown_key(key, known, &child_pointer));
+                        continue;
+                    }
+                }
+                check_value(child, values, &child_pointer, diagnostics);
+            }
+        }
+    }
+}
+
+/// Report an unknown key, as an error when it looks like a typo of a known key
+pub(crate) fn unknown_key(key: &str, known: &[&str], pointer: &str) -> Diagnostic {
+    let suggestion = known
+        .iter()
+        .map(|candidate| (candidate, edit_distance(&key.to_lowercase(), &candidate.to_lowercase())))
+        .filter(|(_, distance)| *distance <= 2)
+        .min_by_key(|(_, distance)| *distance);
+
+    match suggestion {
+        Some((candidate, _)) => diagnostic(
+            pointer,
+            DiagnosticSeverity::Error,
+            error_codes::PR003,
+            format!("Unknown key '{}' (did you mean '{}'?)", key, candidate),
+        ),
+        None => diagnostic(
+            pointer,
+            DiagnosticSeverity::Warning,
+            error_codes::PR003,
+            format!("Unknown key '{}'", key),
+        ),
+    }
+}
+
+/// Check transport-specific requirements of MCP server entries
+fn check_mcp_servers(servers: Option<&Value>, pointer: &str, diagnostics: &mut Vec<Diagnostic>) {
+    let servers = match servers.and_then(|s| s.as_object()) {
+        Some(servers) => servers,
+        None => return,
+    };
+
+    for (name, server) in servers {
+        let server_pointer = format!("{}/{}", pointer, escape_pointer(name));
+        let transport = server.get("type").and_then(|t| t.as_str()).unwrap_or("stdio");
+        let required = if transport == "stdio" { "command" } else { "url" };
+
+        if server.is_object() && server.get(required).is_none() {
+            diagnostics.push(diagnostic(
+                &server_pointer,
+                DiagnosticSeverity::Error,
+                error_codes::PR004,
+                format!("MCP server '{}' ({}) is missing '{}'", name, transport, required),
+            ));
+        }
+    }
+}
+
+/// Levenshtein distance between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b_chars: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b_chars.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b_chars.iter().enumerate() {
+            let cost = if a_char == *b_char { 0 } else { 1 };
+            current.push((previous[j] + cost).min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b_chars.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_settings_has_no_diagnostics() {
+        let content = r#"{
+  "model": "sonnet",
+  "permissions": {"allow": ["Read"], "defaultMode": "plan"},
+  "env": {"A": "1"}
+}"#;
+        assert!(validate_content(content, ConfigFileKind::Settings).is_empty());
+    }
+
+    #[test]
+    fn test_syntax_error_reports_pr001_with_position() {
+        let diagnostics = validate_content("{\n  \"model\": \n}", ConfigFileKind::Settings);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "PR001");
+        assert_eq!(diagnostics[0].line, Some(3));
+    }
+
+    #[test]
+    fn test_recovered_syntax_errors_still_get_schema_checks() {
+        let content = "{\n  // team defaults\n  \"modle\": \"opus\",\n}";
+        let diagnostics = validate_content(content, ConfigFileKind::Settings);
+
+        let codes: Vec<&str> = diagnostics.iter().map(|d| d.code.as_str()).collect();
+        assert_eq!(codes, vec!["PR001", "PR001", "PR003"]);
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (Some(2), Some(3)));
+        assert_eq!((diagnostics[1].line, diagnostics[1].column), (Some(3), Some(18)));
+        assert_eq!(diagnostics[2].pointer, "/modle");
+    }
+
+    #[test]
+    fn test_typo_reports_pr003_error_with_suggestion() {
+        let content = "{\n  \"permisions\": {\"allow\": []}\n}";
+        let diagnostics = validate_content(content, ConfigFileKind::Settings);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "PR003");
+        assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Error);
+        assert_eq!(diagnostics[0].pointer, "/permisions");
+        assert!(diagnostics[0].message.contains("did you mean 'permissions'"));
+        assert_eq!(diagnostics[0].line, Some(2));
+        assert_eq!(diagnostics[0].column, Some(3));
+    }
+
+    #[test]
+    fn test_diagnostic_points_at_repeated_string() {
+        let content = "{\n  \"permissions\": {\n    \"allow\": [\"Bash\"],\n    \"deny\": [\"Read\", 1]\n  }\n}";
+        let diagnostics = validate_content(content, ConfigFileKind::Settings);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].pointer, "/permissions/deny/1");
+        assert_eq!(diagnostics[0].line, Some(4));
+        assert_eq!(diagnostics[0].column, Some(22));
+    }
+
+    #[test]
+    fn test_unrelated_unknown_key_is_warning() {
+        let diagnostics = validate_content(r#"{"somethingElse": 1}"#, ConfigFileKind::Settings);
+        assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Warning);
+    }
+
+    #[test]
+    fn test_string_instead_of_array_reports_pr002() {
+        let content = r#"{"permissions": {"allow": "Bash"}}"#;
+        let diagnostics = validate_content(content, ConfigFileKind::Settings);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "PR002");
+        assert_eq!(diagnostics[0].pointer, "/permissions/allow");
+    }
+
+    #[test]
+    fn test_invalid_enum_reports_pr004() {
+        let content = r#"{"permissions": {"defaultMode": "yolo"}}"#;
+        let diagnostics = validate_content(content, ConfigFileKind::Settings);
+        assert_eq!(diagnostics[0].code, "PR004");
+    }
+
+    #[test]
+    fn test_every_evaluated_permission_mode_is_valid() {
+        for mode in PERMISSION_MODES {
+            let content = format!(r#"{{"permissions": {{"defaultMode": "{}"}}}}"#, mode);
+            assert!(validate_content(&content, ConfigFileKind::Settings).is_empty(), "{}", mode);
+        }
+    }
+
+    #[test]
+    fn test_unknown_hook_event() {
+        let content = r#"{"hooks": {"PreToolUs": []}}"#;
+        let diagnostics = validate_content(content, ConfigFileKind::Settings);
+        assert_eq!(diagnostics[0].code, "PR003");
+        assert!(diagnostics[0].message.contains("PreToolUse"));
+    }
+
+    #[test]
+    fn test_invalid_hook_matcher_is_located() {
+        let content = "{\n  \"hooks\": {\n    \"PreToolUse\": [\n      {\"matcher\": \"Bash|(Edit\", \"hooks\": []}\n    ]\n  }\n}";
+        let diagnostics = validate_content(content, ConfigFileKind::Settings);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].pointer, "/hooks/PreToolUse/0/matcher");
+        assert_eq!(diagnostics[0].line, Some(4));
+    }
+
+    #[test]
+    fn test_mcp_server_missing_command() {
+        let content = r#"{"mcpServers": {"github": {"args": ["x"]}, "remote": {"type": "http", "url": "https://x"}}}"#;
+        let diagnostics = validate_content(content, ConfigFileKind::Mcp);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "PR004");
+        assert_eq!(diagnostics[0].pointer, "/mcpServers/github");
+    }
+
+    #[test]
+    fn test_claude_json_allows_unknown_top_level_keys() {
+        let content = r#"{"numStartups": 3, "projects": {"/a/b": {"allowedTools": "x", "custom": 1}}}"#;
+        let diagnostics = validate_content(content, ConfigFileKind::ClaudeJson);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].pointer, "/projects/~1a~1b/allowedTools");
+    }
+
+    #[test]
+    fn test_config_file_kind_from_path() {
+        assert_eq!(
+            ConfigFileKind::from_path(Path::new("/p/.claude/settings.local.json")),
+            Some(ConfigFileKind::Settings)
+        );
+        assert_eq!(ConfigFileKind::from_path(Path::new("/p/.mcp.json")), Some(ConfigFileKind::Mcp));
+        assert_eq!(ConfigFileKind::from_path(Path::new("/p/readme.md")), None);
+        assert_eq!(
+            ConfigFileKind::from_path(Path::new("/p/.claude/agents/reviewer.md")),
+            Some(ConfigFileKind::Agent)
+        );
+    }
+
+    #[test]
+    fn test_validate_project() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let claude_dir = temp_dir.path().join(".claude");
+        std::fs::create_dir_all(&claude_dir).unwrap();
+        std::fs::write(claude_dir.join("settings.json"), r#"{"modle": "opus"}"#).unwrap();
+        std::fs::write(temp_dir.path().join(".mcp.json"), r#"{"mcpServers": {}}"#).unwrap();
+
+        let agents_dir = claude_dir.join("agents");
+        std::fs::create_dir_all(&agents_dir).unwrap();
+        std::fs::write(agents_dir.join("helper.md"), "no frontmatter").unwrap();
+
+        let results = validate_project(temp_dir.path());
+        assert_eq!(results.len(), 3);
+        assert!(!results.iter().find(|r| r.kind == ConfigFileKind::Agent).unwrap().valid);
+        assert!(results.iter().find(|r| r.kind == ConfigFileKind::Mcp).unwrap().valid);
+        let settings = results.iter().find(|r| r.kind == ConfigFileKind::Settings).unwrap();
+        assert!(!settings.valid);
+        assert_eq!(settings.diagnostics.len(), 1);
+        assert_eq!(settings.diagnostics[0].severity, DiagnosticSeverity::Error);
+    }
+}
@@ -1,9 +1,10 @@
 use notify::RecursiveMode;
 use notify_debouncer_mini::{new_debouncer, DebounceEventResult, DebouncedEvent};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 use tauri::{AppHandle, Emitter, Manager};
 
+use crate::config::{history, slash_commands};
 use crate::types::app::AppError;
 
 /// Config file change event payload sent to frontend
@@ -19,13 +20,57 @@ pub struct WatcherState {
     pub _debouncer: notify_debouncer_mini::Debouncer<notify::RecommendedWatcher>,
 }
 
+/// Config files and directories whose changes are reported
+#[derive(Debug, Clone, Default)]
+struct WatchTargets {
+    /// Single config files, watched through their directories
+    files: Vec<PathBuf>,
+    /// Agent and command directories, watched recursively
+    dirs: Vec<PathBuf>,
+}
+
+impl WatchTargets {
+    /// Targets in the user's home directory and the current project
+    fn new(home_dir: Option<&Path>, current_dir: Option<&Path>) -> Self {
+        let mut targets = Self::default();
+
+        if let Some(home_dir) = home_dir {
+            targets.files.push(home_dir.join(".claude.json"));
+            targets.files.push(home_dir.join(".claude").join("settings.json"));
+            // Only agents and commands, not the entire ~/.claude, for performance
+            targets.dirs.push(home_dir.join(".claude").join("agents"));
+            // Subdirectories of commands are namespaces
+            targets.dirs.push(home_dir.join(".claude").join("commands"));
+        }
+
+        if let Some(current_dir) = current_dir {
+            targets.files.push(current_dir.join(".mcp.json"));
+            targets.files.push(current_dir.join(".claude").join("settings.json"));
+            targets.files.push(current_dir.join(".claude").join("settings.local.json"));
+            targets.dirs.push(current_dir.join(".claude").join("agents"));
+            targets.dirs.push(slash_commands::project_commands_dir(current_dir));
+        }
+
+        targets.dirs.retain(|dir| dir.exists());
+        targets
+    }
+
+    /// Whether a changed path is a target file or lies in a target directory
+    fn contains(&self, path: &Path) -> bool {
+        self.files.iter().any(|file| file == path) || self.dirs.iter().any(|dir| path.starts_with(dir))
+    }
+}
+
 /// Initialize file system watcher for configuration files
 /// Watches user-level and project-level config files with 300ms debouncing
 pub fn watch_config_files(app: AppHandle) -> Result<(), AppError> {
     let debounce_duration = Duration::from_millis(300);
+    let current_dir = std::env::current_dir().ok();
+    let targets = WatchTargets::new(dirs::home_dir().as_deref(), current_dir.as_deref());
 
-    // Clone app handle for the callback
+    // Clone app handle and targets for the callback
     let app_clone = app.clone();
+    let filter = targets.clone();
 
     // Create debounced watcher with callback
     let mut debouncer = new_debouncer(
@@ -33,7 +78,8 @@ pub fn watch_config_files(app: AppHandle) -> Result<(), AppError> {
         move |result: DebounceEventResult| {
             match result {
                 Ok(events) => {
-                    for event in events {
+                    // Drop changes to unrelated files in the watched directories before anything else
+                    for event in events.into_iter().filter(|event| filter.contains(&event.path)) {
                         handle_file_event(&app_clone, event);
                     }
                 }
@@ -47,65 +93,26 @@ pub fn watch_config_files(app: AppHandle) -> Result<(), AppError> {
 
     let watcher = debouncer.watcher();
 
-    // Watch user home directory for .claude.json and .claude/ subdirectories
-    if let Some(home_dir) = dirs::home_dir() {
-        // Watch ~/.claude.json specifically (not entire home dir)
-        let claude_config = home_dir.join(".claude.json");
-        if claude_config.exists() {
-            watcher
-                .watch(&claude_config, RecursiveMode::NonRecursive)
-                .map_err(|e| {
-                    AppError::Filesystem(format!("Failed to watch {}: {}", claude_config.display(), e))
-                })?;
-            println!("Watching: {}", claude_config.display());
-        }
-
-        // Watch ~/.claude/settings.json specifically
-        let settings_file = home_dir.join(".claude").join("settings.json");
-        if settings_file.exists() {
-            watcher
-                .watch(&settings_file, RecursiveMode::NonRecursive)
-                .map_err(|e| {
-                    AppError::Filesystem(format!("Failed to watch {}: {}", settings_file.display(), e))
-                })?;
-            println!("Watching: {}", settings_file.display());
-        }
-
-        // Watch ~/.claude/agents/ directory (performance: only agents, not entire .claude)
-        let agents_dir = home_dir.join(".claude").join("agents");
-        if agents_dir.exists() {
-            watcher
-                .watch(&agents_dir, RecursiveMode::Recursive)
-                .map_err(|e| {
-                    AppError::Filesystem(format!("Failed to watch {}: {}", agents_dir.display(), e))
-                })?;
-            println!("Watching: {}", agents_dir.display());
-        }
+    for dir in &targets.dirs {
+        watcher
+            .watch(dir, RecursiveMode::Recursive)
+            .map_err(|e| AppError::Filesystem(format!("Failed to watch {}: {}", dir.display(), e)))?;
+        println!("Watching: {}", dir.display());
     }
 
-    // Watch current project directory for .mcp.json and .claude/ files
-    if let Ok(current_dir) = std::env::current_dir() {
-        // Watch project .mcp.json
-        let mcp_config = current_dir.join(".mcp.json");
-        if mcp_config.exists() {
-            watcher
-                .watch(&mcp_config, RecursiveMode::NonRecursive)
-                .map_err(|e| {
-                    AppError::Filesystem(format!("Failed to watch {}: {}", mcp_config.display(), e))
-                })?;
-            println!("Watching: {}", mcp_config.display());
-        }
+    // Saving a file by renaming a new one over it ends a watch on the file itself,
+    // so watch the directories. ~/.claude.json sits directly in the home directory,
+    // which is why home is watched; the callback ignores every other file in it.
+    for dir in watched_dirs(&targets.files) {
+        watcher
+            .watch(&dir, RecursiveMode::NonRecursive)
+            .map_err(|e| AppError::Filesystem(format!("Failed to watch {}: {}", dir.display(), e)))?;
+        println!("Watching: {}", dir.display());
+    }
 
-        // Watch project .claude/agents/ directory
-        let project_agents_dir = current_dir.join(".claude").join("agents");
-        if project_agents_dir.exists() {
-            watcher
-                .watch(&project_agents_dir, RecursiveMode::Recursive)
-                .map_err(|e| {
-                    AppError::Filesystem(format!("Failed to watch {}: {}", project_agents_dir.display(), e))
-                })?;
-            println!("Watching: {}", project_agents_dir.display());
-        }
+    // Record the current version of each watched file so the first change can be rolled back
+    for path in targets.files.iter().filter(|path| path.is_file()) {
+        record_version(path);
     }
 
     // Store the debouncer in Tauri managed state to prevent it from being dropped
@@ -116,6 +123,17 @@ pub fn watch_config_files(app: AppHandle) -> Result<(), AppError> {
     Ok(())
 }
 
+/// Existing parent directories of `files`, each listed once
+fn watched_dirs(files: &[PathBuf]) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    for dir in files.iter().filter_map(|file| file.parent()) {
+        if dir.is_dir() && !dirs.iter().any(|known| known == dir) {
+            dirs.push(dir.to_path_buf());
+        }
+    }
+    dirs
+}
+
 /// Handle file system events and emit to frontend
 fn handle_file_event(app: &AppHandle, event: DebouncedEvent) {
     // Determine change type from the event kind
@@ -131,6 +149,10 @@ fn handle_file_event(app: &AppHandle, event: DebouncedEvent) {
 
     // Check if this is a config file we care about
     if is_config_file(path) {
+        if change_type == "modify" {
+            record_version(path);
+        }
+
         let event_payload = ConfigChangedEvent {
             path: path.display().to_string(),
             change_type: change_type.to_string(),
@@ -144,6 +166,16 @@ fn handle_file_event(app: &AppHandle, event: DebouncedEvent) {
     }
 }
 
+/// Store the current content of a config file in the version history
+fn record_version(path: &Path) {
+    let Some(history_dir) = history::history_dir() else {
+        return;
+    };
+    if let Err(e) = history::record_version(&history_dir, path) {
+        eprintln!("Failed to record version of {}: {}", path.display(), e);
+    }
+}
+
 /// Check if the path is a configuration file we should watch
 /// Uses Path methods instead of string matching for cross-platform compatibility
 fn is_config_file(path: &Path) -> bool {
@@ -163,8 +195,8 @@ fn is_config_file(path: &Path) -> bool {
         return true;
     }
 
-    // Check for settings.json in .claude directory
-    if file_name == "settings.json" {
+    // Check for settings.json and settings.local.json in .claude directory
+    if file_name == "settings.json" || file_name == "settings.local.json" {
         if let Some(parent) = path.parent() {
             if let Some(parent_name) = parent.file_name().and_then(|n| n.to_str()) {
                 if parent_name == ".claude" {
@@ -192,6 +224,11 @@ fn is_config_file(path: &Path) -> bool {
         }
     }
 
+    // Check for .md files anywhere below .claude/commands/
+    if slash_commands::is_command_file(path) {
+        return true;
+    }
+
     false
 }
 
@@ -212,6 +249,15 @@ mod tests {
         assert!(is_config_file(&path));
     }
 
+    #[test]
+    fn test_is_config_file_local_settings() {
+        let path = PathBuf::from("/home/user/project/.claude/settings.local.json");
+        assert!(is_config_file(&path));
+
+        let outside_claude_dir = PathBuf::from("/home/user/project/settings.local.json");
+        assert!(!is_config_file(&outside_claude_dir));
+    }
+
     #[test]
     fn test_is_config_file_mcp_json() {
         let path = PathBuf::from("/home/user/project/.mcp.json");
@@ -224,6 +270,49 @@ mod tests {
         assert!(is_config_file(&path));
     }
 
+    #[test]
+    fn test_is_config_file_command_md() {
+        let path = PathBuf::from("/home/user/project/.claude/commands/frontend/component.md");
+        assert!(is_config_file(&path));
+
+        let not_markdown = PathBuf::from("/home/user/project/.claude/commands/notes.txt");
+        assert!(!is_config_file(&not_markdown));
+    }
+
+    #[test]
+    fn test_watched_dirs_are_existing_parents() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let project = temp_dir.path();
+        std::fs::create_dir_all(project.join(".claude")).unwrap();
+
+        let files = vec![
+            project.join(".mcp.json"),
+            project.join(".claude").join("settings.json"),
+            project.join(".claude").join("settings.local.json"),
+            project.join("missing").join("settings.json"),
+        ];
+        assert_eq!(watched_dirs(&files), vec![project.to_path_buf(), project.join(".claude")]);
+    }
+
+    #[test]
+    fn test_watch_targets_ignore_other_files_in_home() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let home = temp_dir.path().join("home");
+        let project = temp_dir.path().join("project");
+        std::fs::create_dir_all(home.join(".claude").join("agents")).unwrap();
+        std::fs::create_dir_all(&project).unwrap();
+
+        let targets = WatchTargets::new(Some(&home), Some(&project));
+        assert!(targets.contains(&home.join(".claude.json")));
+        assert!(targets.contains(&home.join(".claude").join("agents").join("reviewer.md")));
+        assert!(targets.contains(&project.join(".claude").join("settings.local.json")));
+        assert!(!targets.contains(&home.join(".bash_history")));
+        assert!(!targets.contains(&home.join(".claude.json.tmp")));
+        assert!(!targets.contains(&project.join("README.md")));
+        // Directories that do not exist are not watched
+        assert!(!targets.contains(&project.join(".claude").join("agents").join("a.md")));
+    }
+
     #[test]
     fn test_is_config_file_not_config() {
         let path = PathBuf::from("/home/user/some-file.txt");
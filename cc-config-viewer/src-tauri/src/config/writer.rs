@@ -0,0 +1,331 @@
+//! Configuration file writer
+//!
+//! Writes Claude Code config files without risking a half-written file:
+//! content goes to a temporary file in the same directory, is synced to
+//! disk and then renamed over the original. Before a file is replaced, a
+//! timestamped copy is kept under `<data dir>/cc-config-viewer/backups`.
+
+use crate::config::reader;
+use crate::types::app::AppError;
+use serde::Serialize;
+use serde_json::ser::{PrettyFormatter, Serializer};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Backups kept per file; older ones are removed
+pub const MAX_BACKUPS: usize = 20;
+
+/// Result of writing a config file
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WriteOutcome {
+    pub path: String,
+    /// `false` when the file already had the requested content
+    pub changed: bool,
+    /// Copy of the previous content, if one was made
+    pub backup: Option<String>,
+}
+
+/// Location of config file backups
+pub fn backup_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("cc-config-viewer").join("backups"))
+}
+
+/// Backup directory of one file, named after the file and a hash of its full path
+fn backup_subdir(backup_dir: &Path, path: &Path) -> PathBuf {
+    let hash = format!("{:x}", Sha256::digest(path.as_os_str().as_encoded_bytes()));
+    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    backup_dir.join(format!("{}-{}", name, &hash[..16]))
+}
+
+/// Resolve symlinks so the link itself is not replaced by a regular file
+fn write_target(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Copy `path` into its backup directory, removing the oldest backups beyond `MAX_BACKUPS`
+///
+/// Returns `None` when the file does not exist yet.
+pub fn backup_file(backup_dir: &Path, path: &Path) -> Result<Option<PathBuf>, AppError> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+
+    let dir = backup_subdir(backup_dir, &write_target(path));
+    std::fs::create_dir_all(&dir).map_err(AppError::from)?;
+
+    let timestamp = chrono::Utc::now().format("%Y%m%dT%H%M%S%.6fZ").to_string();
+    let extension = path.extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default();
+    // Every name has a counter so backups made within the same microsecond still sort in order
+    let mut counter = 0;
+    let mut backup = dir.join(format!("{}-{:03}{}", timestamp, counter, extension));
+    while backup.exists() {
+        counter += 1;
+        backup = dir.join(format!("{}-{:03}{}", timestamp, counter, extension));
+    }
+    std::fs::copy(path, &backup).map_err(AppError::from)?;
+
+    // Timestamps sort chronologically, so the oldest backups come first
+    let mut backups: Vec<PathBuf> = std::fs::read_dir(&dir)
+        .map_err(AppError::from)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.is_file())
+        .collect();
+    backups.sort();
+    let excess = backups.len().saturating_sub(MAX_BACKUPS);
+    for old in &backups[..excess] {
+        if let Err(e) = std::fs::remove_file(old) {
+            eprintln!("Warning: Failed to remove old backup {}: {}", old.display(), e);
+        }
+    }
+
+    Ok(Some(backup))
+}
+
+/// Replace `path` with `contents` via a synced temporary file and a rename
+///
+/// Creates missing parent directories and keeps the permissions of an existing file.
+pub fn write_atomic(path: &Path, contents: &str) -> Result<(), AppError> {
+    let target = write_target(path);
+    let dir = match target.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    std::fs::create_dir_all(&dir).map_err(AppError::from)?;
+
+    let mut temp = tempfile::Builder::new()
+        .prefix(".cc-config-viewer-")
+        .suffix(".tmp")
+        .tempfile_in(&dir)
+        .map_err(AppError::from)?;
+    temp.write_all(contents.as_bytes()).map_err(AppError::from)?;
+    if let Ok(metadata) = std::fs::metadata(&target) {
+        std::fs::set_permissions(temp.path(), metadata.permissions()).map_err(AppError::from)?;
+    }
+    temp.as_file().sync_all().map_err(AppError::from)?;
+    temp.persist(&target).map_err(|e| AppError::from(e.error))?;
+
+    // Sync the directory so the rename itself survives a crash
+    #[cfg(unix)]
+    if let Ok(dir) = std::fs::File::open(&dir) {
+        let _ = dir.sync_all();
+    }
+
+    Ok(())
+}
+
+/// Indentation unit of existing JSON, taken from its first indented line
+fn detect_indent(content: &str) -> String {
+    content
+        .lines()
+        .skip(1)
+        .map(|line| &line[..line.len() - line.trim_start_matches([' ', '\t']).len()])
+        .find(|indent| !indent.is_empty())
+        .unwrap_or("  ")
+        .to_string()
+}
+
+/// Pretty-print `value` the way `original` was formatted
+///
+/// Keeps the indentation unit, line endings and trailing newline of the
+/// original; key order follows `value`, which keeps the order it was parsed in.
+/// New files get two-space indentation and a trailing newline.
+pub fn render_json(value: &Value, original: Option<&str>) -> Result<String, AppError> {
+    let indent = original.map(detect_indent).unwrap_or_else(|| "  ".to_string());
+    let mut buffer = Vec::new();
+    let mut serializer = Serializer::with_formatter(&mut buffer, PrettyFormatter::with_indent(indent.as_bytes()));
+    value.serialize(&mut serializer)?;
+    let mut rendered = String::from_utf8(buffer).map_err(|e| AppError::Parse(e.to_string()))?;
+
+    if original.is_none_or(|o| o.ends_with('\n')) {
+        rendered.push('\n');
+    }
+    if original.is_some_and(|o| o.contains("\r\n")) {
+        rendered = rendered.replace('\n', "\r\n");
+    }
+
+    Ok(rendered)
+}
+
+/// Write a config file, backing up its previous content to `backup_dir` when given
+///
+/// The managed settings file is read-only and cannot be written.
+pub fn write_config(path: &Path, contents: &str, backup_dir: Option<&Path>) -> Result<WriteOutcome, AppError> {
+    let managed = reader::managed_settings_path();
+    if write_target(path) == write_target(&managed) {
+        return Err(AppError::Permission(format!(
+            "Managed settings are read-only: {}",
+            managed.display()
+        )));
+    }
+
+    let display = path.to_string_lossy().to_string();
+    if std::fs::read_to_string(path).is_ok_and(|existing| existing == contents) {
+        return Ok(WriteOutcome {
+            path: display,
+            changed: false,
+            backup: None,
+        });
+    }
+
+    let backup = match backup_dir {
+        Some(backup_dir) => backup_file(backup_dir, path)?,
+        None => None,
+    };
+    write_atomic(path, contents)?;
+
+    Ok(WriteOutcome {
+        path: display,
+        changed: true,
+        backup: backup.map(|b| b.to_string_lossy().to_string()),
+    })
+}
+
+/// Write a JSON config file, keeping the formatting of the current file
+///
+/// The file is re-serialized, so a current file with comments or trailing
+/// commas is refused rather than losing them.
+pub fn write_json(path: &Path, value: &Value, backup_dir: Option<&Path>) -> Result<WriteOutcome, AppError> {
+    let original = std::fs::read_to_string(path).ok();
+    if let Some(original) = &original {
+        if let Err(e) = serde_json::from_str::<Value>(original) {
+            return Err(AppError::Parse(format!(
+                "{} is not strict JSON ({}); writing it would drop its comments, so edit it by hand",
+                path.display(),
+                e
+            )));
+        }
+    }
+    let contents = render_json(value, original.as_deref())?;
+    write_config(path, &contents, backup_dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_json_keeps_formatting() {
+        let original = "{\r\n    \"model\": \"opus\",\r\n    \"env\": {\r\n        \"A\": \"1\"\r\n    }\r\n}";
+        let mut value: Value = serde_json::from_str(original).unwrap();
+        value["alwaysThinkingEnabled"] = Value::Bool(true);
+
+        let rendered = render_json(&value, Some(original)).unwrap();
+        assert_eq!(
+            rendered,
+            "{\r\n    \"model\": \"opus\",\r\n    \"env\": {\r\n        \"A\": \"1\"\r\n    },\r\n    \"alwaysThinkingEnabled\": true\r\n}"
+        );
+
+        let tabbed = render_json(&value, Some("{\n\t\"model\": \"opus\"\n}\n")).unwrap();
+        assert!(tabbed.starts_with("{\n\t\"model\""));
+        assert!(tabbed.ends_with("}\n"));
+
+        let new_file = render_json(&serde_json::json!({"b": 1, "a": 2}), None).unwrap();
+        assert_eq!(new_file, "{\n  \"b\": 1,\n  \"a\": 2\n}\n");
+    }
+
+    #[test]
+    fn test_write_atomic_replaces_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join(".claude").join("settings.json");
+
+        write_atomic(&path, "{}\n").unwrap();
+        write_atomic(&path, "{\"model\": \"opus\"}\n").unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "{\"model\": \"opus\"}\n");
+        let entries: Vec<_> = std::fs::read_dir(path.parent().unwrap()).unwrap().collect();
+        assert_eq!(entries.len(), 1, "temporary files are not left behind");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_atomic_follows_symlinks_and_keeps_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let real = temp_dir.path().join("real.json");
+        let link = temp_dir.path().join("link.json");
+        std::fs::write(&real, "{}").unwrap();
+        std::fs::set_permissions(&real, std::fs::Permissions::from_mode(0o600)).unwrap();
+        std::os::unix::fs::symlink(&real, &link).unwrap();
+
+        write_atomic(&link, "{\"a\": 1}").unwrap();
+
+        assert!(std::fs::symlink_metadata(&link).unwrap().file_type().is_symlink());
+        assert_eq!(std::fs::read_to_string(&real).unwrap(), "{\"a\": 1}");
+        assert_eq!(std::fs::metadata(&real).unwrap().permissions().mode() & 0o777, 0o600);
+    }
+
+    #[test]
+    fn test_write_json_backs_up_previous_content() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let backups = temp_dir.path().join("backups");
+        let path = temp_dir.path().join("settings.json");
+
+        let created = write_json(&path, &serde_json::json!({"model": "opus"}), Some(&backups)).unwrap();
+        assert!(created.changed);
+        assert_eq!(created.backup, None);
+
+        let updated = write_json(&path, &serde_json::json!({"model": "sonnet"}), Some(&backups)).unwrap();
+        let backup = PathBuf::from(updated.backup.unwrap());
+        assert_eq!(backup.extension().unwrap(), "json");
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), "{\n  \"model\": \"opus\"\n}\n");
+
+        let unchanged = write_json(&path, &serde_json::json!({"model": "sonnet"}), Some(&backups)).unwrap();
+        assert!(!unchanged.changed);
+        assert_eq!(unchanged.backup, None);
+    }
+
+    #[test]
+    fn test_write_json_refuses_jsonc() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("settings.json");
+        let original = "{\n  // team default\n  \"model\": \"opus\"\n}\n";
+        std::fs::write(&path, original).unwrap();
+
+        let result = write_json(&path, &serde_json::json!({"model": "sonnet"}), None);
+        assert!(matches!(result, Err(AppError::Parse(_))));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), original);
+    }
+
+    #[test]
+    fn test_backup_names_sort_oldest_first() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let backups = temp_dir.path().join("backups");
+        let path = temp_dir.path().join("settings.json");
+
+        let mut made = Vec::new();
+        for version in 0..5 {
+            std::fs::write(&path, version.to_string()).unwrap();
+            made.push(backup_file(&backups, &path).unwrap().unwrap());
+        }
+
+        let mut sorted = made.clone();
+        sorted.sort();
+        assert_eq!(sorted, made);
+    }
+
+    #[test]
+    fn test_backup_subdir_is_stable() {
+        let dir = backup_subdir(Path::new("/backups"), Path::new("/home/a/.claude/settings.json"));
+        assert_eq!(dir, Path::new("/backups/settings.json-3b002acbf78bd925"));
+    }
+
+    #[test]
+    fn test_backup_file_prunes_old_backups() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let backups = temp_dir.path().join("backups");
+        let path = temp_dir.path().join("CLAUDE.md");
+        std::fs::write(&path, "# Notes").unwrap();
+
+        for _ in 0..MAX_BACKUPS + 3 {
+            backup_file(&backups, &path).unwrap();
+        }
+
+        let dir = backup_subdir(&backups, &path.canonicalize().unwrap());
+        assert_eq!(std::fs::read_dir(dir).unwrap().count(), MAX_BACKUPS);
+        assert_eq!(backup_file(&backups, &temp_dir.path().join("missing.md")).unwrap(), None);
+    }
+}
@@ -6,11 +6,21 @@ mod config;
 mod types;
 mod utils;
 
-use commands::config::{read_config, parse_config, watch_config, get_current_dir, get_home_dir};
-use commands::source::{get_source_location, open_in_editor, copy_to_clipboard};
+use commands::config::{
+    read_config, parse_config, parse_config_with_spans, parse_config_tolerant, watch_config,
+    get_current_dir, get_home_dir, get_effective_config, trace_config_key, get_managed_policy,
+    validate_config_file, validate_project_configs, list_sub_agents, list_slash_commands,
+    list_hooks, get_memory_graph, list_mcp_servers, probe_mcp_server,
+    refresh_mcp_inventory, get_mcp_inventory, evaluate_permission,
+    expand_mcp_config, preview_config_patch, commit_config_patch,
+    list_config_versions, diff_config_versions, restore_config_version,
+};
+use commands::source::{get_source_location, get_source_locations, open_in_editor, copy_to_clipboard};
 use commands::project_commands::{
     list_projects, scan_projects, watch_projects, health_check_project, calculate_health_metrics,
-    refresh_all_project_health,
+    refresh_all_project_health, compare_projects, calculate_diff, categorize_differences,
+    calculate_summary_stats, filter_capabilities, lint_permissions, transfer_capability,
+    sync_differences,
 };
 use commands::export_commands::{
     save_export_file, get_downloads_path, validate_export_data, generate_export_filename,
@@ -54,10 +64,33 @@ pub fn run() {
             greet,
             read_config,
             parse_config,
+            parse_config_with_spans,
+            parse_config_tolerant,
             watch_config,
             get_current_dir,
             get_home_dir,
+            get_effective_config,
+            trace_config_key,
+            get_managed_policy,
+            validate_config_file,
+            validate_project_configs,
+            list_sub_agents,
+            list_slash_commands,
+            list_hooks,
+            get_memory_graph,
+            list_mcp_servers,
+            probe_mcp_server,
+            refresh_mcp_inventory,
+            get_mcp_inventory,
+            evaluate_permission,
+            expand_mcp_config,
+            preview_config_patch,
+            commit_config_patch,
+            list_config_versions,
+            diff_config_versions,
+            restore_config_version,
             get_source_location,
+            get_source_locations,
             open_in_editor,
             copy_to_clipboard,
             list_projects,
@@ -66,6 +99,14 @@ pub fn run() {
             health_check_project,
             calculate_health_metrics,
             refresh_all_project_health,
+            compare_projects,
+            calculate_diff,
+            categorize_differences,
+            calculate_summary_stats,
+            filter_capabilities,
+            lint_permissions,
+            transfer_capability,
+            sync_differences,
             save_export_file,
             get_downloads_path,
             validate_export_data,
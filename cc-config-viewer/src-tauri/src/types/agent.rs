@@ -0,0 +1,41 @@
+//! Sub-agent types
+//!
+//! Typed model of the Markdown sub-agent definitions in `~/.claude/agents`
+//! and `<project>/.claude/agents`.
+
+use crate::types::app::ConfigScope;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A sub-agent definition
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SubAgent {
+    /// Identifier from the `name` frontmatter key, or the file name when missing
+    pub name: String,
+    pub description: Option<String>,
+    /// Allowed tools; `None` means the agent inherits every tool
+    pub tools: Option<Vec<String>>,
+    /// Model alias (`sonnet`, `opus`, `haiku`) or `inherit`
+    pub model: Option<String>,
+    pub color: Option<String>,
+    /// System prompt from the Markdown body
+    pub prompt: String,
+    pub scope: ConfigScope,
+    pub path: String,
+    /// Frontmatter keys this model does not know about
+    pub extra: BTreeMap<String, String>,
+}
+
+impl SubAgent {
+    /// The definition without its location, for comparing agents across projects
+    pub fn definition(&self) -> serde_json::Value {
+        serde_json::json!({
+            "name": self.name,
+            "description": self.description,
+            "tools": self.tools,
+            "model": self.model,
+            "color": self.color,
+            "prompt": self.prompt,
+        })
+    }
+}
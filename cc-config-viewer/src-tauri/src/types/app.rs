@@ -49,8 +49,51 @@ pub struct Project {
     pub config_path: String,
 }
 
+/// Claude Code settings scope, ordered from lowest to highest precedence
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ConfigScope {
+    #[serde(rename = "user")]
+    User,
+    #[serde(rename = "project")]
+    Project,
+    #[serde(rename = "local")]
+    Local,
+    #[serde(rename = "managed")]
+    Managed,
+}
+
+impl ConfigScope {
+    /// All scopes from highest to lowest precedence
+    pub const PRECEDENCE: [ConfigScope; 4] = [
+        ConfigScope::Managed,
+        ConfigScope::Local,
+        ConfigScope::Project,
+        ConfigScope::User,
+    ];
+
+    /// Identifier used for `ConfigSource.type_` and `Capability.source`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConfigScope::User => "user",
+            ConfigScope::Project => "project",
+            ConfigScope::Local => "local",
+            ConfigScope::Managed => "managed",
+        }
+    }
+
+    /// Priority of the scope (higher wins)
+    pub fn priority(&self) -> u32 {
+        match self {
+            ConfigScope::User => 1,
+            ConfigScope::Project => 2,
+            ConfigScope::Local => 3,
+            ConfigScope::Managed => 4,
+        }
+    }
+}
+
 /// Represents the source of a configuration value
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[allow(dead_code)]
 pub struct ConfigSource {
     pub type_: String,
@@ -59,7 +102,7 @@ pub struct ConfigSource {
 }
 
 /// A configuration entry with its value and source
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[allow(dead_code)]
 pub struct ConfigEntry {
     pub key: String,
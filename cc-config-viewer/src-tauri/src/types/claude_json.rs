@@ -0,0 +1,22 @@
+//! `~/.claude.json` project entry types
+
+use crate::types::mcp::McpServerEntry;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// A project's entry in the `projects` map of `~/.claude.json`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ClaudeJsonProject {
+    /// Project path as written in the `projects` key
+    pub path: String,
+    pub allowed_tools: Vec<String>,
+    /// Servers from the entry's `mcpServers`, attributed to local scope
+    pub mcp_servers: Vec<McpServerEntry>,
+    /// `.mcp.json` servers the user approved or rejected for this project
+    pub enabled_mcpjson_servers: Vec<String>,
+    pub disabled_mcpjson_servers: Vec<String>,
+    pub has_trust_dialog_accepted: bool,
+    pub has_completed_project_onboarding: bool,
+    /// Remaining keys as written, except the prompt `history`
+    pub other: Map<String, Value>,
+}
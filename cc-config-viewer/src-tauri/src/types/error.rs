@@ -102,6 +102,17 @@ pub mod error_codes {
     pub const PR003: &str = "PR003";
     pub const PR004: &str = "PR004";
 
+    // Permission Rule Lint
+    pub const PL001: &str = "PL001";
+    pub const PL002: &str = "PL002";
+    pub const PL003: &str = "PL003";
+    pub const PL004: &str = "PL004";
+    pub const PL005: &str = "PL005";
+
+    // MCP Environment Expansion
+    pub const MC001: &str = "MC001";
+    pub const MC002: &str = "MC002";
+
     // Network Errors
     pub const NT001: &str = "NT001";
     pub const NT002: &str = "NT002";
@@ -0,0 +1,13 @@
+//! Config version history types
+
+use serde::{Deserialize, Serialize};
+
+/// A recorded version of a config file
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ConfigVersion {
+    /// SHA-256 of the content, which is also its snapshot name
+    pub hash: String,
+    pub size: u64,
+    /// RFC 3339 time the version was recorded
+    pub recorded_at: String,
+}
@@ -0,0 +1,48 @@
+//! Hook types
+//!
+//! Flattened view of the `hooks` section of settings files: one entry per
+//! command, with the event and matcher that trigger it.
+
+use crate::types::app::ConfigScope;
+use serde::{Deserialize, Serialize};
+
+/// A hook command configured in one settings file
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ConfiguredHook {
+    /// Hook event, e.g. `PreToolUse` or `Stop`
+    pub event: String,
+    /// Matcher as written; `None` when the hook applies to every tool
+    pub matcher: Option<String>,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub command: Option<String>,
+    pub timeout: Option<u64>,
+    pub scope: ConfigScope,
+    pub path: String,
+    /// JSON pointer of the hook command within its settings file
+    pub pointer: String,
+    /// Problems with the matcher, such as an invalid regex or unknown tool
+    pub matcher_issues: Vec<String>,
+}
+
+impl ConfiguredHook {
+    /// The hook without its location, for comparing hooks across projects
+    pub fn definition(&self) -> serde_json::Value {
+        serde_json::json!({
+            "event": self.event,
+            "matcher": self.matcher,
+            "type": self.type_,
+            "command": self.command,
+            "timeout": self.timeout,
+        })
+    }
+}
+
+/// Every hook that applies to a project, across all scopes
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct HookInventory {
+    /// Hooks ordered from highest to lowest precedence scope
+    pub hooks: Vec<ConfiguredHook>,
+    /// Highest precedence scope setting `disableAllHooks`, if any
+    pub disabled_by: Option<ConfigScope>,
+}
@@ -0,0 +1,237 @@
+//! MCP server types
+//!
+//! Typed model of the `mcpServers` entries in `.mcp.json` and
+//! `~/.claude.json`. Entries without a `type` are stdio servers.
+
+use crate::types::app::{AppError, ConfigScope};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// An MCP server definition, tagged by transport
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum McpServer {
+    /// Local process speaking MCP over stdin/stdout
+    #[serde(rename = "stdio")]
+    Stdio {
+        command: String,
+        #[serde(default)]
+        args: Vec<String>,
+        #[serde(default)]
+        env: BTreeMap<String, String>,
+    },
+    /// Remote server using server-sent events
+    #[serde(rename = "sse")]
+    Sse {
+        url: String,
+        #[serde(default)]
+        headers: BTreeMap<String, String>,
+    },
+    /// Remote server using streamable HTTP
+    #[serde(rename = "http")]
+    Http {
+        url: String,
+        #[serde(default)]
+        headers: BTreeMap<String, String>,
+    },
+}
+
+impl McpServer {
+    /// Parse one `mcpServers` entry, treating a missing `type` as stdio
+    pub fn from_value(value: &Value) -> Result<Self, AppError> {
+        let mut object = value
+            .as_object()
+            .cloned()
+            .ok_or_else(|| AppError::Parse("Expected MCP server to be an object".to_string()))?;
+        object
+            .entry("type")
+            .or_insert_with(|| Value::String("stdio".to_string()));
+
+        serde_json::from_value(Value::Object(object)).map_err(AppError::from)
+    }
+}
+
+/// A named MCP server and the config file defining it
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct McpServerEntry {
+    pub name: String,
+    #[serde(flatten)]
+    pub server: McpServer,
+    /// `user` for `~/.claude.json`, `project` for `.mcp.json` and `local` for
+    /// a project entry in `~/.claude.json`
+    pub scope: ConfigScope,
+    pub path: String,
+}
+
+/// A tool reported by an MCP server's `tools/list`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct McpTool {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+/// A prompt reported by an MCP server's `prompts/list`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct McpPrompt {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+/// A resource reported by an MCP server's `resources/list`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct McpResource {
+    pub uri: String,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub mime_type: Option<String>,
+}
+
+/// Outcome of starting or connecting to an MCP server and completing the handshake
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct McpProbeResult {
+    pub name: String,
+    /// `stdio`, `sse` or `http`
+    pub transport: String,
+    /// Whether `initialize` and `tools/list`, when advertised, succeeded
+    pub success: bool,
+    /// `serverInfo.name` from the `initialize` result
+    pub server_name: Option<String>,
+    pub server_version: Option<String>,
+    pub protocol_version: Option<String>,
+    /// `capabilities` from the `initialize` result
+    pub capabilities: Option<Value>,
+    /// Tools from `tools/list`; `None` when the server does not advertise tools
+    pub tools: Option<Vec<McpTool>>,
+    /// Prompts from `prompts/list`; `None` when the server does not advertise prompts
+    pub prompts: Option<Vec<McpPrompt>>,
+    /// Resources from `resources/list`; `None` when the server does not advertise resources
+    pub resources: Option<Vec<McpResource>>,
+    /// Failures of `prompts/list` and `resources/list`, which leave the probe successful
+    #[serde(default)]
+    pub list_errors: Vec<String>,
+    pub error: Option<String>,
+    /// Output the server wrote to stderr, kept only when the probe fails
+    pub stderr: Option<String>,
+    pub duration_ms: u64,
+}
+
+/// What a server offered the last time it was probed successfully
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct McpInventory {
+    pub name: String,
+    /// Definition the server was probed with; the inventory is stale once it changes
+    pub server: McpServer,
+    pub scope: ConfigScope,
+    pub server_name: Option<String>,
+    pub server_version: Option<String>,
+    pub tools: Vec<McpTool>,
+    pub prompts: Vec<McpPrompt>,
+    pub resources: Vec<McpResource>,
+    /// RFC 3339 time of the probe
+    pub updated_at: String,
+}
+
+/// How a `${VAR}` reference was resolved
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum EnvReferenceStatus {
+    /// The variable is set
+    Set,
+    /// The variable is unset and the `${VAR:-default}` default was used
+    Default,
+    /// The variable is unset and has no default
+    Unresolved,
+}
+
+/// A `${VAR}` or `${VAR:-default}` reference in `.mcp.json`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct McpEnvReference {
+    pub name: String,
+    pub default: Option<String>,
+    pub status: EnvReferenceStatus,
+    /// JSON pointer of the string containing the reference
+    pub pointer: String,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+}
+
+/// A credential written into `.mcp.json` instead of referenced as a variable
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct McpLiteralSecret {
+    pub pointer: String,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    /// Why the value looks like a secret; the value itself is not reported
+    pub reason: String,
+}
+
+/// A `.mcp.json` server after `${VAR}` expansion
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct McpServerExpansion {
+    pub name: String,
+    pub path: String,
+    /// Server entry with every resolvable reference replaced
+    pub expanded: Value,
+    pub references: Vec<McpEnvReference>,
+    pub literal_secrets: Vec<McpLiteralSecret>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_from_value_defaults_to_stdio() {
+        let server = McpServer::from_value(&json!({"command": "npx", "args": ["-y", "server"]})).unwrap();
+        assert_eq!(
+            server,
+            McpServer::Stdio {
+                command: "npx".to_string(),
+                args: vec!["-y".to_string(), "server".to_string()],
+                env: BTreeMap::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_from_value_remote_transports() {
+        let sse = McpServer::from_value(&json!({"type": "sse", "url": "https://x/sse"})).unwrap();
+        assert!(matches!(sse, McpServer::Sse { .. }));
+
+        let http = McpServer::from_value(&json!({
+            "type": "http",
+            "url": "https://x/mcp",
+            "headers": {"Authorization": "Bearer t"}
+        }))
+        .unwrap();
+        match http {
+            McpServer::Http { headers, .. } => assert_eq!(headers["Authorization"], "Bearer t"),
+            other => panic!("expected http server, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_from_value_rejects_invalid_entries() {
+        assert!(McpServer::from_value(&json!({"type": "http"})).is_err());
+        assert!(McpServer::from_value(&json!({"type": "websocket", "url": "ws://x"})).is_err());
+        assert!(McpServer::from_value(&json!("npx server")).is_err());
+    }
+
+    #[test]
+    fn test_entry_serializes_flat() {
+        let entry = McpServerEntry {
+            name: "github".to_string(),
+            server: McpServer::from_value(&json!({"command": "gh-mcp"})).unwrap(),
+            scope: ConfigScope::Project,
+            path: "/p/.mcp.json".to_string(),
+        };
+
+        let value = serde_json::to_value(&entry).unwrap();
+        assert_eq!(value["name"], "github");
+        assert_eq!(value["type"], "stdio");
+        assert_eq!(value["command"], "gh-mcp");
+        assert_eq!(value["scope"], "project");
+    }
+}
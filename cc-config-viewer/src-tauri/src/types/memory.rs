@@ -0,0 +1,74 @@
+//! Memory file types
+//!
+//! `CLAUDE.md` and `CLAUDE.local.md` files that apply to a project and the
+//! `@path` imports between them.
+
+use serde::{Deserialize, Serialize};
+
+/// Why a memory file is loaded
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum MemorySource {
+    /// `~/.claude/CLAUDE.md`
+    #[serde(rename = "user")]
+    User,
+    /// `CLAUDE.md` or `CLAUDE.local.md` in a directory between the repo root and the project
+    #[serde(rename = "ancestor")]
+    Ancestor,
+    /// `CLAUDE.md` or `.claude/CLAUDE.md` in the project directory
+    #[serde(rename = "project")]
+    Project,
+    /// `CLAUDE.local.md` in the project directory
+    #[serde(rename = "local")]
+    Local,
+    /// Memory file in a subdirectory, loaded when Claude reads files there
+    #[serde(rename = "nested")]
+    Nested,
+    /// Only reached through an `@path` import
+    #[serde(rename = "import")]
+    Import,
+}
+
+/// Outcome of following an `@path` import
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ImportStatus {
+    #[serde(rename = "resolved")]
+    Resolved,
+    #[serde(rename = "missing")]
+    Missing,
+    /// The target is already being imported further up the chain
+    #[serde(rename = "cycle")]
+    Cycle,
+    /// The target is beyond the maximum import depth and is not loaded
+    #[serde(rename = "too-deep")]
+    TooDeep,
+}
+
+/// An `@path` import found in a memory file
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct MemoryImport {
+    /// Path as written after `@`
+    pub raw: String,
+    pub line: u32,
+    /// Resolved target path
+    pub target: String,
+    pub status: ImportStatus,
+}
+
+/// A memory file and the imports it contains
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct MemoryFile {
+    pub path: String,
+    pub source: MemorySource,
+    pub size: u64,
+    /// Number of import hops from the nearest discovered memory file
+    pub depth: u32,
+    pub imports: Vec<MemoryImport>,
+}
+
+/// Every memory file that applies to a project, with the import graph as edges
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct MemoryGraph {
+    /// Files in the order Claude Code loads them; imported files follow their importer
+    pub files: Vec<MemoryFile>,
+    pub total_size: u64,
+}
@@ -1,3 +1,13 @@
+pub mod agent;
 pub mod app;
+pub mod claude_json;
 pub mod export;
 pub mod error;
+pub mod history;
+pub mod hook;
+pub mod mcp;
+pub mod memory;
+pub mod patch;
+pub mod permission;
+pub mod settings;
+pub mod slash_command;
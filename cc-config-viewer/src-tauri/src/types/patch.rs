@@ -0,0 +1,129 @@
+//! Config editing types
+//!
+//! JSON Patch operations (RFC 6902), the preview returned before a patch is
+//! written to a config file, capability transfers between scopes and syncs
+//! of compared capabilities between projects.
+
+use crate::config::validation::{Diagnostic, FileValidation};
+use crate::config::writer::WriteOutcome;
+use crate::types::app::{Capability, ConfigScope};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// One RFC 6902 operation; `path` and `from` are JSON pointers
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOperation {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+    Move { from: String, path: String },
+    Copy { from: String, path: String },
+    Test { path: String, value: Value },
+}
+
+/// Config file that can be edited
+///
+/// Managed settings are read-only and have no target.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConfigTarget {
+    /// `~/.claude/settings.json`
+    UserSettings,
+    /// `<project>/.claude/settings.json`
+    ProjectSettings,
+    /// `<project>/.claude/settings.local.json`
+    LocalSettings,
+    /// `<project>/.mcp.json`
+    ProjectMcp,
+    /// `~/.claude.json`
+    ClaudeJson,
+}
+
+/// Result of applying a patch without writing it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchPreview {
+    pub path: String,
+    /// Hash of the file the patch was applied to; pass it back to commit
+    pub base_hash: String,
+    /// Unified diff from the current to the patched file
+    pub diff: String,
+    pub changed: bool,
+    /// Validation of the patched file
+    pub validation: FileValidation,
+    /// Errors in the patched file that the current file does not have
+    pub new_errors: Vec<Diagnostic>,
+}
+
+/// Whether a transferred capability is removed from its source scope
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TransferMode {
+    Move,
+    Copy,
+}
+
+/// A capability to move or copy to another scope
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TransferRequest {
+    /// Capability as extracted; its `source` is the scope it is taken from
+    pub capability: Capability,
+    /// A single entry of a list capability, such as one `permissions.allow` rule
+    pub item: Option<Value>,
+    pub to: ConfigScope,
+    pub mode: TransferMode,
+}
+
+/// Result of moving or copying a capability to another scope
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CapabilityTransfer {
+    pub key: String,
+    pub from: ConfigScope,
+    pub to: ConfigScope,
+    pub mode: TransferMode,
+    /// Changes to each affected file, target first
+    pub files: Vec<PatchPreview>,
+    /// Base hash of each file by path; pass them back to commit this preview
+    pub base_hashes: BTreeMap<String, String>,
+    /// Files written; empty for a preview
+    pub written: Vec<WriteOutcome>,
+}
+
+/// Which project of a comparison is brought in line with the other
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum SyncDirection {
+    /// Write the left project's capabilities into the right project
+    LeftToRight,
+    /// Write the right project's capabilities into the left project
+    RightToLeft,
+}
+
+/// A selected difference that was not applied
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedDifference {
+    pub capability_id: String,
+    pub reason: String,
+}
+
+/// Result of syncing compared capabilities into a project
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncResult {
+    pub direction: SyncDirection,
+    /// Project whose config files are changed
+    pub target_path: String,
+    /// Capability ids whose differences are applied
+    pub applied: Vec<String>,
+    pub skipped: Vec<SkippedDifference>,
+    /// Changes to each affected file of the target project
+    pub files: Vec<PatchPreview>,
+    /// Base hash of each file by path; pass them back to commit this preview
+    pub base_hashes: BTreeMap<String, String>,
+    /// Files written; empty for a preview
+    pub written: Vec<WriteOutcome>,
+}
@@ -0,0 +1,60 @@
+//! Permission evaluation types
+//!
+//! Result of checking a proposed tool call against the `permissions` rules
+//! and `defaultMode` of every settings scope.
+
+use crate::types::app::ConfigScope;
+use serde::{Deserialize, Serialize};
+
+/// What Claude Code does with a tool call
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum PermissionBehavior {
+    Allow,
+    Ask,
+    Deny,
+}
+
+impl PermissionBehavior {
+    /// Name of the `permissions` list holding rules with this behavior
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PermissionBehavior::Allow => "allow",
+            PermissionBehavior::Ask => "ask",
+            PermissionBehavior::Deny => "deny",
+        }
+    }
+}
+
+/// A permission rule that matches the evaluated tool call
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PermissionRuleMatch {
+    /// Rule as written, e.g. `Bash(npm run test:*)`
+    pub rule: String,
+    pub behavior: PermissionBehavior,
+    pub scope: ConfigScope,
+    pub path: String,
+    /// JSON pointer of the rule within its settings file
+    pub pointer: String,
+    /// Part of a compound Bash command the rule matched
+    pub subcommand: Option<String>,
+}
+
+/// Outcome of evaluating a tool call against a project's permissions
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PermissionEvaluation {
+    /// Tool call as given, e.g. `Bash(npm run test)`
+    pub tool_call: String,
+    pub tool: String,
+    pub decision: PermissionBehavior,
+    /// Rule that decided; `None` when the permission mode decided
+    pub decided_by: Option<PermissionRuleMatch>,
+    /// Effective `defaultMode`, `default` when no scope sets one
+    pub mode: String,
+    /// Scope that set the effective mode
+    pub mode_scope: Option<ConfigScope>,
+    /// Human-readable explanation of the decision
+    pub reason: String,
+    /// Every matching rule, deny rules first, then ask and allow
+    pub matches: Vec<PermissionRuleMatch>,
+}
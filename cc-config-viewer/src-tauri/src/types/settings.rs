@@ -0,0 +1,368 @@
+//! Claude Code settings types
+//!
+//! Typed model of `settings.json`, `settings.local.json` and the managed
+//! policy file. Unknown keys are kept in `extra` and maps keep their file
+//! order so files round-trip.
+
+use crate::types::app::AppError;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Contents of a Claude Code settings file
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeSettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key_helper: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cleanup_period_days: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub env: Option<IndexMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_co_authored_by: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<PermissionSettings>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hooks: Option<IndexMap<String, Vec<HookMatcher>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disable_all_hooks: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_line: Option<StatusLine>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_style: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub force_login_method: Option<String>,
+    #[serde(rename = "forceLoginOrgUUID", skip_serializing_if = "Option::is_none")]
+    pub force_login_org_uuid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enable_all_project_mcp_servers: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled_mcpjson_servers: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disabled_mcpjson_servers: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aws_auth_refresh: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aws_credential_export: Option<String>,
+    /// Legacy top-level tool allow list
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allowed_tools: Option<Vec<String>>,
+    /// Legacy top-level tool deny list
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disallowed_tools: Option<Vec<String>>,
+    /// Keys this model does not know about
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// `permissions` section of a settings file
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionSettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ask: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deny: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub additional_directories: Option<Vec<String>>,
+    /// `default`, `acceptEdits`, `plan`, `bypassPermissions` or `dontAsk`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disable_bypass_permissions_mode: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Hook matcher with the commands to run for matching tools
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct HookMatcher {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matcher: Option<String>,
+    pub hooks: Vec<HookCommand>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// A single hook command
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct HookCommand {
+    #[serde(rename = "type")]
+    pub type_: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Custom status line configuration
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct StatusLine {
+    #[serde(rename = "type")]
+    pub type_: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub padding: Option<i64>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Sections whose children are reported as individual settings
+const NESTED_SECTIONS: [&str; 3] = ["permissions", "env", "hooks"];
+
+/// Sort and deduplicate a list whose order does not matter
+fn normalize_set(list: &mut Option<Vec<String>>) {
+    if let Some(items) = list {
+        items.sort();
+        items.dedup();
+    }
+}
+
+impl ClaudeSettings {
+    /// Build settings from an already parsed JSON value
+    pub fn from_value(value: Value) -> Result<Self, AppError> {
+        serde_json::from_value(value).map_err(AppError::from)
+    }
+
+    /// Build settings from a parsed value, dropping only the entries that do not fit the model
+    ///
+    /// Entries are split as in `entries`, so a mistyped `permissions.allow`
+    /// keeps the rest of `permissions`. Dropped entries are returned as
+    /// dotted keys with their values.
+    pub fn from_value_partial(value: Value) -> (Self, Vec<(String, Value)>) {
+        if let Ok(settings) = Self::from_value(value.clone()) {
+            return (settings, Vec::new());
+        }
+
+        let object = match value {
+            Value::Object(object) => object,
+            value => return (Self::default(), vec![(String::new(), value)]),
+        };
+
+        let mut kept = Map::new();
+        let mut dropped = Vec::new();
+        for (key, value) in object {
+            match value {
+                Value::Object(children) if NESTED_SECTIONS.contains(&key.as_str()) => {
+                    let mut kept_children = Map::new();
+                    for (child_key, child_value) in children {
+                        let entry = format!("{}.{}", key, child_key);
+                        if Self::from_entry(&entry, child_value.clone()).is_ok() {
+                            kept_children.insert(child_key, child_value);
+                        } else {
+                            dropped.push((entry, child_value));
+                        }
+                    }
+                    kept.insert(key, Value::Object(kept_children));
+                }
+                value if Self::from_entry(&key, value.clone()).is_ok() => {
+                    kept.insert(key, value);
+                }
+                value => dropped.push((key, value)),
+            }
+        }
+
+        (Self::from_value(Value::Object(kept)).unwrap_or_default(), dropped)
+    }
+
+    /// Convert back into a JSON value
+    pub fn to_value(&self) -> Value {
+        serde_json::to_value(self).unwrap_or_else(|_| Value::Object(Map::new()))
+    }
+
+    /// Every setting as a dotted key and value
+    ///
+    /// `permissions`, `env` and `hooks` are split into one entry per child
+    /// (`permissions.allow`, `env.NODE_ENV`, `hooks.PreToolUse`); every other
+    /// key, including unknown ones, is reported as-is.
+    pub fn entries(&self) -> Vec<(String, Value)> {
+        let mut entries = Vec::new();
+
+        let object = match self.to_value() {
+            Value::Object(object) => object,
+            _ => return entries,
+        };
+
+        for (key, value) in object {
+            match value {
+                Value::Object(children) if NESTED_SECTIONS.contains(&key.as_str()) => {
+                    for (child_key, child_value) in children {
+                        entries.push((format!("{}.{}", key, child_key), child_value));
+                    }
+                }
+                value => entries.push((key, value)),
+            }
+        }
+
+        entries
+    }
+
+    /// Settings holding a single dotted key as returned by `entries`
+    pub fn from_entry(key: &str, value: Value) -> Result<Self, AppError> {
+        let mut object = Map::new();
+        match key.split_once('.') {
+            Some((section, child)) if NESTED_SECTIONS.contains(&section) => {
+                let mut children = Map::new();
+                children.insert(child.to_string(), value);
+                object.insert(section.to_string(), Value::Object(children));
+            }
+            _ => {
+                object.insert(key.to_string(), value);
+            }
+        }
+        Self::from_value(Value::Object(object))
+    }
+
+    /// Copy with rule and server lists sorted, since their order has no effect
+    fn normalized(mut self) -> Self {
+        if let Some(permissions) = self.permissions.as_mut() {
+            normalize_set(&mut permissions.allow);
+            normalize_set(&mut permissions.ask);
+            normalize_set(&mut permissions.deny);
+            normalize_set(&mut permissions.additional_directories);
+        }
+        normalize_set(&mut self.enabled_mcpjson_servers);
+        normalize_set(&mut self.disabled_mcpjson_servers);
+        normalize_set(&mut self.allowed_tools);
+        normalize_set(&mut self.disallowed_tools);
+        self
+    }
+
+    /// Whether two values of the setting `key` configure the same thing
+    ///
+    /// Permission rules and server lists are compared as sets; values that do
+    /// not fit the model are compared as plain JSON.
+    pub fn entry_eq(key: &str, left: &Value, right: &Value) -> bool {
+        match (Self::from_entry(key, left.clone()), Self::from_entry(key, right.clone())) {
+            (Ok(left), Ok(right)) => left.normalized() == right.normalized(),
+            _ => left == right,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn settings(value: &Value) -> ClaudeSettings {
+        serde_json::from_value(value.clone()).unwrap()
+    }
+
+    #[test]
+    fn test_parse_full_settings() {
+        let value = json!({
+            "model": "claude-sonnet-4",
+            "env": {"NODE_ENV": "test"},
+            "permissions": {
+                "allow": ["Bash(npm run test:*)"],
+                "deny": ["Read(./.env)"],
+                "ask": ["WebFetch"],
+                "defaultMode": "acceptEdits",
+                "additionalDirectories": ["../docs"]
+            },
+            "hooks": {
+                "PreToolUse": [
+                    {"matcher": "Bash", "hooks": [{"type": "command", "command": "echo hi", "timeout": 30}]}
+                ]
+            },
+            "statusLine": {"type": "command", "command": "~/.claude/statusline.sh"},
+            "enabledMcpjsonServers": ["github"]
+        });
+
+        let settings = settings(&value);
+        let permissions = settings.permissions.as_ref().unwrap();
+
+        assert_eq!(settings.model.as_deref(), Some("claude-sonnet-4"));
+        assert_eq!(permissions.default_mode.as_deref(), Some("acceptEdits"));
+        assert_eq!(permissions.ask.as_ref().unwrap(), &vec!["WebFetch".to_string()]);
+        assert_eq!(settings.hooks.as_ref().unwrap()["PreToolUse"][0].hooks[0].timeout, Some(30));
+        assert_eq!(settings.status_line.as_ref().unwrap().type_, "command");
+        assert_eq!(settings.enabled_mcpjson_servers.as_ref().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_unknown_keys_round_trip() {
+        let value = json!({
+            "model": "opus",
+            "spinnerTipsEnabled": false,
+            "permissions": {"allow": ["Read"], "futureOption": true},
+            "hooks": {"Stop": [{"hooks": [{"type": "command", "command": "say done", "shell": "zsh"}]}]}
+        });
+
+        let settings = settings(&value);
+        assert_eq!(settings.extra["spinnerTipsEnabled"], json!(false));
+        assert_eq!(settings.to_value(), value);
+    }
+
+    #[test]
+    fn test_maps_keep_file_order() {
+        let content = r#"{"env":{"ZED":"1","ALPHA":"2"},"hooks":{"Stop":[],"PreToolUse":[]}}"#;
+        let settings: ClaudeSettings = serde_json::from_str(content).unwrap();
+        assert_eq!(serde_json::to_string(&settings).unwrap(), content);
+        assert_eq!(settings.to_value().to_string(), content);
+    }
+
+    #[test]
+    fn test_from_value_rejects_wrong_types() {
+        let result = ClaudeSettings::from_value(json!({"permissions": {"allow": "Bash"}}));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_from_value_partial_drops_only_mistyped_entries() {
+        let (settings, dropped) = ClaudeSettings::from_value_partial(json!({
+            "model": 5,
+            "permissions": {"allow": "Bash", "deny": ["Read(./.env)"]},
+            "env": {"A": "1", "B": 2},
+            "spinnerTipsEnabled": false
+        }));
+
+        assert_eq!(settings.model, None);
+        assert_eq!(settings.permissions.unwrap().deny.unwrap(), vec!["Read(./.env)".to_string()]);
+        assert_eq!(settings.env.unwrap().len(), 1);
+        assert_eq!(settings.extra["spinnerTipsEnabled"], json!(false));
+        let keys: Vec<&str> = dropped.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, vec!["model", "permissions.allow", "env.B"]);
+    }
+
+    #[test]
+    fn test_entry_eq_ignores_rule_order() {
+        assert!(ClaudeSettings::entry_eq(
+            "permissions.allow",
+            &json!(["Read", "Bash(ls)"]),
+            &json!(["Bash(ls)", "Read", "Read"])
+        ));
+        assert!(!ClaudeSettings::entry_eq("permissions.allow", &json!(["Read"]), &json!(["Edit"])));
+        assert!(ClaudeSettings::entry_eq("enabledMcpjsonServers", &json!(["a", "b"]), &json!(["b", "a"])));
+        assert!(!ClaudeSettings::entry_eq("env.A", &json!("1"), &json!("2")));
+        // Order still matters where the model does not say otherwise
+        assert!(!ClaudeSettings::entry_eq("companyAnnouncements", &json!(["a", "b"]), &json!(["b", "a"])));
+    }
+
+    #[test]
+    fn test_entries_split_nested_sections() {
+        let settings = settings(&json!({
+            "model": "opus",
+            "env": {"A": "1"},
+            "permissions": {"allow": ["Read"], "defaultMode": "plan"},
+            "customKey": 1
+        }));
+
+        let keys: Vec<String> = settings.entries().into_iter().map(|(k, _)| k).collect();
+        assert!(keys.contains(&"model".to_string()));
+        assert!(keys.contains(&"env.A".to_string()));
+        assert!(keys.contains(&"permissions.allow".to_string()));
+        assert!(keys.contains(&"permissions.defaultMode".to_string()));
+        assert!(keys.contains(&"customKey".to_string()));
+        assert!(!keys.contains(&"permissions".to_string()));
+    }
+}
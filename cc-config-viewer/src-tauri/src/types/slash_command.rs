@@ -0,0 +1,51 @@
+//! Slash command types
+//!
+//! Typed model of the custom slash commands in `~/.claude/commands` and
+//! `<project>/.claude/commands`.
+
+use crate::types::app::ConfigScope;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A custom slash command
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SlashCommand {
+    /// Command name from the file name (`review` for `review.md`)
+    pub name: String,
+    /// Subdirectories below `commands`, joined with `:` (`frontend:forms`)
+    pub namespace: Option<String>,
+    pub description: Option<String>,
+    /// Tools from `allowed-tools`; `None` means the conversation's tools apply
+    pub allowed_tools: Option<Vec<String>>,
+    pub argument_hint: Option<String>,
+    pub model: Option<String>,
+    /// Prompt from the Markdown body
+    pub prompt: String,
+    pub scope: ConfigScope,
+    pub path: String,
+    /// Frontmatter keys this model does not know about
+    pub extra: BTreeMap<String, String>,
+}
+
+impl SlashCommand {
+    /// Name including its namespace (`frontend:forms:validate`)
+    pub fn qualified_name(&self) -> String {
+        match &self.namespace {
+            Some(namespace) => format!("{}:{}", namespace, self.name),
+            None => self.name.clone(),
+        }
+    }
+
+    /// The definition without its location, for comparing commands across projects
+    pub fn definition(&self) -> serde_json::Value {
+        serde_json::json!({
+            "name": self.name,
+            "namespace": self.namespace,
+            "description": self.description,
+            "allowedTools": self.allowed_tools,
+            "argumentHint": self.argument_hint,
+            "model": self.model,
+            "prompt": self.prompt,
+        })
+    }
+}
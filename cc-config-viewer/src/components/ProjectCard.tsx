@@ -81,6 +81,11 @@ export function ProjectCard({ project, onClick, className }: ProjectCardProps) {
               Local
             </Badge>
           )}
+          {project.configSources.claudeJson && (
+            <Badge variant="secondary" className="text-xs">
+              ~/.claude.json
+            </Badge>
+          )}
         </div>
 
         {/* Sub-agents Information */}
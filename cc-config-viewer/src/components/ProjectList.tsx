@@ -251,6 +251,11 @@ function ProjectCard({ project, onClick, formatLastModified }: ProjectCardProps)
               Local
             </Badge>
           )}
+          {project.configSources.claudeJson && (
+            <Badge variant="secondary" className="text-xs">
+              ~/.claude.json
+            </Badge>
+          )}
         </div>
       </CardContent>
     </Card>
@@ -20,13 +20,7 @@ export function ProjectStats({ projects }: ProjectStatsProps) {
   const projectsWithMcpServers = projects.filter((p) => p.mcpServers && p.mcpServers.length > 0).length
 
   const totalConfigFiles = projects.reduce((sum, p) => sum + p.configFileCount, 0)
-  const totalMcpServers = projects.reduce((sum, p) => {
-    if (!p.mcpServers) return sum
-    return sum + p.mcpServers.reduce((serverSum, serverStr) => {
-      const match = serverStr.match(/(\d+)/)
-      return serverSum + (match ? parseInt(match[1], 10) : 0)
-    }, 0)
-  }, 0)
+  const totalMcpServers = projects.reduce((sum, p) => sum + (p.mcpServers?.length ?? 0), 0)
 
   const stats = [
     {
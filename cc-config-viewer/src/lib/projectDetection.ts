@@ -31,16 +31,9 @@ function getProjectNameFromPath(path: string): string {
  * Convert Rust DiscoveredProject to frontend Project format
  */
 function convertToProject(rustProject: RustDiscoveredProject): Project {
-  // Extract server and agent counts from arrays
-  const mcpCount = rustProject.mcp_servers?.reduce((count, serverStr) => {
-    const match = serverStr.match(/(\d+)/)
-    return count + (match ? parseInt(match[1], 10) : 0)
-  }, 0) || 0
-
-  const agentCount = rustProject.sub_agents?.reduce((count, agentStr) => {
-    const match = agentStr.match(/(\d+)/)
-    return count + (match ? parseInt(match[1], 10) : 0)
-  }, 0) || 0
+  // mcp_servers and sub_agents hold one entry per server or agent
+  const mcpCount = rustProject.mcp_servers?.length || 0
+  const agentCount = rustProject.sub_agents?.length || 0
 
   return {
     id: rustProject.id,
@@ -161,8 +154,13 @@ export async function getDiscoveredProjects(): Promise<DiscoveredProject[]> {
       path: rp.path,
       configFileCount: rp.config_file_count,
       lastModified: new Date(rp.last_modified * 1000),
-      configSources: rp.config_sources,
-      mcpServers: rp.mcp_servers,
+      configSources: {
+        user: rp.config_sources.user,
+        project: rp.config_sources.project,
+        local: rp.config_sources.local,
+        claudeJson: rp.config_sources.claude_json,
+      },
+      mcpServers: rp.mcp_servers?.map((server) => server.name),
       subAgents: rp.sub_agents
     }))
   } catch (error) {
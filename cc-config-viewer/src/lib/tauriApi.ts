@@ -81,6 +81,29 @@ export async function watchConfig(path: string, callback: (path: string) => void
 }
 
 // Project discovery commands
+export interface McpServerEntry {
+  name: string
+  type: 'stdio' | 'sse' | 'http'
+  command?: string
+  args?: string[]
+  env?: Record<string, string>
+  url?: string
+  headers?: Record<string, string>
+  scope: 'user' | 'project' | 'local'
+  path: string
+}
+
+export interface ClaudeJsonProject {
+  path: string
+  allowed_tools: string[]
+  mcp_servers: McpServerEntry[]
+  enabled_mcpjson_servers: string[]
+  disabled_mcpjson_servers: string[]
+  has_trust_dialog_accepted: boolean
+  has_completed_project_onboarding: boolean
+  other: Record<string, unknown>
+}
+
 export interface DiscoveredProject {
   id: string
   name: string
@@ -91,9 +114,12 @@ export interface DiscoveredProject {
     user: boolean
     project: boolean
     local: boolean
+    claude_json: boolean
   }
-  mcp_servers?: string[]
+  mcp_servers?: McpServerEntry[]
   sub_agents?: string[]
+  slash_commands?: string[]
+  claude_json?: ClaudeJsonProject
 }
 
 export async function listProjects(): Promise<DiscoveredProject[]> {
@@ -36,6 +36,7 @@ export interface DiscoveredProject {
     user: boolean
     project: boolean
     local: boolean
+    claudeJson?: boolean
   }
   mcpServers?: string[]
   subAgents?: string[]
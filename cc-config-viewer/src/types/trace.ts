@@ -12,6 +12,9 @@ export interface SourceLocation {
   file_path: string
   line_number?: number
   column_number?: number
+  end_line_number?: number
+  end_column_number?: number
+  json_pointer?: string
   context?: string
 }
 